@@ -14,7 +14,7 @@ use syn::token::{Brace, Colon, Comma, Eq, For, Impl, Paren};
 use syn::{braced, bracketed, parenthesized, parse_quote};
 use syn::{
     Attribute, ConstParam, Data, DeriveInput, Expr, Fields, FieldsNamed, FieldsUnnamed,
-    GenericParam, Generics, Ident, Index, Lifetime, LifetimeDef, Lit, Member, Token, Type,
+    GenericParam, Generics, Ident, Index, Lifetime, LifetimeDef, Lit, LitStr, Member, Token, Type,
     TypeParam, TypePath, TypeTraitObject,
 };
 
@@ -206,6 +206,8 @@ mod kw {
     custom_keyword!(noauto);
     custom_keyword!(children);
     custom_keyword!(column);
+    custom_keyword!(name);
+    custom_keyword!(access);
 }
 
 #[derive(Debug)]
@@ -217,6 +219,7 @@ pub struct WidgetAttrArgs {
     pub halign: Option<Ident>,
     pub valign: Option<Ident>,
     pub handler: Option<Ident>,
+    pub name: Option<LitStr>,
 }
 
 #[derive(Debug)]
@@ -285,6 +288,7 @@ impl Parse for WidgetAttrArgs {
             halign: None,
             valign: None,
             handler: None,
+            name: None,
         };
         if input.is_empty() {
             return Ok(args);
@@ -327,6 +331,10 @@ impl Parse for WidgetAttrArgs {
                 let _: kw::handler = content.parse()?;
                 let _: Eq = content.parse()?;
                 args.handler = Some(content.parse()?);
+            } else if args.name.is_none() && lookahead.peek(kw::name) {
+                let _: kw::name = content.parse()?;
+                let _: Eq = content.parse()?;
+                args.name = Some(content.parse()?);
             } else {
                 return Err(lookahead.error());
             }
@@ -350,6 +358,7 @@ impl ToTokens for WidgetAttrArgs {
             || self.halign.is_some()
             || self.valign.is_some()
             || self.handler.is_some()
+            || self.name.is_some()
         {
             let comma = TokenTree::from(Punct::new(',', Spacing::Alone));
             let mut args = TokenStream::new();
@@ -388,10 +397,16 @@ impl ToTokens for WidgetAttrArgs {
             }
             if let Some(ref ident) = self.handler {
                 if !args.is_empty() {
-                    args.append(comma);
+                    args.append(comma.clone());
                 }
                 args.append_all(quote! { handler = #ident });
             }
+            if let Some(ref lit) = self.name {
+                if !args.is_empty() {
+                    args.append(comma);
+                }
+                args.append_all(quote! { name = #lit });
+            }
             tokens.append_all(quote! { ( #args ) });
         }
     }
@@ -420,6 +435,7 @@ impl ToTokens for GridPos {
 pub struct WidgetArgs {
     pub config: Option<WidgetConfig>,
     pub children: bool,
+    pub access: bool,
 }
 
 impl Default for WidgetArgs {
@@ -427,6 +443,7 @@ impl Default for WidgetArgs {
         WidgetArgs {
             config: Some(WidgetConfig::default()),
             children: true,
+            access: true,
         }
     }
 }
@@ -454,6 +471,8 @@ impl Parse for WidgetArgs {
         let mut have_config = false;
         let mut children = true;
         let mut have_children = false;
+        let mut access = true;
+        let mut have_access = false;
 
         if !input.is_empty() {
             let content;
@@ -467,6 +486,12 @@ impl Parse for WidgetArgs {
                     let _: Eq = content.parse()?;
                     let _: kw::noauto = content.parse()?;
                     children = false;
+                } else if lookahead.peek(kw::access) && !have_access {
+                    have_access = true;
+                    let _: kw::access = content.parse()?;
+                    let _: Eq = content.parse()?;
+                    let _: kw::noauto = content.parse()?;
+                    access = false;
                 } else if lookahead.peek(kw::config) && !have_config {
                     have_config = true;
                     let _: kw::config = content.parse()?;
@@ -535,7 +560,11 @@ impl Parse for WidgetArgs {
             config = Some(WidgetConfig::default());
         }
 
-        Ok(WidgetArgs { config, children })
+        Ok(WidgetArgs {
+            config,
+            children,
+            access,
+        })
     }
 }
 
@@ -131,12 +131,29 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
 
         let mut get_rules = quote! {};
         let mut get_mut_rules = quote! {};
+        let mut name_rules = quote! {};
         for (i, child) in args.children.iter().enumerate() {
             let ident = &child.ident;
             get_rules.append_all(quote! { #i => Some(&self.#ident), });
             get_mut_rules.append_all(quote! { #i => Some(&mut self.#ident), });
+            if let Some(ref name) = child.args.name {
+                name_rules.append_all(quote! { #i => Some(#name), });
+            }
         }
 
+        let child_name = if name_rules.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                fn child_name(&self, _index: usize) -> Option<&'static str> {
+                    match _index {
+                        #name_rules
+                        _ => None
+                    }
+                }
+            }
+        };
+
         toks.append_all(quote! {
             impl #impl_generics kas::WidgetChildren
                 for #name #ty_generics #where_clause
@@ -159,6 +176,7 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         _ => None
                     }
                 }
+                #child_name
             }
         });
     }
@@ -185,6 +203,15 @@ pub fn derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         });
     }
 
+    if args.widget.access {
+        toks.append_all(quote! {
+            impl #impl_generics kas::Accessible
+                    for #name #ty_generics #where_clause
+            {
+            }
+        });
+    }
+
     if let Some(ref layout) = args.layout {
         match layout::data_type(&args.children, layout) {
             Ok(dt) => toks.append_all(quote! {
@@ -0,0 +1,395 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Drawing API for `kas_soft`
+
+use ab_glyph::{Font, FontRef};
+use std::any::Any;
+use std::f32::consts::FRAC_PI_2;
+
+use super::raster::{Shape, Target};
+use super::{DrawPipe, DrawWindow, PassData, Rgb};
+use crate::Error;
+use kas::conv::{Cast, Conv};
+use kas::draw::{Colour, Draw, DrawRounded, DrawShaded, DrawShared, Pass};
+use kas::geom::{Coord, Quad, Rect, Size, Vec2, Vec3};
+
+/// Convert a linear colour component to 8-bit sRGB
+fn linear_to_srgb(x: f32) -> u8 {
+    let x = x.max(0.0).min(1.0);
+    let s = if x <= 0.0031308 {
+        x * 12.92
+    } else {
+        1.055 * x.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0 + 0.5) as u8
+}
+
+impl Default for DrawPipe {
+    fn default() -> Self {
+        DrawPipe::new()
+    }
+}
+
+impl DrawPipe {
+    /// Construct
+    pub fn new() -> Self {
+        // Light dir: `(a, b)` where `0 ≤ a < pi/2` is the angle to the screen
+        // normal (i.e. `a = 0` is straight at the screen) and `b` is the bearing
+        // (from UP, clockwise), both in radians.
+        let dir: (f32, f32) = (0.3, 0.4);
+        assert!(dir.0 >= 0.0);
+        assert!(dir.0 < FRAC_PI_2);
+        let a = (dir.0.sin(), dir.0.cos());
+        // We normalise intensity:
+        let f = a.0 / a.1;
+        let light_norm = Vec3(dir.1.sin() * f, -dir.1.cos() * f, 1.0);
+
+        DrawPipe { light_norm }
+    }
+
+    /// Construct per-window state
+    ///
+    /// This fails if any loaded font cannot be parsed.
+    pub fn new_window(&self, size: Size) -> Result<DrawWindow, Error> {
+        // TODO: use extra caching so we don't load font for each window
+        let font_data = kas::text::fonts::fonts().font_data();
+        let mut fonts = Vec::with_capacity(font_data.len());
+        for i in 0..font_data.len() {
+            let (data, index) = font_data.get_data(i);
+            fonts.push(Some(FontRef::try_from_slice_and_index(data, index)?));
+        }
+
+        Ok(self.new_window_with_fonts(size, fonts))
+    }
+
+    fn new_window_with_fonts(
+        &self,
+        size: Size,
+        fonts: Vec<Option<FontRef<'static>>>,
+    ) -> DrawWindow {
+        let rect = Rect::new(Coord::ZERO, size);
+        let len = usize::conv(size.0) * usize::conv(size.1);
+        DrawWindow {
+            size,
+            clip_regions: vec![rect],
+            depths: vec![kas_theme::START_PASS.depth()],
+            passes: vec![],
+            text: vec![],
+            fonts,
+            light_norm: self.light_norm,
+            pixels: vec![0; 4 * len],
+        }
+    }
+
+    /// Process window resize
+    ///
+    /// The output buffer is cleared.
+    pub fn resize(&self, window: &mut DrawWindow, size: Size) {
+        window.size = size;
+        window.clip_regions[0].size = size;
+        let len = usize::conv(size.0) * usize::conv(size.1);
+        window.pixels.clear();
+        window.pixels.resize(4 * len, 0);
+    }
+
+    /// Render batched draw instructions to the window's output buffer
+    ///
+    /// This replaces the whole contents of [`DrawWindow::pixels`]. The
+    /// `clear_color` is used as the background (as with `Theme::clear_color`).
+    pub fn render(&self, window: &mut DrawWindow, clear_color: Colour) {
+        let width = usize::conv(window.size.0);
+        let len = width * usize::conv(window.size.1);
+        let mut colour = vec![Rgb::from(clear_color); len];
+        let mut depth = vec![kas_theme::START_PASS.depth(); len];
+
+        {
+            let mut target = Target {
+                width,
+                colour: &mut colour,
+                depth: &mut depth,
+                light_norm: window.light_norm,
+            };
+
+            // Each clip region is a separate pass, drawn in order
+            for (pass, rect) in window.clip_regions.iter().enumerate() {
+                let data = match window.passes.get(pass) {
+                    Some(data) => data,
+                    None => break,
+                };
+                let d = window.depths[pass];
+                let shapes = data
+                    .squares
+                    .iter()
+                    .chain(data.shaded_round.iter())
+                    .chain(data.flat_round.iter());
+                for shape in shapes {
+                    target.draw(*rect, d, shape);
+                }
+            }
+        }
+
+        for run in window.text.drain(..) {
+            let (min, max) = run.bounds;
+            let rect = window.clip_regions[run.pass];
+            let x0 = min.0.max(rect.pos.0.cast());
+            let y0 = min.1.max(rect.pos.1.cast());
+            let x1 = max.0.min((rect.pos.0 + rect.size.0).cast());
+            let y1 = max.1.min((rect.pos.1 + rect.size.1).cast());
+
+            for (font_id, glyph, col, alpha) in run.glyphs {
+                let font = match window.fonts.get(font_id) {
+                    Some(Some(font)) => font,
+                    _ => continue,
+                };
+                let outline = match font.outline_glyph(glyph) {
+                    Some(outline) => outline,
+                    None => continue,
+                };
+                let bounds = outline.px_bounds();
+                outline.draw(|gx, gy, coverage| {
+                    let x = bounds.min.x + gx as f32;
+                    let y = bounds.min.y + gy as f32;
+                    if x < x0 || x + 1.0 > x1 || y < y0 || y + 1.0 > y1 {
+                        return;
+                    }
+                    let i = y as usize * width + x as usize;
+                    if run.depth < depth[i] {
+                        return;
+                    }
+                    let a = alpha * coverage;
+                    let d = colour[i];
+                    colour[i] = Rgb {
+                        r: col.r * a + d.r * (1.0 - a),
+                        g: col.g * a + d.g * (1.0 - a),
+                        b: col.b * a + d.b * (1.0 - a),
+                    };
+                });
+            }
+        }
+
+        for (px, c) in window.pixels.chunks_exact_mut(4).zip(colour.iter()) {
+            px[0] = linear_to_srgb(c.r);
+            px[1] = linear_to_srgb(c.g);
+            px[2] = linear_to_srgb(c.b);
+            px[3] = 255;
+        }
+
+        // Keep only first clip region (which is the entire window)
+        window.clip_regions.truncate(1);
+        window.depths.truncate(1);
+        window.passes.clear();
+    }
+}
+
+impl DrawShared for DrawPipe {
+    type Draw = DrawWindow;
+}
+
+impl DrawWindow {
+    fn add_shape(&mut self, pass: usize, shape: Shape) {
+        if self.passes.len() <= pass {
+            self.passes.resize(pass + 1, PassData::default());
+        }
+        let data = &mut self.passes[pass];
+        match shape {
+            Shape::Square { .. } => data.squares.push(shape),
+            Shape::ShadedRound { .. } => data.shaded_round.push(shape),
+            Shape::FlatRound { .. } | Shape::Line { .. } => data.flat_round.push(shape),
+        }
+    }
+
+    fn add_square(&mut self, pass: Pass, outer: Quad, inner: Quad, mut norm: Vec2, col: Colour) {
+        let aa = outer.a;
+        let bb = outer.b;
+        let (cc, dd) = fix_inner(outer, inner);
+
+        if !aa.lt(bb) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+        if !Vec2::splat(-1.0).le(norm) || !norm.le(Vec2::splat(1.0)) {
+            norm = Vec2::splat(0.0);
+        }
+
+        let inner = Quad { a: cc, b: dd };
+        let col = col.into();
+        let shape = Shape::Square {
+            outer,
+            inner,
+            norm,
+            col,
+        };
+        self.add_shape(pass.pass(), shape);
+    }
+}
+
+/// Adjust `inner` such that `outer.a ≤ inner.a ≤ inner.b ≤ outer.b`
+fn fix_inner(outer: Quad, inner: Quad) -> (Vec2, Vec2) {
+    let (aa, bb) = (outer.a, outer.b);
+    let mut cc = inner.a;
+    let mut dd = inner.b;
+    if !aa.le(cc) || !cc.le(bb) {
+        cc = aa;
+    }
+    if !aa.le(dd) || !dd.le(bb) {
+        dd = bb;
+    }
+    if !cc.le(dd) {
+        dd = cc;
+    }
+    (cc, dd)
+}
+
+/// Get the mid-point of a quad as a zero-size quad
+fn mid(rect: Quad) -> Quad {
+    let mid = (rect.a + rect.b) * 0.5;
+    Quad { a: mid, b: mid }
+}
+
+impl Draw for DrawWindow {
+    #[inline]
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn add_clip_region(&mut self, rect: Rect, depth: f32) -> Pass {
+        let window_rect = self.clip_regions[0];
+        let rect = rect.intersection(&window_rect).unwrap_or_else(|| {
+            log::warn!("add_clip_region: intersection of rect and window rect is empty");
+            Rect::new(Coord::ZERO, Size::ZERO)
+        });
+        let pass = self.clip_regions.len().cast();
+        self.clip_regions.push(rect);
+        self.depths.push(depth);
+        Pass::new_pass_with_depth(pass, depth)
+    }
+
+    #[inline]
+    fn rect(&mut self, pass: Pass, rect: Quad, col: Colour) {
+        self.add_square(pass, rect, mid(rect), Vec2::splat(0.0), col);
+    }
+
+    #[inline]
+    fn frame(&mut self, pass: Pass, outer: Quad, inner: Quad, col: Colour) {
+        self.add_square(pass, outer, inner, Vec2::splat(0.0), col);
+    }
+}
+
+impl DrawRounded for DrawWindow {
+    fn rounded_line(&mut self, pass: Pass, p1: Vec2, p2: Vec2, radius: f32, col: Colour) {
+        let col = col.into();
+        self.add_shape(
+            pass.pass(),
+            Shape::Line {
+                p1,
+                p2,
+                radius,
+                col,
+            },
+        );
+    }
+
+    #[inline]
+    fn circle(&mut self, pass: Pass, rect: Quad, inner_radius: f32, col: Colour) {
+        self.rounded_frame(pass, rect, mid(rect), inner_radius, col);
+    }
+
+    fn rounded_frame(
+        &mut self,
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        inner_radius: f32,
+        col: Colour,
+    ) {
+        if !outer.a.lt(outer.b) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+        let (a, b) = fix_inner(outer, inner);
+        let shape = Shape::FlatRound {
+            outer,
+            inner: Quad { a, b },
+            inner_radius,
+            col: col.into(),
+        };
+        self.add_shape(pass.pass(), shape);
+    }
+}
+
+impl DrawShaded for DrawWindow {
+    #[inline]
+    fn shaded_square(&mut self, pass: Pass, rect: Quad, norm: (f32, f32), col: Colour) {
+        self.add_square(pass, rect, mid(rect), Vec2::from(norm), col);
+    }
+
+    #[inline]
+    fn shaded_circle(&mut self, pass: Pass, rect: Quad, norm: (f32, f32), col: Colour) {
+        self.shaded_round_frame(pass, rect, mid(rect), norm, col);
+    }
+
+    #[inline]
+    fn shaded_square_frame(
+        &mut self,
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        col: Colour,
+    ) {
+        self.add_square(pass, outer, inner, Vec2::from(norm), col);
+    }
+
+    fn shaded_round_frame(
+        &mut self,
+        pass: Pass,
+        outer: Quad,
+        inner: Quad,
+        norm: (f32, f32),
+        col: Colour,
+    ) {
+        if !outer.a.lt(outer.b) {
+            // zero / negative size: nothing to draw
+            return;
+        }
+        let mut norm = Vec2::from(norm);
+        if !Vec2::splat(-1.0).le(norm) || !norm.le(Vec2::splat(1.0)) {
+            norm = Vec2::splat(0.0);
+        }
+        let (a, b) = fix_inner(outer, inner);
+        let shape = Shape::ShadedRound {
+            outer,
+            inner: Quad { a, b },
+            norm,
+            col: col.into(),
+        };
+        self.add_shape(pass.pass(), shape);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_rect() {
+        let pipe = DrawPipe::new();
+        let mut window = pipe.new_window_with_fonts(Size(8, 8), vec![]);
+        let quad = Quad::with_coords(Vec2(2.0, 2.0), Vec2(6.0, 6.0));
+        window.rect(kas_theme::START_PASS, quad, Colour::new(1.0, 0.0, 0.0));
+        pipe.render(&mut window, Colour::grey(0.0));
+
+        let pixel = |x: usize, y: usize| {
+            let i = 4 * (y * 8 + x);
+            &window.pixels()[i..i + 4]
+        };
+        assert_eq!(pixel(1, 1), [0, 0, 0, 255]);
+        assert_eq!(pixel(2, 2), [255, 0, 0, 255]);
+        assert_eq!(pixel(5, 5), [255, 0, 0, 255]);
+        assert_eq!(pixel(6, 5), [0, 0, 0, 255]);
+        assert_eq!(pixel(5, 6), [0, 0, 0, 255]);
+    }
+}
@@ -0,0 +1,168 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Text drawing API for `kas_soft`
+
+use ab_glyph::{FontRef, GlyphId, Point};
+
+use super::{DrawWindow, Rgb, TextGlyph, TextRun};
+use kas::draw::{Colour, Draw, DrawText, Pass};
+use kas::geom::{Quad, Vec2};
+use kas::text::fonts::{fonts, FontId};
+use kas::text::{Effect, Glyph, TextDisplay};
+
+fn make_glyph(offset: Vec2, height: f32, glyph: Glyph) -> ab_glyph::Glyph {
+    let kas::text::Vec2(x, y) = glyph.position;
+    ab_glyph::Glyph {
+        id: GlyphId(glyph.id.0),
+        scale: height.into(),
+        position: Point {
+            x: offset.0 + x,
+            y: offset.1 + y,
+        },
+    }
+}
+
+impl DrawWindow {
+    fn queue_text(&mut self, pass: Pass, pos: Vec2, bounds: Vec2, glyphs: Vec<TextGlyph>) {
+        self.text.push(TextRun {
+            pass: pass.pass(),
+            depth: pass.depth(),
+            bounds: (pos, pos + bounds),
+            glyphs,
+        });
+    }
+}
+
+impl DrawText for DrawWindow {
+    fn prepare_fonts(&mut self) {
+        let fonts = fonts();
+        let n1 = self.fonts.len();
+        let n2 = fonts.num_fonts();
+        if n2 > n1 {
+            // TODO: use extra caching so we don't load font for each window
+            let font_data = fonts.font_data();
+            for i in n1..n2 {
+                let (data, index) = font_data.get_data(i);
+                match FontRef::try_from_slice_and_index(data, index) {
+                    Ok(font) => self.fonts.push(Some(font)),
+                    Err(e) => {
+                        log::error!("prepare_fonts: failed to load font {}: {}", i, e);
+                        // Keep indices aligned with FontId; glyphs of this font are skipped
+                        self.fonts.push(None);
+                    }
+                }
+            }
+        }
+    }
+
+    fn text(
+        &mut self,
+        pass: Pass,
+        pos: Vec2,
+        bounds: Vec2,
+        offset: Vec2,
+        text: &TextDisplay,
+        col: Colour,
+    ) {
+        let offset = pos - offset;
+        let rgb = Rgb::from(col);
+
+        let mut glyphs = Vec::with_capacity(text.num_glyphs());
+        let for_glyph = |font_id: FontId, _, height: f32, glyph: Glyph| {
+            let glyph = make_glyph(offset, height, glyph);
+            glyphs.push((font_id.get(), glyph, rgb, col.a));
+        };
+        text.glyphs(for_glyph);
+
+        self.queue_text(pass, pos, bounds, glyphs);
+    }
+
+    fn text_col_effects(
+        &mut self,
+        pass: Pass,
+        pos: Vec2,
+        bounds: Vec2,
+        offset: Vec2,
+        text: &TextDisplay,
+        col: Colour,
+        effects: &[Effect<()>],
+    ) {
+        // Optimisation: use cheaper TextDisplay::glyphs method
+        if effects.len() <= 1
+            && effects
+                .get(0)
+                .map(|e| e.flags == Default::default())
+                .unwrap_or(true)
+        {
+            return self.text(pass, pos, bounds, offset, text, col);
+        }
+
+        let offset = pos - offset;
+        let rgb = Rgb::from(col);
+
+        let mut glyphs = Vec::with_capacity(text.num_glyphs());
+        let for_glyph = |font_id: FontId, _, height: f32, glyph: Glyph, _, _| {
+            let glyph = make_glyph(offset, height, glyph);
+            glyphs.push((font_id.get(), glyph, rgb, col.a));
+        };
+        let for_rect = |x1, x2, mut y, h: f32, _, _| {
+            let y2 = y + h;
+            if h < 1.0 {
+                // h too small can make the line invisible due to rounding
+                // In this case we prefer to push the line up (nearer text).
+                y = y2 - 1.0;
+            }
+            let quad = Quad::with_coords(pos + Vec2(x1, y), pos + Vec2(x2, y2));
+            self.rect(pass, quad, col);
+        };
+        text.glyphs_with_effects(effects, for_glyph, for_rect);
+
+        self.queue_text(pass, pos, bounds, glyphs);
+    }
+
+    fn text_effects(
+        &mut self,
+        pass: Pass,
+        pos: Vec2,
+        bounds: Vec2,
+        offset: Vec2,
+        text: &TextDisplay,
+        effects: &[Effect<Colour>],
+    ) {
+        // Optimisation: use cheaper TextDisplay::glyphs method
+        if effects.len() <= 1
+            && effects
+                .get(0)
+                .map(|e| e.flags == Default::default())
+                .unwrap_or(true)
+        {
+            let col = effects.get(0).map(|e| e.aux).unwrap_or(Colour::default());
+            return self.text(pass, pos, bounds, offset, text, col);
+        }
+
+        let offset = pos - offset;
+
+        let mut glyphs = Vec::with_capacity(text.num_glyphs());
+        let for_glyph = |font_id: FontId, _, height: f32, glyph: Glyph, i: usize, _| {
+            let col = effects.get(i).map(|e| e.aux).unwrap_or(Colour::default());
+            let glyph = make_glyph(offset, height, glyph);
+            glyphs.push((font_id.get(), glyph, Rgb::from(col), col.a));
+        };
+        let for_rect = |x1, x2, mut y, h: f32, i: usize, _| {
+            let y2 = y + h;
+            if h < 1.0 {
+                // h too small can make the line invisible due to rounding
+                // In this case we prefer to push the line up (nearer text).
+                y = y2 - 1.0;
+            }
+            let quad = Quad::with_coords(pos + Vec2(x1, y), pos + Vec2(x2, y2));
+            self.rect(pass, quad, effects[i].aux);
+        };
+        text.glyphs_with_effects(effects, for_glyph, for_rect);
+
+        self.queue_text(pass, pos, bounds, glyphs);
+    }
+}
@@ -0,0 +1,98 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Drawing API for `kas_soft`
+//!
+//! Draw commands are queued per [`Pass`](kas::draw::Pass), then rasterised in
+//! a single step by [`DrawPipe::render`]. Ordering follows that of `kas-wgpu`:
+//! within each pass, square shapes are drawn before shaded rounded shapes,
+//! which are drawn before flat rounded shapes. Text is drawn last, subject to
+//! a depth test against the pass depth.
+
+mod draw_pipe;
+mod draw_text;
+mod raster;
+
+use ab_glyph::FontRef;
+use kas::geom::{Rect, Size, Vec3};
+
+use raster::Shape;
+
+/// 3-part colour data (linear RGB)
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(crate) struct Rgb {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+impl From<kas::draw::Colour> for Rgb {
+    fn from(c: kas::draw::Colour) -> Self {
+        Rgb {
+            r: c.r,
+            g: c.g,
+            b: c.b,
+        }
+    }
+}
+
+/// Shared pipeline data
+pub struct DrawPipe {
+    light_norm: Vec3,
+}
+
+/// Queued draw commands for a single pass
+#[derive(Clone, Debug, Default)]
+struct PassData {
+    squares: Vec<Shape>,
+    shaded_round: Vec<Shape>,
+    flat_round: Vec<Shape>,
+}
+
+/// A positioned glyph: font index, glyph, colour and alpha
+type TextGlyph = (usize, ab_glyph::Glyph, Rgb, f32);
+
+/// A queued run of positioned glyphs
+struct TextRun {
+    pass: usize,
+    depth: f32,
+    /// Clip rect in pixel coordinates: `(min, max)`
+    bounds: (kas::geom::Vec2, kas::geom::Vec2),
+    glyphs: Vec<TextGlyph>,
+}
+
+/// Per-window pipeline data
+///
+/// This contains the output buffer, available via [`DrawWindow::pixels`]
+/// after [`DrawPipe::render`] has been called.
+pub struct DrawWindow {
+    size: Size,
+    clip_regions: Vec<Rect>,
+    depths: Vec<f32>,
+    passes: Vec<PassData>,
+    text: Vec<TextRun>,
+    fonts: Vec<Option<FontRef<'static>>>,
+    light_norm: Vec3,
+    pixels: Vec<u8>,
+}
+
+impl DrawWindow {
+    /// Size of the output buffer
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Access the output buffer
+    ///
+    /// Pixels are stored in row-major order, starting from the top-left, with
+    /// four bytes per pixel (RGBA, sRGB encoded). Output is always opaque: the
+    /// alpha component is 255.
+    /// Contents are updated by [`DrawPipe::render`].
+    #[inline]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+}
@@ -0,0 +1,323 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Shape rasterisation
+//!
+//! Each routine here is a CPU translation of the corresponding `kas-wgpu`
+//! shader: values which the GPU interpolates per-vertex are instead calculated
+//! directly for each pixel centre.
+
+use super::Rgb;
+use kas::geom::{Quad, Rect, Vec2, Vec3};
+
+/// Sample offset used for anti-aliasing (see `kas-wgpu`)
+const OFFSET: f32 = 0.125;
+
+/// A queued shape
+#[derive(Clone, Debug)]
+pub enum Shape {
+    /// Frame with square corners; a rect is a frame with a zero-size inner
+    Square {
+        outer: Quad,
+        inner: Quad,
+        norm: Vec2,
+        col: Rgb,
+    },
+    /// Shaded frame with rounded corners; a circle is a frame with a zero-size inner
+    ShadedRound {
+        outer: Quad,
+        inner: Quad,
+        norm: Vec2,
+        col: Rgb,
+    },
+    /// Flat frame with rounded corners; a circle is a frame with a zero-size inner
+    FlatRound {
+        outer: Quad,
+        inner: Quad,
+        inner_radius: f32,
+        col: Rgb,
+    },
+    /// Line with rounded ends
+    Line {
+        p1: Vec2,
+        p2: Vec2,
+        radius: f32,
+        col: Rgb,
+    },
+}
+
+/// Render target
+pub struct Target<'a> {
+    pub width: usize,
+    pub colour: &'a mut [Rgb],
+    pub depth: &'a mut [f32],
+    pub light_norm: Vec3,
+}
+
+impl<'a> Target<'a> {
+    #[inline]
+    fn write(&mut self, x: usize, y: usize, c: Rgb, depth: f32) {
+        let i = y * self.width + x;
+        self.colour[i] = c;
+        self.depth[i] = depth;
+    }
+
+    #[inline]
+    fn blend(&mut self, x: usize, y: usize, c: Rgb, alpha: f32, depth: f32) {
+        let i = y * self.width + x;
+        let d = self.colour[i];
+        self.colour[i] = Rgb {
+            r: c.r * alpha + d.r * (1.0 - alpha),
+            g: c.g * alpha + d.g * (1.0 - alpha),
+            b: c.b * alpha + d.b * (1.0 - alpha),
+        };
+        self.depth[i] = depth;
+    }
+
+    #[inline]
+    fn shade(&self, col: Rgb, norm: Vec3) -> Rgb {
+        let l = self.light_norm;
+        let f = norm.0 * l.0 + norm.1 * l.1 + norm.2 * l.2;
+        Rgb {
+            r: col.r * f,
+            g: col.g * f,
+            b: col.b * f,
+        }
+    }
+
+    /// Draw `shape` within the `clip` rect at the given `depth`
+    pub fn draw(&mut self, clip: Rect, depth: f32, shape: &Shape) {
+        match shape {
+            Shape::Square {
+                outer,
+                inner,
+                norm,
+                col,
+            } => self.square(clip, depth, *outer, *inner, *norm, *col),
+            Shape::ShadedRound {
+                outer,
+                inner,
+                norm,
+                col,
+            } => self.shaded_round(clip, depth, *outer, *inner, *norm, *col),
+            Shape::FlatRound {
+                outer,
+                inner,
+                inner_radius,
+                col,
+            } => self.flat_round(clip, depth, *outer, *inner, *inner_radius, *col),
+            Shape::Line {
+                p1,
+                p2,
+                radius,
+                col,
+            } => self.line(clip, depth, *p1, *p2, *radius, *col),
+        }
+    }
+
+    fn square(&mut self, clip: Rect, depth: f32, outer: Quad, inner: Quad, norm: Vec2, col: Rgb) {
+        let (a, b, c, d) = (outer.a, outer.b, inner.a, inner.b);
+        let depth_in = |p: f32, edge: f32, w: f32| {
+            if w > 0.0 {
+                (p - edge) / w
+            } else {
+                f32::INFINITY
+            }
+        };
+
+        for_pixels(clip, outer, |x, y, p| {
+            if c.0 < p.0 && p.0 < d.0 && c.1 < p.1 && p.1 < d.1 {
+                return;
+            }
+
+            // Each bar is the trapezoid between outer and inner edges; we
+            // select the bar nearest the pixel, relative to its width.
+            let bars = [
+                (depth_in(p.1, a.1, c.1 - a.1), Vec2(0.0, -1.0)),
+                (depth_in(p.0, a.0, c.0 - a.0), Vec2(-1.0, 0.0)),
+                (depth_in(b.1, p.1, b.1 - d.1), Vec2(0.0, 1.0)),
+                (depth_in(b.0, p.0, b.0 - d.0), Vec2(1.0, 0.0)),
+            ];
+            let mut bar = bars[0];
+            for b in &bars[1..] {
+                if b.0 < bar.0 {
+                    bar = *b;
+                }
+            }
+
+            let t = bar.0.max(0.0).min(1.0);
+            let norm2 = bar.1 * (norm.1 + (norm.0 - norm.1) * t);
+            let n3 = 1.0 - (norm2.0 * norm2.0 + norm2.1 * norm2.1).sqrt();
+            let c = self.shade(col, Vec3::from2(norm2, n3));
+            self.write(x, y, c, depth);
+        });
+    }
+
+    fn shaded_round(
+        &mut self,
+        clip: Rect,
+        depth: f32,
+        outer: Quad,
+        inner: Quad,
+        norm: Vec2,
+        col: Rgb,
+    ) {
+        let adjust = Vec2(std::f32::consts::FRAC_PI_2 * norm.0, norm.1 - norm.0);
+
+        for_pixels(clip, outer, |x, y, p| {
+            let (dir, off) = match round_dir(outer, inner, p) {
+                Some(v) => v,
+                None => return,
+            };
+
+            let sample_a = |dir: Vec2| if dir.sum_square() <= 1.0 { 0.25 } else { 0.0 };
+            let alpha = multi_sample(dir, off, sample_a);
+            if alpha == 0.0 {
+                return;
+            }
+
+            let ss = dir.sum_square();
+            let mut z = (1.0 - ss).max(0.0).sqrt();
+            let h = ss.sqrt();
+            let t = adjust.0 + adjust.1 * h.atan2(z);
+            let mut normh = Vec2::splat(0.0);
+            if h > 0.0 {
+                normh = dir * (t.sin() / h);
+                z = t.cos();
+            }
+
+            let c = self.shade(col, Vec3::from2(normh, z));
+            self.blend(x, y, c, alpha, depth);
+        });
+    }
+
+    fn flat_round(
+        &mut self,
+        clip: Rect,
+        depth: f32,
+        outer: Quad,
+        inner: Quad,
+        inner_radius: f32,
+        col: Rgb,
+    ) {
+        let inner_radius = inner_radius.max(0.0).min(1.0);
+        let sample_a = |dir: Vec2| {
+            let ss = dir.sum_square();
+            if inner_radius <= ss && ss <= 1.0 {
+                0.25
+            } else {
+                0.0
+            }
+        };
+
+        for_pixels(clip, outer, |x, y, p| {
+            if let Some((dir, off)) = round_dir(outer, inner, p) {
+                let alpha = multi_sample(dir, off, sample_a);
+                self.blend(x, y, col, alpha, depth);
+            }
+        });
+    }
+
+    fn line(&mut self, clip: Rect, depth: f32, p1: Vec2, p2: Vec2, radius: f32, col: Rgb) {
+        if p1 == p2 {
+            let a = p1 - radius;
+            let b = p2 + radius;
+            let quad = Quad { a, b };
+            let mid = Quad { a: p1, b: p1 };
+            self.flat_round(clip, depth, quad, mid, radius, col);
+            return;
+        }
+
+        let v = p2 - p1;
+        let len = v.sum_square().sqrt();
+        let u = v / len;
+        let off = Vec2::splat(OFFSET / radius);
+
+        let bounds = Quad {
+            a: p1.min(p2) - radius,
+            b: p1.max(p2) + radius,
+        };
+        let sample_a = |dir: Vec2| if dir.sum_square() <= 1.0 { 0.25 } else { 0.0 };
+
+        for_pixels(clip, bounds, |x, y, p| {
+            // Coordinates in the line's frame, scaled by radius:
+            let q = p - p1;
+            let along = q.0 * u.0 + q.1 * u.1;
+            let perp = q.1 * u.0 - q.0 * u.1;
+            if perp.abs() > radius || along < -radius || along > len + radius {
+                return;
+            }
+            let dx = if along < 0.0 {
+                along
+            } else if along > len {
+                along - len
+            } else {
+                0.0
+            };
+            let dir = Vec2(dx, perp) / radius;
+
+            let alpha = multi_sample(dir, off, sample_a);
+            self.blend(x, y, col, alpha, depth);
+        });
+    }
+}
+
+/// Call `f(x, y, centre)` for each pixel whose centre is within `quad` and
+/// which is within `clip`
+fn for_pixels<F: FnMut(usize, usize, Vec2)>(clip: Rect, quad: Quad, mut f: F) {
+    let x0 = (quad.a.0 - 0.5).ceil().max(clip.pos.0 as f32) as i32;
+    let y0 = (quad.a.1 - 0.5).ceil().max(clip.pos.1 as f32) as i32;
+    let x1 = (quad.b.0 - 0.5)
+        .ceil()
+        .min((clip.pos.0 + clip.size.0) as f32) as i32;
+    let y1 = (quad.b.1 - 0.5)
+        .ceil()
+        .min((clip.pos.1 + clip.size.1) as f32) as i32;
+
+    for y in y0.max(0)..y1 {
+        for x in x0.max(0)..x1 {
+            let p = Vec2(x as f32 + 0.5, y as f32 + 0.5);
+            f(x as usize, y as usize, p);
+        }
+    }
+}
+
+/// Calculate direction and sample offset for a rounded frame at `p`
+///
+/// Direction is zero on the inner edge and has length one at the outer corner.
+/// Returns `None` for pixels within the inner region.
+fn round_dir(outer: Quad, inner: Quad, p: Vec2) -> Option<(Vec2, Vec2)> {
+    let (a, b) = (outer.a, outer.b);
+    let (c, d) = (inner.a, inner.b);
+    if c.0 < p.0 && p.0 < d.0 && c.1 < p.1 && p.1 < d.1 {
+        return None;
+    }
+
+    let axis = |p: f32, a: f32, b: f32, c: f32, d: f32| -> (f32, f32) {
+        let off = |w: f32| if w > 0.0 { OFFSET / w } else { 0.0 };
+        if p < c {
+            let w = c - a;
+            (if w > 0.0 { (p - c) / w } else { 0.0 }, off(w))
+        } else if p > d {
+            let w = b - d;
+            (if w > 0.0 { (p - d) / w } else { 0.0 }, off(w))
+        } else if p < (c + d) * 0.5 {
+            (0.0, off(c - a))
+        } else {
+            (0.0, off(b - d))
+        }
+    };
+    let x = axis(p.0, a.0, b.0, c.0, d.0);
+    let y = axis(p.1, a.1, b.1, c.1, d.1);
+    Some((Vec2(x.0, y.0), Vec2(x.1, y.1)))
+}
+
+/// Multi-sample alpha to avoid ugly aliasing
+#[inline]
+fn multi_sample<F: Fn(Vec2) -> f32>(dir: Vec2, off: Vec2, sample_a: F) -> f32 {
+    let off1 = Vec2(off.0, 3.0 * off.1);
+    let off2 = Vec2(3.0 * off.0, off.1);
+    sample_a(dir + off1) + sample_a(dir - off1) + sample_a(dir + off2) + sample_a(dir - off2)
+}
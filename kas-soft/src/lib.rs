@@ -0,0 +1,52 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! KAS software renderer
+//!
+//! This crate provides a CPU-based implementation of the [`kas::draw`] traits,
+//! rendering into an in-memory RGBA buffer. No GPU and no windowing system is
+//! required.
+//!
+//! Usage is similar to that of the draw types in `kas-wgpu`: construct a
+//! [`DrawPipe`], initialise the theme with it, construct a [`DrawWindow`] per
+//! output surface, draw widgets via the theme's draw handle, then call
+//! [`DrawPipe::render`] to rasterise queued primitives. The result is
+//! available via [`DrawWindow::pixels`].
+//!
+//! For the common case of drawing a whole [`kas::Window`] off-screen (e.g. for
+//! reference-image tests), use [`Snapshot`] instead; this yields an [`Image`]
+//! which may be saved as PNG.
+//!
+//! Glyph rasterisation is provided by [ab_glyph].
+//!
+//! [ab_glyph]: https://github.com/alexheretic/ab-glyph
+
+#![cfg_attr(feature = "gat", feature(generic_associated_types))]
+
+pub mod draw;
+mod snapshot;
+
+use thiserror::Error;
+
+pub use draw::{DrawPipe, DrawWindow};
+pub use snapshot::{Image, Snapshot};
+
+pub use kas;
+pub use kas_theme as theme;
+
+/// Possible failures from rendering or saving an [`Image`]
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Font loading error
+    #[error("failed to load font")]
+    Font(#[from] ab_glyph::InvalidFont),
+    /// PNG encoding error
+    #[error("PNG encoding failed")]
+    Png(#[from] png::EncodingError),
+    /// IO error
+    #[error("error writing image file")]
+    Io(#[from] std::io::Error),
+}
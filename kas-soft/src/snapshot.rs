@@ -0,0 +1,324 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Off-screen rendering of windows
+
+use log::warn;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+
+use kas::conv::Cast;
+use kas::draw::SizeHandle;
+use kas::event::{Config, CursorIcon, ManagerState, UpdateHandle};
+use kas::geom::{Coord, Rect, Size};
+use kas::layout::SolveCache;
+use kas::testing::MockShell;
+use kas::{ShellWindow, ThemeAction, ThemeApi, TkAction, WindowId};
+use kas_theme::Theme;
+
+use crate::{DrawPipe, Error};
+
+/// An RGBA image
+///
+/// Pixels are stored in row-major order, starting from the top-left, with
+/// four bytes per pixel (sRGB encoded).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    size: Size,
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Image size
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Access pixel data
+    #[inline]
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Take pixel data
+    #[inline]
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    /// Encode as PNG, writing to `w`
+    pub fn write_png<W: Write>(&self, w: W) -> Result<(), Error> {
+        let mut encoder = png::Encoder::new(w, self.size.0.cast(), self.size.1.cast());
+        encoder.set_color(png::ColorType::RGBA);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+
+    /// Encode as PNG, writing to the file at `path`
+    ///
+    /// The file is created or replaced.
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let file = std::fs::File::create(path)?;
+        self.write_png(std::io::BufWriter::new(file))
+    }
+}
+
+/// Off-screen window renderer
+///
+/// This type draws a [`kas::Window`] to an [`Image`] without any windowing
+/// system or GPU, for example to compare against reference images in tests:
+/// ```no_run
+/// # use kas::widget::{Label, Window};
+/// let theme = kas_theme::FlatTheme::new();
+/// let mut snapshot = kas_soft::Snapshot::new(theme);
+/// let mut window = Window::new("Hello", Label::new("Hello, world!"));
+/// let image = snapshot.render(&mut window, None, 1.0).unwrap();
+/// image.save_png("hello.png").unwrap();
+/// ```
+///
+/// Each call to [`Snapshot::render`] configures the window, solves its layout
+/// and draws it. Pop-ups opened during configuration are drawn.
+pub struct Snapshot<T: Theme<DrawPipe>> {
+    draw: DrawPipe,
+    theme: T,
+    config: Rc<RefCell<Config>>,
+}
+
+impl<T: Theme<DrawPipe>> Snapshot<T>
+where
+    T::Window: kas_theme::Window,
+{
+    /// Construct, using the default event configuration
+    #[inline]
+    pub fn new(theme: T) -> Self {
+        Self::new_config(theme, Default::default())
+    }
+
+    /// Construct with the given event configuration
+    pub fn new_config(mut theme: T, config: Rc<RefCell<Config>>) -> Self {
+        let mut draw = DrawPipe::new();
+        theme.init(&mut draw);
+        Snapshot {
+            draw,
+            theme,
+            config,
+        }
+    }
+
+    /// Access the theme
+    ///
+    /// This may be used to select a colour scheme or font size before
+    /// rendering.
+    #[inline]
+    pub fn theme_mut(&mut self) -> &mut T {
+        &mut self.theme
+    }
+
+    /// Render a window
+    ///
+    /// The window is laid out with the given `size`, or its ideal size if
+    /// `None`, at the given `scale_factor`, then drawn.
+    pub fn render(
+        &mut self,
+        window: &mut dyn kas::Window,
+        size: Option<Size>,
+        scale_factor: f32,
+    ) -> Result<Image, Error> {
+        let mut draw = self.draw.new_window(Size::ZERO)?;
+        let mut theme_window = self.theme.new_window(&mut draw, scale_factor);
+
+        let mut mgr = ManagerState::new(self.config.clone());
+        let mut shell = SnapshotShell::new(&mut self.theme, &mut theme_window, scale_factor);
+        mgr.configure(&mut shell, window);
+
+        let mut solve_cache = None;
+        shell.size_handle(&mut |size_handle| {
+            let cache = SolveCache::find_constraints(window.as_widget_mut(), size_handle);
+            solve_cache = Some(cache);
+        });
+        let mut solve_cache = solve_cache.unwrap();
+        let size = size.unwrap_or_else(|| solve_cache.ideal(true).max(Size(1, 1)));
+        let rect = Rect::new(Coord::ZERO, size);
+        self.draw.resize(&mut draw, size);
+
+        let mut action = TkAction::RESIZE;
+        let mut popups = vec![];
+        // Limit iterations: a misbehaving widget could repeatedly request action
+        for _ in 0..8 {
+            if shell.resize {
+                shell.resize = false;
+                action |= TkAction::RESIZE;
+            }
+            if action.contains(TkAction::RECONFIGURE) {
+                mgr.configure(&mut shell, window);
+                action |= TkAction::RESIZE;
+            }
+            if action.intersects(TkAction::RESIZE | TkAction::SET_SIZE) {
+                if action.contains(TkAction::RESIZE) {
+                    solve_cache.invalidate_rule_cache();
+                }
+                mgr.with(&mut shell, |mgr| {
+                    solve_cache.apply_rect(window.as_widget_mut(), mgr, rect, true);
+                    window.resize_popups(mgr);
+                });
+            }
+
+            let new: Vec<_> = shell
+                .mock
+                .popups()
+                .iter()
+                .filter(|p| !popups.contains(&p.0))
+                .cloned()
+                .collect();
+            popups.extend(new.iter().map(|p| p.0));
+            for (id, popup) in new.into_iter() {
+                mgr.with(&mut shell, |mgr| window.add_popup(mgr, id, popup));
+            }
+
+            action = mgr.update(&mut shell, window);
+            if action.contains(TkAction::REGION_MOVED) {
+                mgr.region_moved(&mut shell, window);
+            }
+            let pending = TkAction::RECONFIGURE | TkAction::RESIZE | TkAction::SET_SIZE;
+            let popups_done = shell.mock.popups().iter().all(|p| popups.contains(&p.0));
+            if !action.intersects(pending) && !shell.resize && popups_done {
+                break;
+            }
+        }
+        drop(shell);
+
+        unsafe {
+            // Safety: we must drop draw_handle after draw call (wrong lifetime)
+            let mut draw_handle = self.theme.draw_handle(&mut draw, &mut theme_window, rect);
+            window.draw(&mut draw_handle, &mgr, false);
+        }
+        self.draw.render(&mut draw, self.theme.clear_color());
+
+        Ok(Image {
+            size,
+            pixels: draw.pixels().to_vec(),
+        })
+    }
+}
+
+/// Implementation of [`kas::ShellWindow`] for off-screen rendering
+///
+/// This forwards to a [`MockShell`] except that theme adjustment and size
+/// handles use the real theme.
+struct SnapshotShell<'a, T: Theme<DrawPipe>> {
+    mock: MockShell,
+    theme: &'a mut T,
+    theme_window: &'a mut T::Window,
+    scale_factor: f32,
+    resize: bool,
+}
+
+impl<'a, T: Theme<DrawPipe>> SnapshotShell<'a, T> {
+    fn new(theme: &'a mut T, theme_window: &'a mut T::Window, scale_factor: f32) -> Self {
+        SnapshotShell {
+            mock: MockShell::new_scale_factor(scale_factor),
+            theme,
+            theme_window,
+            scale_factor,
+            resize: false,
+        }
+    }
+}
+
+impl<'a, T> ShellWindow for SnapshotShell<'a, T>
+where
+    T: Theme<DrawPipe>,
+    T::Window: kas_theme::Window,
+{
+    #[inline]
+    fn add_popup(&mut self, popup: kas::Popup) -> WindowId {
+        self.mock.add_popup(popup)
+    }
+
+    fn add_window(&mut self, widget: Box<dyn kas::Window>) -> WindowId {
+        warn!("Snapshot: add_window is not supported during off-screen rendering");
+        self.mock.add_window(widget)
+    }
+
+    #[inline]
+    fn close_window(&mut self, id: WindowId) {
+        self.mock.close_window(id);
+    }
+
+    #[inline]
+    fn trigger_update(&mut self, handle: UpdateHandle, payload: u64) {
+        self.mock.trigger_update(handle, payload);
+    }
+
+    #[inline]
+    fn get_clipboard(&mut self) -> Option<String> {
+        self.mock.get_clipboard()
+    }
+
+    #[inline]
+    fn set_clipboard<'c>(&mut self, content: Cow<'c, str>) {
+        self.mock.set_clipboard(content);
+    }
+
+    fn adjust_theme(&mut self, f: &mut dyn FnMut(&mut dyn ThemeApi) -> ThemeAction) {
+        match f(&mut *self.theme) {
+            ThemeAction::None | ThemeAction::RedrawAll => (),
+            ThemeAction::ThemeResize => {
+                self.theme
+                    .update_window(self.theme_window, self.scale_factor);
+                self.resize = true;
+            }
+        }
+    }
+
+    fn size_handle(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
+        use kas_theme::Window;
+        let mut size_handle = unsafe { self.theme_window.size_handle() };
+        f(&mut size_handle);
+    }
+
+    #[inline]
+    fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        self.mock.set_cursor_icon(icon);
+    }
+
+    #[inline]
+    fn set_ime_cursor_area(&mut self, rect: Rect) {
+        self.mock.set_ime_cursor_area(rect);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kas::widget::{Separator, Window};
+
+    #[test]
+    fn write_png() {
+        let mut snapshot = Snapshot::new(kas_theme::FlatTheme::new());
+        let mut window = Window::new("Separator", Separator::new());
+        let image = snapshot
+            .render(&mut window, Some(Size(24, 16)), 1.0)
+            .unwrap();
+        assert_eq!(image.size(), Size(24, 16));
+        assert_eq!(image.pixels().len(), 4 * 24 * 16);
+
+        let mut buf = vec![];
+        image.write_png(&mut buf).unwrap();
+        let decoder = png::Decoder::new(&buf[..]);
+        let (info, mut reader) = decoder.read_info().unwrap();
+        assert_eq!((info.width, info.height), (24, 16));
+        assert_eq!(info.color_type, png::ColorType::RGBA);
+        let mut pixels = vec![0; info.buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(pixels, image.pixels());
+    }
+}
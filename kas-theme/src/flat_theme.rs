@@ -188,6 +188,29 @@ impl<'a, D: Draw + DrawRounded> DrawHandle<'a, D> {
         inner2
     }
 
+    /// Draw the background of selected text
+    fn draw_selection_bg(
+        &mut self,
+        pos: Vec2,
+        bounds: Vec2,
+        offset: Vec2,
+        text: &TextDisplay,
+        range: Range<usize>,
+    ) {
+        for (p1, p2) in &text.highlight_lines(range) {
+            let mut p1 = Vec2::from(*p1) - offset;
+            let mut p2 = Vec2::from(*p2) - offset;
+            if !p2.gt(Vec2::ZERO) || !p1.lt(bounds) {
+                continue;
+            }
+            p1 = p1.max(Vec2::ZERO);
+            p2 = p2.min(bounds);
+
+            let quad = Quad::with_coords(pos + p1, pos + p2);
+            self.draw.rect(self.pass, quad, self.cols.text_sel_bg);
+        }
+    }
+
     /// Draw a handle (for slider, scrollbar)
     fn draw_handle(&mut self, rect: Rect, state: InputState) {
         let outer = Quad::from(rect + self.offset);
@@ -332,19 +355,7 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
         let offset = Vec2::from(offset);
         let col = self.cols.text_class(class);
 
-        // Draw background:
-        for (p1, p2) in &text.highlight_lines(range.clone()) {
-            let mut p1 = Vec2::from(*p1) - offset;
-            let mut p2 = Vec2::from(*p2) - offset;
-            if !p2.gt(Vec2::ZERO) || !p1.lt(bounds) {
-                continue;
-            }
-            p1 = p1.max(Vec2::ZERO);
-            p2 = p2.min(bounds);
-
-            let quad = Quad::with_coords(pos + p1, pos + p2);
-            self.draw.rect(self.pass, quad, self.cols.text_sel_bg);
-        }
+        self.draw_selection_bg(pos, bounds, offset, text, range.clone());
 
         let effects = [
             Effect {
@@ -367,6 +378,51 @@ impl<'a, D: Draw + DrawRounded + DrawText> draw::DrawHandle for DrawHandle<'a, D
             .text_effects(self.pass, pos, bounds, offset, text, &effects);
     }
 
+    fn text_offset_effects(
+        &mut self,
+        pos: Coord,
+        bounds: Vec2,
+        offset: Offset,
+        text: &TextDisplay,
+        range: Range<usize>,
+        effects: &[Effect<()>],
+        class: TextClass,
+    ) {
+        let pos = Vec2::from(pos + self.offset);
+        let offset = Vec2::from(offset);
+        let col = self.cols.text_class(class);
+
+        if range.start >= range.end {
+            self.draw
+                .text_col_effects(self.pass, pos, bounds, offset, text, col, effects);
+            return;
+        }
+
+        self.draw_selection_bg(pos, bounds, offset, text, range.clone());
+
+        // Merge effects with selection colours
+        let (sel_start, sel_end): (u32, u32) = (range.start.cast(), range.end.cast());
+        let mut starts: Vec<u32> = effects.iter().map(|e| e.start).collect();
+        starts.extend_from_slice(&[0, sel_start, sel_end]);
+        starts.sort_unstable();
+        starts.dedup();
+        let effects: Vec<_> = (starts.into_iter())
+            .map(|start| {
+                let flags = (effects.iter().rev())
+                    .find(|e| e.start <= start)
+                    .map(|e| e.flags)
+                    .unwrap_or_default();
+                let aux = match sel_start <= start && start < sel_end {
+                    true => self.cols.text_sel,
+                    false => col,
+                };
+                Effect { start, flags, aux }
+            })
+            .collect();
+        self.draw
+            .text_effects(self.pass, pos, bounds, offset, text, &effects);
+    }
+
     fn edit_marker(
         &mut self,
         pos: Coord,
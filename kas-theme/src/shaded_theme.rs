@@ -15,7 +15,7 @@ use kas::draw::{
     Pass, SizeHandle, TextClass,
 };
 use kas::geom::*;
-use kas::text::{AccelString, Text, TextApi, TextDisplay};
+use kas::text::{AccelString, Effect, Text, TextApi, TextDisplay};
 use kas::{ThemeAction, ThemeApi};
 
 /// A theme using simple shading to give apparent depth to elements
@@ -325,6 +325,20 @@ where
             .text_selected_range(pos, bounds, offset, text, range, class);
     }
 
+    fn text_offset_effects(
+        &mut self,
+        pos: Coord,
+        bounds: Vec2,
+        offset: Offset,
+        text: &TextDisplay,
+        range: Range<usize>,
+        effects: &[Effect<()>],
+        class: TextClass,
+    ) {
+        self.as_flat()
+            .text_offset_effects(pos, bounds, offset, text, range, effects, class);
+    }
+
     fn edit_marker(
         &mut self,
         pos: Coord,
@@ -0,0 +1,84 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Drag-and-drop example
+//!
+//! Rows may be reordered within each list or moved to the other list.
+
+use kas::dir::Down;
+use kas::prelude::*;
+use kas::widget::view::{ListDrag, ListMsg, ListView};
+use kas::widget::{Label, ScrollBars, Window};
+
+type List = ScrollBars<ListView<Down, Box<[String]>>>;
+
+fn list(items: &[&str]) -> List {
+    let data: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+    ScrollBars::new(ListView::new(data.into_boxed_slice()).with_drag_and_drop(true))
+}
+
+/// Apply a drag-and-drop message to the data of `list`
+fn update(list: &mut List, mgr: &mut Manager, msg: ListMsg<usize, VoidMsg>) {
+    let mut data = list.data().to_vec();
+    match msg {
+        ListMsg::Reorder(key, index) => {
+            let item = data.remove(key);
+            let index = if index > key { index - 1 } else { index };
+            data.insert(index, item);
+        }
+        ListMsg::Drop(payload, index) => {
+            if let Some(drag) = payload.get::<ListDrag<usize, String>>() {
+                data.insert(index, drag.item.clone());
+            }
+        }
+        ListMsg::DraggedOut(key) => {
+            data.remove(key);
+        }
+        _ => return,
+    }
+    *list.data_mut() = data.into_boxed_slice();
+    list.update_view(mgr);
+}
+
+fn main() -> Result<(), kas_wgpu::Error> {
+    env_logger::init();
+
+    let window = Window::new(
+        "Drag and drop",
+        make_widget! {
+            #[layout(grid)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget(row=0, col=0)] _ = Label::new("To do"),
+                #[widget(row=0, col=1)] _ = Label::new("Done"),
+                #[widget(row=1, col=0, handler = update_todo)] todo: List =
+                    list(&["Write code", "Test code", "Write docs", "Release"]),
+                #[widget(row=1, col=1, handler = update_done)] done: List =
+                    list(&["Plan features"]),
+            }
+            impl {
+                fn update_todo(
+                    &mut self,
+                    mgr: &mut Manager,
+                    msg: ListMsg<usize, VoidMsg>,
+                ) -> Response<VoidMsg> {
+                    update(&mut self.todo, mgr, msg);
+                    Response::None
+                }
+                fn update_done(
+                    &mut self,
+                    mgr: &mut Manager,
+                    msg: ListMsg<usize, VoidMsg>,
+                ) -> Response<VoidMsg> {
+                    update(&mut self.done, mgr, msg);
+                    Response::None
+                }
+            }
+        },
+    );
+
+    let theme = kas_theme::ShadedTheme::new();
+    kas_wgpu::Toolkit::new(theme)?.with(window)?.run()
+}
@@ -257,7 +257,6 @@ fn main() -> Result<(), kas_wgpu::Error> {
                 }
                 fn set_radio(&mut self, mgr: &mut Manager, msg: ListMsg<usize, EntryMsg>) -> Response<VoidMsg> {
                     match msg {
-                        ListMsg::Select(_) | ListMsg::Deselect(_) => (),
                         ListMsg::Child(n, EntryMsg::Select) => {
                             let text = self.list.data_mut().set_active(n);
                             *mgr |= self.display.set_string(text);
@@ -267,6 +266,7 @@ fn main() -> Result<(), kas_wgpu::Error> {
                                 *mgr |= self.display.set_string(text);
                             }
                         }
+                        _ => (),
                     }
                     Response::None
                 }
@@ -0,0 +1,72 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! File drop example
+//!
+//! Drag files from a file manager onto the drop area.
+
+use kas::event::{self, VoidMsg};
+use kas::prelude::*;
+use kas::widget::{Label, StringLabel, Window};
+
+const PROMPT: &str = "Drop a file here to open";
+
+#[derive(Clone, Debug, Widget)]
+#[handler(handle = noauto)]
+#[widget(config = noauto)]
+#[layout(single)]
+struct DropArea {
+    #[widget_core]
+    core: CoreData,
+    #[widget]
+    label: StringLabel,
+}
+
+impl WidgetConfig for DropArea {
+    fn accept_files(&self) -> bool {
+        true
+    }
+}
+
+impl event::Handler for DropArea {
+    type Msg = VoidMsg;
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<VoidMsg> {
+        let text = match event {
+            Event::FileHover(path) => format!("Open {}?", path.display()),
+            Event::FileHoverCancelled => PROMPT.to_string(),
+            Event::FileDrop(path) => match std::fs::metadata(&path) {
+                Ok(meta) => format!("Opened {} ({} bytes)", path.display(), meta.len()),
+                Err(err) => format!("Failed to open {}: {}", path.display(), err),
+            },
+            _ => return Response::Unhandled,
+        };
+        *mgr |= self.label.set_string(text);
+        Response::None
+    }
+}
+
+fn main() -> Result<(), kas_wgpu::Error> {
+    env_logger::init();
+
+    let drop_area = DropArea {
+        core: Default::default(),
+        label: Label::new(PROMPT.to_string()),
+    };
+    let window = Window::new(
+        "File drop",
+        make_widget! {
+            #[layout(down)]
+            #[handler(msg = VoidMsg)]
+            struct {
+                #[widget] _ = Label::new("Files dropped elsewhere are ignored"),
+                #[widget] _ = drop_area,
+            }
+        },
+    );
+
+    let theme = kas_theme::ShadedTheme::new();
+    kas_wgpu::Toolkit::new(theme)?.with(window)?.run()
+}
@@ -186,7 +186,10 @@ fn main() -> Result<(), kas_wgpu::Error> {
             #[widget(row=1, col=0)] _ = Label::new("EditBox"),
             #[widget(row=1, col=1)] _ = EditBox::new("edit me").with_guard(Guard),
             #[widget(row=2, col=0)] _ = Label::new("TextButton"),
-            #[widget(row=2, col=1)] _ = TextButton::new_msg("&Press me", Item::Button),
+            #[widget(row=2, col=1)] _ = WithTooltip::new(
+                TextButton::new_msg("&Press me", Item::Button),
+                "A button with a tooltip",
+            ),
             #[widget(row=3, col=0)] _ = Label::new("CheckBox"),
             #[widget(row=3, col=1)] _ = CheckBox::new("&Check me")
                 .with_state(true)
@@ -12,7 +12,7 @@ use wgpu::util::DeviceExt;
 use wgpu::{include_spirv, Buffer, ShaderModule};
 
 use kas::draw::Pass;
-use kas::event::{self, Command};
+use kas::event::{self, Command, Gesture, GestureRecogniser, GestureSet};
 use kas::geom::{DVec2, Vec2, Vec3};
 use kas::prelude::*;
 use kas::widget::{Label, ReserveP, Slider, Window};
@@ -383,6 +383,7 @@ struct Mandlebrot {
     view_alpha: f64,
     rel_width: f32,
     iter: i32,
+    gestures: GestureRecogniser,
 }
 
 impl Mandlebrot {
@@ -395,6 +396,9 @@ impl Mandlebrot {
             view_alpha: 0.0,
             rel_width: 0.0,
             iter: 64,
+            gestures: GestureRecogniser::new(
+                GestureSet::ZOOM | GestureSet::ROTATE | GestureSet::SCROLL | GestureSet::DOUBLE_TAP,
+            ),
         }
     }
 
@@ -403,6 +407,41 @@ impl Mandlebrot {
         self.delta = DVec2(-0.5, 0.0);
     }
 
+    /// Adjust the view for the screen-space transform `p → alpha * p + delta`
+    fn pan(&mut self, alpha: DVec2, delta: DVec2) {
+        // Our full transform (from screen coordinates to world coordinates) is:
+        // f(p) = α_w * α_v * p + α_w * δ_v + δ_w
+        // where _w indicate world transforms (self.alpha, self.delta)
+        // and _v indicate view transforms (see notes in PipeWindow::invoke).
+        //
+        // To adjust the world offset (in reverse), we use the following formulae:
+        // α_w' = (1/α) * α_w
+        // δ_w' = δ_w - α_w' * α_v * δ + (α_w - α_w') δ_v
+        // where x' is the "new x".
+        let new_alpha = self.alpha.complex_div(alpha);
+        self.delta = self.delta - new_alpha.complex_mul(delta) * self.view_alpha
+            + (self.alpha - new_alpha).complex_mul(self.view_delta);
+        self.alpha = new_alpha;
+    }
+
+    fn apply_gesture(&mut self, gesture: Gesture) {
+        // Express each gesture as a transform p → alpha * p + delta
+        let (alpha, delta) = match gesture {
+            Gesture::Zoom { scale, center } => (DVec2(scale, 0.0), center * (1.0 - scale)),
+            Gesture::Rotate { angle, center } => {
+                let alpha = DVec2(angle.cos(), angle.sin());
+                (alpha, center - alpha.complex_mul(center))
+            }
+            Gesture::Scroll(delta) => (DVec2(1.0, 0.0), delta),
+            Gesture::DoubleTap { coord, .. } => {
+                // Zoom in about the tap
+                (DVec2(2.0, 0.0), -DVec2::from(coord))
+            }
+            Gesture::LongPress { .. } => return,
+        };
+        self.pan(alpha, delta);
+    }
+
     fn loc(&self) -> String {
         let op = if self.delta.1 < 0.0 { "−" } else { "+" };
         format!(
@@ -488,35 +527,20 @@ impl event::Handler for Mandlebrot {
                 mgr.redraw(self.id());
                 Response::Msg(())
             }
-            Event::Pan { alpha, delta } => {
-                // Our full transform (from screen coordinates to world coordinates) is:
-                // f(p) = α_w * α_v * p + α_w * δ_v + δ_w
-                // where _w indicate world transforms (self.alpha, self.delta)
-                // and _v indicate view transforms (see notes in PipeWindow::invoke).
-                //
-                // To adjust the world offset (in reverse), we use the following formulae:
-                // α_w' = (1/α) * α_w
-                // δ_w' = δ_w - α_w' * α_v * δ + (α_w - α_w') δ_v
-                // where x' is the "new x".
-                let new_alpha = self.alpha.complex_div(alpha.into());
-                self.delta = self.delta - new_alpha.complex_mul(delta.into()) * self.view_alpha
-                    + (self.alpha - new_alpha).complex_mul(self.view_delta);
-                self.alpha = new_alpha;
-
+            event => {
+                let id = self.id();
+                let mut gestures = vec![];
+                let on_gesture = |_: &mut Manager, gesture| gestures.push(gesture);
+                let response = self.gestures.handle(mgr, id, event, on_gesture);
+                if gestures.is_empty() {
+                    return response.void_into();
+                }
+                for gesture in gestures {
+                    self.apply_gesture(gesture);
+                }
                 mgr.redraw(self.id());
                 Response::Msg(())
             }
-            Event::PressStart { source, coord, .. } => {
-                mgr.request_grab(
-                    self.id(),
-                    source,
-                    coord,
-                    event::GrabMode::PanFull,
-                    Some(event::CursorIcon::Grabbing),
-                );
-                Response::None
-            }
-            _ => Response::None,
         }
     }
 }
@@ -20,6 +20,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 Markdown supports *italic* and **bold** highlighting, ***both***, even with*in* w**o**rds.
 As an extension, it also supports ~~strikethrough~~.
 
+Links like [the KAS repository](https://github.com/kas-gui/kas) may be clicked
+or selected with the Tab key.
+
 Inline `code = 2;` is supported. Code blocks are supported:
 ```
 let x = 1;
@@ -45,21 +48,26 @@ It also supports lists:
             struct {
                 #[widget(row=0, col=0, rspan=2)] editor: EditBox =
                     EditBox::new(doc).multi_line(true),
-                #[widget(row=0, col=1)] label: ScrollBarRegion<Label<Markdown>> =
-                    ScrollBarRegion::new2(Label::new(Markdown::new(doc)?)),
+                #[widget(row=0, col=1, handler=link)] label: ScrollBarRegion<Label<Markdown, String>> =
+                    ScrollBarRegion::new2(Label::from_markdown(doc)?
+                        .on_link(|_, target| Some(target.to_string()))),
                 #[widget(row=1, col=1, handler=update)] _ = TextButton::new_msg("&Update", ()),
             }
             impl {
                 fn update(&mut self, mgr: &mut Manager, _: ()) -> Response<VoidMsg> {
-                    let text = match Markdown::new(self.editor.get_str()) {
-                        Ok(text) => text,
+                    let action = match self.label.set_markdown(self.editor.get_str()) {
+                        Ok(action) => action,
                         Err(err) => {
                             let string = format!("```\n{}\n```", err);
-                            Markdown::new(&string).unwrap()
+                            self.label.set_markdown(&string).unwrap()
                         }
                     };
                     // TODO: this should update the size requirements of the inner area
-                    *mgr |= self.label.set_text(text);
+                    *mgr |= action;
+                    Response::None
+                }
+                fn link(&mut self, _: &mut Manager, target: String) -> Response<VoidMsg> {
+                    println!("Link activated: {}", target);
                     Response::None
                 }
             }
@@ -6,7 +6,7 @@
 //! Options
 
 use super::Error;
-use log::warn;
+use log::{info, warn};
 use std::env::var;
 use std::path::PathBuf;
 pub use wgpu::{BackendBit, PowerPreference};
@@ -20,6 +20,8 @@ pub enum ConfigMode {
     Read,
     /// Use default config and write out
     WriteDefault,
+    /// Read config, merging over the platform defaults
+    Merge,
 }
 
 /// Shell options
@@ -67,6 +69,8 @@ impl Options {
     /// -   `Read` (default): read-only
     /// -   `WriteDefault`: generate platform-default configuration, and write
     ///     it to the config path, overwriting any existing config
+    /// -   `Merge`: read-only; bindings from the config are applied over
+    ///     platform-default configuration (see [`kas::event::Config::merge_path`])
     ///
     /// Note: in the future, the default will likely change to a read-write mode,
     /// allowing changes to be written out.
@@ -102,6 +106,7 @@ impl Options {
             options.config_mode = match v.as_str() {
                 "READ" => ConfigMode::Read,
                 "WRITEDEFAULT" => ConfigMode::WriteDefault,
+                "MERGE" => ConfigMode::Merge,
                 other => {
                     warn!("Unexpected environment value: KAS_CONFIG_MODE={}", other);
                     options.config_mode
@@ -168,6 +173,17 @@ impl Options {
                     config.write_path(&self.config_path, Default::default())?;
                     Ok(config)
                 }
+                ConfigMode::Merge => {
+                    let mut config: kas::event::Config = Default::default();
+                    let conflicts = config.merge_path(&self.config_path, Default::default())?;
+                    for c in conflicts {
+                        info!(
+                            "Config: {:?}+{:?} rebound from {:?} to {:?}",
+                            c.modifiers, c.vkey, c.old, c.new
+                        );
+                    }
+                    Ok(config)
+                }
             }
         } else {
             Ok(Default::default())
@@ -11,11 +11,11 @@ use std::time::Instant;
 use kas::conv::Cast;
 use kas::draw::SizeHandle;
 use kas::event::{CursorIcon, ManagerState, UpdateHandle};
-use kas::geom::{Coord, Rect, Size};
+use kas::geom::{Coord, Offset, Rect, Size};
 use kas::layout::SolveCache;
 use kas::{ThemeAction, ThemeApi, TkAction, WindowId};
 use kas_theme::Theme;
-use winit::dpi::PhysicalSize;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::error::OsError;
 use winit::event::WindowEvent;
 use winit::event_loop::EventLoopWindowTarget;
@@ -520,4 +520,11 @@ where
     fn set_cursor_icon(&mut self, icon: CursorIcon) {
         self.window.set_cursor_icon(icon);
     }
+
+    fn set_ime_cursor_area(&mut self, rect: Rect) {
+        // winit only supports positioning the candidate window
+        let pos = rect.pos + Offset(0, rect.size.1);
+        self.window
+            .set_ime_position(PhysicalPosition::new(pos.0, pos.1));
+    }
 }
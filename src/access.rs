@@ -0,0 +1,539 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Accessibility
+//!
+//! Widgets describe themselves to assistive technologies (e.g. screen readers)
+//! via the [`Accessible`] trait: a [`Role`], a label, a value and a set of
+//! supported [`AccessActions`]. From these (plus state tracked by the event
+//! manager, such as focus), an [`AccessTree`] snapshot of a window may be
+//! built.
+//!
+//! The tree is intended for consumption by a platform adapter (e.g. AT-SPI or
+//! AccessKit), which should rebuild or update its copy after each event-handling
+//! cycle via [`AccessTree::update`]. This crate does not include such an
+//! adapter. Via [`std::fmt::Display`], the tree may be dumped as text, e.g. for
+//! testing.
+//!
+//! Only widgets with a role other than [`Role::Generic`] appear in the tree;
+//! other widgets (e.g. layout containers and frames) are transparent, with
+//! their children attached to the nearest reported ancestor. The root of the
+//! tree is always reported.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::event::ManagerState;
+use crate::geom::{Offset, Rect};
+use crate::{TkAction, WidgetConfig, WidgetId, WidgetPath};
+
+// for doc use
+#[allow(unused)]
+use crate::{event::Event, event::Manager, Accessible};
+
+/// The role of a widget
+///
+/// This describes what kind of UI element a widget is. Widgets with role
+/// [`Role::Generic`] are not reported in an [`AccessTree`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Role {
+    /// No specific role (e.g. a layout container); not reported
+    Generic,
+    /// A window (the root)
+    Window,
+    /// Static text
+    Label,
+    /// A push-button
+    Button,
+    /// A check-box (see [`AccessNode::checked`])
+    CheckBox,
+    /// A radio button (see [`AccessNode::checked`])
+    RadioButton,
+    /// Editable text; the value is the text
+    TextInput,
+    /// A slider; the value is the slider's value
+    Slider,
+    /// A scroll bar; the value is the scroll offset
+    ScrollBar,
+    /// A progress bar; the value is the fraction complete
+    ProgressBar,
+    /// A drop-down list; the value is the selected entry
+    ComboBox,
+    /// A menu bar
+    MenuBar,
+    /// A menu (list of menu items)
+    Menu,
+    /// An entry in a menu
+    MenuItem,
+    /// A menu entry which may be toggled (see [`AccessNode::checked`])
+    MenuItemCheckBox,
+    /// A separator
+    Separator,
+}
+
+impl Role {
+    /// Whether children of widgets with this role are presentational
+    ///
+    /// Such widgets are reported as a single node: descendants are omitted
+    /// from the [`AccessTree`] (for example, the label of a check-box is
+    /// reported as the check-box's label, not as a separate node).
+    pub fn children_presentational(self) -> bool {
+        match self {
+            Role::Label
+            | Role::Button
+            | Role::CheckBox
+            | Role::RadioButton
+            | Role::TextInput
+            | Role::Slider
+            | Role::ScrollBar
+            | Role::ProgressBar
+            | Role::MenuItemCheckBox
+            | Role::Separator => true,
+            _ => false,
+        }
+    }
+}
+
+bitflags! {
+    /// State of a node in an [`AccessTree`]
+    #[derive(Default)]
+    pub struct AccessState: u8 {
+        /// The widget (or an ancestor) is disabled
+        const DISABLED = 1 << 0;
+        /// The widget supports keyboard focus
+        const FOCUSABLE = 1 << 1;
+        /// The widget has keyboard (navigation or character) focus
+        const FOCUSED = 1 << 2;
+    }
+}
+
+bitflags! {
+    /// Actions supported by a widget
+    ///
+    /// An adapter may perform these actions on behalf of an assistive
+    /// technology through the event manager. Actions should be directed to
+    /// the node's [`AccessNode::target`].
+    #[derive(Default)]
+    pub struct AccessActions: u8 {
+        /// Give the widget focus via [`Manager::set_nav_focus`]
+        ///
+        /// This is added automatically for focusable widgets which are not
+        /// disabled.
+        const FOCUS = 1 << 0;
+        /// Activate the widget by sending [`Event::Activate`]
+        const CLICK = 1 << 1;
+    }
+}
+
+/// A node of an [`AccessTree`], describing one widget
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessNode {
+    /// Widget identifier
+    ///
+    /// This is only valid until the next reconfigure; see [`AccessTree::update`].
+    pub id: WidgetId,
+    /// Widget path, from the window root
+    pub path: WidgetPath,
+    /// Target of actions and focus
+    ///
+    /// This is usually the same as `id`, but for widgets with presentational
+    /// children (see [`Role::children_presentational`]) which do not support
+    /// focus themselves, this is the first focusable descendant (e.g. the box
+    /// of a labelled check-box).
+    pub target: WidgetId,
+    /// Role
+    pub role: Role,
+    /// Accessible name, e.g. the text of a button
+    pub label: Option<String>,
+    /// Value, e.g. the contents of a text input
+    pub value: Option<String>,
+    /// Checked state of check-boxes, radio buttons and toggles
+    pub checked: Option<bool>,
+    /// State
+    pub state: AccessState,
+    /// Supported actions
+    pub actions: AccessActions,
+    /// Region, in window coordinates
+    pub rect: Rect,
+    /// Reported children, in order
+    pub children: Vec<WidgetId>,
+}
+
+impl AccessNode {
+    fn new(
+        mgr: &ManagerState,
+        widget: &dyn WidgetConfig,
+        path: &WidgetPath,
+        offset: Offset,
+        disabled: bool,
+    ) -> Self {
+        let id = widget.id();
+        let role = widget.access_role();
+        let target = match widget.key_nav() || !role.children_presentational() {
+            true => widget,
+            false => find_focusable(widget).unwrap_or(widget),
+        };
+
+        let mut state = AccessState::empty();
+        let mut actions = widget.access_actions();
+        if disabled {
+            state |= AccessState::DISABLED;
+        }
+        if target.key_nav() {
+            state |= AccessState::FOCUSABLE;
+            if !disabled {
+                actions |= AccessActions::FOCUS;
+            }
+        }
+        if mgr.nav_focus(target.id()) || mgr.char_focus(target.id()).0 {
+            state |= AccessState::FOCUSED;
+        }
+        AccessNode {
+            id,
+            path: path.clone(),
+            target: target.id(),
+            role,
+            label: widget.access_label(),
+            value: widget.access_value(),
+            checked: widget.access_checked(),
+            state,
+            actions,
+            rect: widget.rect() - offset,
+            children: vec![],
+        }
+    }
+}
+
+// Find the first focusable descendant
+fn find_focusable(widget: &dyn WidgetConfig) -> Option<&dyn WidgetConfig> {
+    (0..widget.num_children())
+        .filter_map(|i| widget.get_child(i))
+        .find_map(|w| match w.key_nav() {
+            true => Some(w),
+            false => find_focusable(w),
+        })
+}
+
+/// A change to an [`AccessTree`]
+///
+/// See [`AccessTree::update`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessUpdate {
+    /// True if all identifiers were reassigned (reconfigure)
+    ///
+    /// In this case the adapter should replace its whole tree.
+    pub full: bool,
+    /// New and changed nodes
+    pub nodes: Vec<AccessNode>,
+    /// Identifiers of removed nodes
+    pub removed: Vec<WidgetId>,
+    /// The focussed node, if changed
+    pub focus: Option<Option<WidgetId>>,
+}
+
+impl AccessUpdate {
+    /// True if nothing changed
+    pub fn is_empty(&self) -> bool {
+        !self.full && self.nodes.is_empty() && self.removed.is_empty() && self.focus.is_none()
+    }
+}
+
+/// A snapshot of the accessibility tree of a window
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessTree {
+    root: WidgetId,
+    focus: Option<WidgetId>,
+    nodes: HashMap<WidgetId, AccessNode>,
+}
+
+impl AccessTree {
+    /// Build a snapshot
+    ///
+    /// The `widget` should be the window root. This requires that the widget
+    /// tree has already been configured by [`ManagerState::configure`].
+    pub fn new(mgr: &ManagerState, widget: &dyn WidgetConfig) -> Self {
+        let mut tree = AccessTree {
+            root: widget.id(),
+            focus: None,
+            nodes: HashMap::new(),
+        };
+        let path = WidgetPath::root();
+        let disabled = widget.is_disabled();
+        let mut node = AccessNode::new(mgr, widget, &path, Offset::ZERO, disabled);
+        let mut children = vec![];
+        tree.add_children(mgr, widget, path, Offset::ZERO, disabled, &mut children);
+        node.children = children;
+        tree.insert(node);
+        tree
+    }
+
+    fn insert(&mut self, node: AccessNode) {
+        if node.state.contains(AccessState::FOCUSED) {
+            self.focus = Some(node.id);
+        }
+        self.nodes.insert(node.id, node);
+    }
+
+    // Add reported descendants of widget, pushing the ids of top-most reported
+    // descendants to children.
+    fn add_children(
+        &mut self,
+        mgr: &ManagerState,
+        widget: &dyn WidgetConfig,
+        path: WidgetPath,
+        offset: Offset,
+        disabled: bool,
+        children: &mut Vec<WidgetId>,
+    ) {
+        for index in 0..widget.num_children() {
+            if widget.access_child_hidden(index) {
+                continue;
+            }
+            let child = match widget.get_child(index) {
+                Some(child) => child,
+                None => continue,
+            };
+            let path = path.child(widget.child_segment(index));
+            let offset = offset + widget.translation(index);
+            let disabled = disabled || child.is_disabled();
+
+            if child.access_role() == Role::Generic {
+                self.add_children(mgr, child, path, offset, disabled, children);
+                continue;
+            }
+
+            let mut node = AccessNode::new(mgr, child, &path, offset, disabled);
+            if !node.role.children_presentational() {
+                let mut grandchildren = vec![];
+                self.add_children(mgr, child, path, offset, disabled, &mut grandchildren);
+                node.children = grandchildren;
+            }
+            children.push(node.id);
+            self.insert(node);
+        }
+    }
+
+    /// Update the snapshot after an event-handling cycle
+    ///
+    /// The `action` should be the [`TkAction`] resulting from the cycle (e.g.
+    /// as returned by [`ManagerState::update`]); the snapshot is only rebuilt
+    /// when some action was requested. Returns the changes.
+    ///
+    /// Widget identifiers are reassigned on [`TkAction::RECONFIGURE`], in
+    /// which case [`AccessUpdate::full`] is set and all nodes are reported.
+    pub fn update(
+        &mut self,
+        mgr: &ManagerState,
+        widget: &dyn WidgetConfig,
+        action: TkAction,
+    ) -> AccessUpdate {
+        let mut update = AccessUpdate::default();
+        if action.is_empty() {
+            return update;
+        }
+
+        let new = AccessTree::new(mgr, widget);
+        if action.contains(TkAction::RECONFIGURE) {
+            update.full = true;
+            update.nodes = new.nodes.values().cloned().collect();
+            update.focus = Some(new.focus);
+        } else {
+            for (id, node) in &new.nodes {
+                if self.nodes.get(id) != Some(node) {
+                    update.nodes.push(node.clone());
+                }
+            }
+            for id in self.nodes.keys() {
+                if !new.nodes.contains_key(id) {
+                    update.removed.push(*id);
+                }
+            }
+            if new.focus != self.focus {
+                update.focus = Some(new.focus);
+            }
+        }
+        update.nodes.sort_by_key(|node| node.path.clone());
+        update.removed.sort();
+
+        *self = new;
+        update
+    }
+
+    /// Get the root node
+    pub fn root(&self) -> &AccessNode {
+        &self.nodes[&self.root]
+    }
+
+    /// Get the focussed node, if any
+    pub fn focus(&self) -> Option<&AccessNode> {
+        self.focus.and_then(|id| self.nodes.get(&id))
+    }
+
+    /// Get a node by identifier
+    pub fn get(&self, id: WidgetId) -> Option<&AccessNode> {
+        self.nodes.get(&id)
+    }
+
+    /// Number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Iterate over all nodes, depth-first, parents before children
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &AccessNode)> {
+        let mut stack = vec![(0, self.root)];
+        std::iter::from_fn(move || {
+            let (depth, id) = stack.pop()?;
+            let node = &self.nodes[&id];
+            for child in node.children.iter().rev() {
+                stack.push((depth + 1, *child));
+            }
+            Some((depth, node))
+        })
+    }
+}
+
+impl fmt::Display for AccessNode {
+    /// Format as a single line, e.g. `Button "Save" [focused]`
+    ///
+    /// Identifiers and coordinates are omitted for stability.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.role)?;
+        if let Some(ref label) = self.label {
+            write!(f, " {:?}", label)?;
+        }
+        if let Some(ref value) = self.value {
+            write!(f, " value={:?}", value)?;
+        }
+        let mut flags = vec![];
+        match self.checked {
+            Some(true) => flags.push("checked"),
+            Some(false) => flags.push("unchecked"),
+            None => (),
+        }
+        if self.state.contains(AccessState::DISABLED) {
+            flags.push("disabled");
+        }
+        if self.state.contains(AccessState::FOCUSED) {
+            flags.push("focused");
+        }
+        if !flags.is_empty() {
+            write!(f, " [{}]", flags.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for AccessTree {
+    /// Format as an indented list of nodes, one per line
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (depth, node) in self.iter() {
+            writeln!(f, "{:indent$}{}", "", node, indent = 2 * depth)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AccessTree, AccessUpdate, Role};
+    use crate::dir::Right;
+    use crate::prelude::*;
+    use crate::testing::Harness;
+    use crate::widget::{EditBox, Label, Slider};
+
+    #[derive(Debug, Widget)]
+    #[layout(column)]
+    struct Form {
+        #[widget_core]
+        core: CoreData,
+        #[layout_data]
+        layout_data: <Self as LayoutData>::Data,
+        #[widget]
+        label: Label<&'static str>,
+        #[widget]
+        edit: EditBox,
+        #[widget(handler = slide)]
+        slider: Slider<i32, Right>,
+    }
+
+    impl Form {
+        fn slide(&mut self, _: &mut Manager, _: i32) -> Response<VoidMsg> {
+            Response::None
+        }
+    }
+
+    fn form() -> Form {
+        Form {
+            core: Default::default(),
+            layout_data: Default::default(),
+            label: Label::new("Name"),
+            edit: EditBox::new("abc"),
+            slider: Slider::new(0, 10, 1).with_value(3),
+        }
+    }
+
+    #[test]
+    fn dump() {
+        let mut harness = Harness::new(form());
+        let expected = r#"Window "Harness"
+  Label "Name"
+  TextInput value="abc"
+  Slider value="3"
+"#;
+        assert_eq!(harness.access_tree().to_string(), expected);
+
+        let rect = harness.widget().edit.rect();
+        harness.click(rect.pos + rect.size / 2);
+        let tree = harness.access_tree().to_string();
+        assert!(
+            tree.contains("\n  TextInput value=\"abc\" [focused]\n"),
+            "{}",
+            tree
+        );
+    }
+
+    #[test]
+    fn update() {
+        let mut harness = Harness::new(form());
+        let mut tree = AccessTree::new(harness.state(), harness.widget());
+        let mut update = |harness: &mut Harness<Form>| {
+            let action = harness.take_action();
+            tree.update(harness.state(), harness.widget(), action)
+        };
+        let roles = |update: &AccessUpdate| -> Vec<Role> {
+            update.nodes.iter().map(|node| node.role).collect()
+        };
+
+        // Nothing is reported without an action
+        assert_eq!(update(&mut harness), AccessUpdate::default());
+
+        // Focus changes are reported, along with the focussed node
+        let rect = harness.widget().edit.rect();
+        harness.click(rect.pos + rect.size / 2);
+        let result = update(&mut harness);
+        assert_eq!(roles(&result), vec![Role::TextInput]);
+        assert_eq!(result.focus, Some(Some(result.nodes[0].id)));
+
+        // Only changed nodes are reported
+        harness.type_str("d");
+        let result = update(&mut harness);
+        assert_eq!(roles(&result), vec![Role::TextInput]);
+        assert_eq!(result.nodes[0].value.as_deref(), Some("abcd"));
+        assert_eq!(result.focus, None);
+
+        harness.with_manager(|mgr, form| *mgr |= form.slider.set_value(7));
+        let result = update(&mut harness);
+        assert_eq!(roles(&result), vec![Role::Slider]);
+        assert_eq!(result.nodes[0].value.as_deref(), Some("7"));
+
+        // All nodes are reported on reconfigure
+        harness.with_manager(|mgr, _| *mgr |= TkAction::RECONFIGURE);
+        let result = update(&mut harness);
+        assert!(result.full);
+        assert_eq!(result.nodes.len(), 4);
+        assert!(result.focus.is_some());
+    }
+}
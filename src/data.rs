@@ -10,8 +10,12 @@ use std::convert::TryFrom;
 use std::fmt;
 use std::num::NonZeroU32;
 use std::rc::Rc;
+use std::str::FromStr;
 use std::u32;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 use crate::geom::Rect;
 
 // for doc use
@@ -67,7 +71,8 @@ impl<T: 'static> Future<T> {
 ///
 /// Identifiers are assigned when configured and when re-configured
 /// (via [`kas::TkAction::RECONFIGURE`]). Since user-code is not notified of a
-/// re-configure, user-code should not store a `WidgetId`.
+/// re-configure, user-code should not store a `WidgetId`; use a [`WidgetPath`]
+/// instead.
 #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct WidgetId(NonZeroU32);
 
@@ -131,6 +136,188 @@ fn size_of_option_widget_id() {
     assert_eq!(size_of::<WidgetId>(), size_of::<Option<WidgetId>>());
 }
 
+/// Segment of a [`WidgetPath`]
+///
+/// Each segment identifies one child of a widget. Where possible, children
+/// are identified by name or key, which are unaffected by the insertion or
+/// removal of siblings; see [`kas::WidgetChildren::child_segment`].
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PathSegment {
+    /// Child index (see [`kas::WidgetChildren::get_child`])
+    Index(usize),
+    /// Child name (see [`kas::WidgetCore::name`])
+    Name(String),
+    /// Child key (see [`kas::WidgetChildren::child_key`])
+    Key(String),
+}
+
+impl From<usize> for PathSegment {
+    #[inline]
+    fn from(index: usize) -> Self {
+        PathSegment::Index(index)
+    }
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            PathSegment::Index(index) => write!(f, "{}", index),
+            PathSegment::Name(name) => write!(f, "@{}", name),
+            PathSegment::Key(key) => write!(f, "#{}", key),
+        }
+    }
+}
+
+impl FromStr for PathSegment {
+    type Err = ();
+    fn from_str(s: &str) -> Result<PathSegment, ()> {
+        if let Some(name) = s.strip_prefix('@') {
+            Ok(PathSegment::Name(name.to_string()))
+        } else if let Some(key) = s.strip_prefix('#') {
+            Ok(PathSegment::Key(key.to_string()))
+        } else {
+            s.parse().map(PathSegment::Index).map_err(|_| ())
+        }
+    }
+}
+
+/// Widget path
+///
+/// A path identifies a widget by a list of [`PathSegment`]s, each selecting
+/// one child, from the window root; the empty path is the root. Children are
+/// identified by name (see [`kas::WidgetCore::name`]) where this is unique
+/// among siblings, otherwise by key (see [`kas::WidgetChildren::child_key`],
+/// e.g. the data key of a [`kas::widget::view::ListView`] row), and otherwise
+/// by child index.
+///
+/// Unlike a [`WidgetId`], a path is unaffected by re-configure and by the
+/// insertion or removal of widgets elsewhere in the tree. Segments using a
+/// child index change when a widget is inserted or removed before the child
+/// within the same parent; named and keyed segments do not. Paths are thus
+/// suitable for storing references to widgets, for example in saved state or
+/// in tests.
+///
+/// Paths may be resolved via [`kas::WidgetChildren::find_path`] and found via
+/// [`kas::WidgetChildren::path_of`]. Paths are formatted (and parsed) as a list
+/// of segments each preceded by `/`, where names are prefixed with `@` and
+/// keys with `#`, e.g. `/0/@menu/#3`; the root is `/`. Names and keys
+/// containing `/` cannot be parsed.
+#[derive(Clone, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WidgetPath(Vec<PathSegment>);
+
+impl WidgetPath {
+    /// The path of the root widget
+    #[inline]
+    pub fn root() -> Self {
+        WidgetPath(vec![])
+    }
+
+    /// Construct from child indices
+    pub fn from_indices(indices: Vec<usize>) -> Self {
+        WidgetPath(indices.into_iter().map(PathSegment::Index).collect())
+    }
+
+    /// Construct from segments
+    #[inline]
+    pub fn from_segments(segments: Vec<PathSegment>) -> Self {
+        WidgetPath(segments)
+    }
+
+    /// Access segments
+    #[inline]
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    /// True if this is the path of the root widget
+    #[inline]
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the path of a child of this widget
+    pub fn child<S: Into<PathSegment>>(&self, segment: S) -> Self {
+        let mut path = self.clone();
+        path.push(segment);
+        path
+    }
+
+    /// Get the path of the parent widget, if any
+    pub fn parent(&self) -> Option<Self> {
+        match self.0.split_last() {
+            Some((_, rest)) => Some(WidgetPath(rest.to_vec())),
+            None => None,
+        }
+    }
+
+    /// Append a segment
+    #[inline]
+    pub fn push<S: Into<PathSegment>>(&mut self, segment: S) {
+        self.0.push(segment.into());
+    }
+
+    /// Remove and return the last segment
+    #[inline]
+    pub fn pop(&mut self) -> Option<PathSegment> {
+        self.0.pop()
+    }
+
+    /// Check whether the widget at `path` is a descendant of (or is) this widget
+    #[inline]
+    pub fn is_ancestor_of(&self, path: &WidgetPath) -> bool {
+        path.0.starts_with(&self.0)
+    }
+}
+
+impl fmt::Display for WidgetPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        if self.0.is_empty() {
+            return write!(f, "/");
+        }
+        for segment in &self.0 {
+            write!(f, "/{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for WidgetPath {
+    type Err = ();
+    fn from_str(s: &str) -> Result<WidgetPath, ()> {
+        if s == "/" {
+            return Ok(WidgetPath::root());
+        }
+        if !s.starts_with('/') {
+            return Err(());
+        }
+        let mut path = WidgetPath::root();
+        for part in s[1..].split('/') {
+            path.push(part.parse::<PathSegment>()?);
+        }
+        Ok(path)
+    }
+}
+
+#[test]
+fn widget_path_fmt() {
+    let path = WidgetPath::from_indices(vec![0, 2, 1]);
+    assert_eq!(path.to_string(), "/0/2/1");
+    assert_eq!("/0/2/1".parse(), Ok(path));
+    assert_eq!(WidgetPath::root().to_string(), "/");
+    assert_eq!("/".parse(), Ok(WidgetPath::root()));
+    assert_eq!("/0//1".parse::<WidgetPath>(), Err(()));
+    assert_eq!("0/1".parse::<WidgetPath>(), Err(()));
+
+    let path = WidgetPath::root()
+        .child(0)
+        .child(PathSegment::Name("menu".into()))
+        .child(PathSegment::Key("3".into()));
+    assert_eq!(path.to_string(), "/0/@menu/#3");
+    assert_eq!("/0/@menu/#3".parse(), Ok(path));
+}
+
 /// Common widget data
 ///
 /// All widgets should embed a `#[widget_core] core: CoreData` field.
@@ -139,4 +326,6 @@ pub struct CoreData {
     pub rect: Rect,
     pub id: WidgetId,
     pub disabled: bool,
+    /// User-assigned name; see [`kas::WidgetCore::name`]
+    pub name: Option<String>,
 }
@@ -12,7 +12,7 @@ use kas::dir::Direction;
 use kas::draw::{Draw, Pass};
 use kas::geom::{Coord, Offset, Rect, Size, Vec2};
 use kas::layout::{AxisInfo, FrameRules, Margins, SizeRules};
-use kas::text::{format::FormattableText, AccelString, Text, TextApi, TextDisplay};
+use kas::text::{format::FormattableText, AccelString, Effect, Text, TextApi, TextDisplay};
 
 // for doc use
 #[allow(unused)]
@@ -338,6 +338,35 @@ pub trait DrawHandle {
         class: TextClass,
     );
 
+    /// Draw text with effects and an optional selection
+    ///
+    /// This is like [`DrawHandle::text_offset`] but applies the given
+    /// `effects` (underline and strikethrough only; sorted by `start`), for
+    /// example to mark search matches. If `range` is not empty, this range is
+    /// drawn as selected (like [`DrawHandleExt::text_selected`]).
+    ///
+    /// The default implementation ignores `effects`, forwarding to
+    /// [`DrawHandle::text_offset`] or (with a selection)
+    /// [`DrawHandle::text_selected_range`]. (Since `text` is a bare
+    /// [`TextDisplay`], it cannot be passed to [`DrawHandle::text_effects`].)
+    fn text_offset_effects(
+        &mut self,
+        pos: Coord,
+        bounds: Vec2,
+        offset: Offset,
+        text: &TextDisplay,
+        range: Range<usize>,
+        effects: &[Effect<()>],
+        class: TextClass,
+    ) {
+        let _ = effects;
+        if range.start < range.end {
+            self.text_selected_range(pos, bounds, offset, text, range, class);
+        } else {
+            self.text_offset(pos, bounds, offset, text, class);
+        }
+    }
+
     /// Draw an edit marker at the given `byte` index on this `text`
     fn edit_marker(
         &mut self,
@@ -639,6 +668,19 @@ impl<H: DrawHandle> DrawHandle for Box<H> {
         self.deref_mut()
             .text_selected_range(pos, bounds, offset, text, range, class);
     }
+    fn text_offset_effects(
+        &mut self,
+        pos: Coord,
+        bounds: Vec2,
+        offset: Offset,
+        text: &TextDisplay,
+        range: Range<usize>,
+        effects: &[Effect<()>],
+        class: TextClass,
+    ) {
+        self.deref_mut()
+            .text_offset_effects(pos, bounds, offset, text, range, effects, class);
+    }
     fn edit_marker(
         &mut self,
         pos: Coord,
@@ -741,6 +783,19 @@ where
         self.deref_mut()
             .text_selected_range(pos, bounds, offset, text, range, class);
     }
+    fn text_offset_effects(
+        &mut self,
+        pos: Coord,
+        bounds: Vec2,
+        offset: Offset,
+        text: &TextDisplay,
+        range: Range<usize>,
+        effects: &[Effect<()>],
+        class: TextClass,
+    ) {
+        self.deref_mut()
+            .text_offset_effects(pos, bounds, offset, text, range, effects, class);
+    }
     fn edit_marker(
         &mut self,
         pos: Coord,
@@ -5,7 +5,7 @@
 
 //! Event handling configuration
 
-use super::shortcuts::Shortcuts;
+use super::shortcuts::{ShortcutConflict, Shortcuts};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -19,6 +19,12 @@ pub enum ConfigError {
     #[cfg(feature = "json")]
     #[error("config (de)serialisation to JSON failed")]
     Json(#[from] serde_json::Error),
+    #[cfg(feature = "toml")]
+    #[error("config deserialisation from TOML failed")]
+    TomlDe(#[from] toml_::de::Error),
+    #[cfg(feature = "toml")]
+    #[error("config serialisation to TOML failed")]
+    TomlSer(#[from] toml_::ser::Error),
     #[error("error reading / writing config file")]
     IoError(#[from] std::io::Error),
     #[error("format not supported: {0}")]
@@ -56,19 +62,89 @@ impl Default for ConfigFormat {
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Config {
+    /// Time allowed between the strokes of a chord, in milliseconds
+    ///
+    /// See [`Shortcuts::bind_chord`]. A pending chord is cancelled when the
+    /// next stroke is not pressed within this time.
+    #[cfg_attr(feature = "serde", serde(default = "default_chord_timeout_ms"))]
+    pub chord_timeout_ms: u32,
+    /// Delay before showing a tooltip on hover, in milliseconds
+    ///
+    /// If zero, tooltips are only shown on [`super::Command::Tooltip`].
+    #[cfg_attr(feature = "serde", serde(default = "default_tooltip_delay_ms"))]
+    pub tooltip_delay_ms: u32,
+    /// Duration a touch must be held to count as a long press, in milliseconds
+    ///
+    /// See [`super::Event::LongPress`]. If zero, long presses are not detected.
+    #[cfg_attr(feature = "serde", serde(default = "default_long_press_ms"))]
+    pub long_press_ms: u32,
+    /// Enable kinetic scrolling
+    ///
+    /// If true, scroll regions keep moving with decaying velocity after a
+    /// touch drag or touchpad scroll ends.
+    #[cfg_attr(feature = "serde", serde(default = "default_kinetic_scrolling"))]
+    pub kinetic_scrolling: bool,
+    /// Bounce off the ends of the scroll range during kinetic scrolling
+    ///
+    /// If true, kinetic scrolling may overshoot the end of the scroll range,
+    /// then springs back onto the end. If false, kinetic scrolling stops at
+    /// the end of the scroll range.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub scroll_bounce: bool,
+    // NOTE: TOML requires that tables follow all plain values, hence this
+    // field must be last.
     pub shortcuts: Shortcuts,
 }
 
+fn default_chord_timeout_ms() -> u32 {
+    2000
+}
+
+fn default_tooltip_delay_ms() -> u32 {
+    600
+}
+
+fn default_long_press_ms() -> u32 {
+    500
+}
+
+fn default_kinetic_scrolling() -> bool {
+    true
+}
+
 impl Default for Config {
     fn default() -> Self {
         let mut shortcuts = Shortcuts::new();
         shortcuts.load_platform_defaults();
-        Config { shortcuts }
+        Config {
+            chord_timeout_ms: default_chord_timeout_ms(),
+            tooltip_delay_ms: default_tooltip_delay_ms(),
+            long_press_ms: default_long_press_ms(),
+            kinetic_scrolling: default_kinetic_scrolling(),
+            scroll_bounce: false,
+            shortcuts,
+        }
     }
 }
 
+/// File representation used by [`Config::merge_path`]
+///
+/// Settings absent from the file are `None` and do not replace existing
+/// values.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Deserialize))]
+struct ConfigFile {
+    chord_timeout_ms: Option<u32>,
+    tooltip_delay_ms: Option<u32>,
+    long_press_ms: Option<u32>,
+    kinetic_scrolling: Option<bool>,
+    scroll_bounce: Option<bool>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    shortcuts: Shortcuts,
+}
+
 impl Config {
-    fn guess_format(path: &Path) -> ConfigFormat {
+    pub(crate) fn guess_format(path: &Path) -> ConfigFormat {
         // use == since there is no OsStr literal
         if let Some(ext) = path.extension() {
             if ext == "json" {
@@ -86,24 +162,39 @@ impl Config {
     }
 
     /// Read from a path
-    pub fn from_path(path: &Path, mut format: ConfigFormat) -> Result<Self, ConfigError> {
-        if format == ConfigFormat::None {
-            format = Self::guess_format(path);
-        }
+    pub fn from_path(path: &Path, format: ConfigFormat) -> Result<Self, ConfigError> {
+        read_path(path, format)
+    }
 
-        match format {
-            #[cfg(feature = "json")]
-            ConfigFormat::Json => {
-                let r = std::io::BufReader::new(std::fs::File::open(path)?);
-                Ok(serde_json::from_reader(r)?)
-            }
-            #[cfg(feature = "yaml")]
-            ConfigFormat::Yaml => {
-                let r = std::io::BufReader::new(std::fs::File::open(path)?);
-                Ok(serde_yaml::from_reader(r)?)
-            }
-            _ => Err(ConfigError::UnsupportedFormat(format)),
+    /// Read from a path, merging over `self`
+    ///
+    /// This is typically used to apply user configuration over the platform
+    /// defaults ([`Config::default`]). Shortcut bindings from the file
+    /// replace existing bindings; all replaced bindings are returned. Other
+    /// settings are taken from the file where present; settings omitted from
+    /// the file are unchanged.
+    pub fn merge_path(
+        &mut self,
+        path: &Path,
+        format: ConfigFormat,
+    ) -> Result<Vec<ShortcutConflict>, ConfigError> {
+        let other: ConfigFile = read_path(path, format)?;
+        if let Some(value) = other.chord_timeout_ms {
+            self.chord_timeout_ms = value;
+        }
+        if let Some(value) = other.tooltip_delay_ms {
+            self.tooltip_delay_ms = value;
+        }
+        if let Some(value) = other.long_press_ms {
+            self.long_press_ms = value;
+        }
+        if let Some(value) = other.kinetic_scrolling {
+            self.kinetic_scrolling = value;
+        }
+        if let Some(value) = other.scroll_bounce {
+            self.scroll_bounce = value;
         }
+        Ok(self.shortcuts.merge(&other.shortcuts))
     }
 
     /// Write to a path
@@ -119,14 +210,115 @@ impl Config {
                 serde_json::to_writer_pretty(w, self)?;
                 Ok(())
             }
+            #[cfg(feature = "toml")]
+            ConfigFormat::Toml => {
+                let s = toml_::to_string_pretty(self)?;
+                std::fs::write(path, s)?;
+                Ok(())
+            }
             #[cfg(feature = "yaml")]
             ConfigFormat::Yaml => {
                 let w = std::io::BufWriter::new(std::fs::File::create(path)?);
                 serde_yaml::to_writer(w, self)?;
                 Ok(())
             }
-            // NOTE: Toml is not supported since the `toml` crate does not support enums as map keys
             _ => Err(ConfigError::UnsupportedFormat(format)),
         }
     }
 }
+
+#[cfg(feature = "serde")]
+fn read_path<T>(path: &Path, mut format: ConfigFormat) -> Result<T, ConfigError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    if format == ConfigFormat::None {
+        format = Config::guess_format(path);
+    }
+
+    match format {
+        #[cfg(feature = "json")]
+        ConfigFormat::Json => {
+            let r = std::io::BufReader::new(std::fs::File::open(path)?);
+            Ok(serde_json::from_reader(r)?)
+        }
+        #[cfg(feature = "toml")]
+        ConfigFormat::Toml => {
+            let s = std::fs::read_to_string(path)?;
+            Ok(toml_::from_str(&s)?)
+        }
+        #[cfg(feature = "yaml")]
+        ConfigFormat::Yaml => {
+            let r = std::io::BufReader::new(std::fs::File::open(path)?);
+            Ok(serde_yaml::from_reader(r)?)
+        }
+        _ => Err(ConfigError::UnsupportedFormat(format)),
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn read_path<T>(path: &Path, mut format: ConfigFormat) -> Result<T, ConfigError> {
+    if format == ConfigFormat::None {
+        format = Config::guess_format(path);
+    }
+    Err(ConfigError::UnsupportedFormat(format))
+}
+
+#[cfg(all(test, feature = "toml"))]
+mod test {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn toml_round_trip() {
+        let mut config = Config::default();
+        config.tooltip_delay_ms = 250;
+        config.scroll_bounce = true;
+
+        let path = std::env::temp_dir().join("kas-event-config-test.toml");
+        config.write_path(&path, ConfigFormat::None).unwrap();
+        let read = Config::from_path(&path, ConfigFormat::None).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(read.tooltip_delay_ms, 250);
+        assert_eq!(read.long_press_ms, config.long_press_ms);
+        assert_eq!(read.kinetic_scrolling, config.kinetic_scrolling);
+        assert!(read.scroll_bounce);
+        let bindings = |c: &Config| c.shortcuts.iter().collect::<HashSet<_>>();
+        assert_eq!(bindings(&read), bindings(&config));
+    }
+
+    #[test]
+    fn merge_path() {
+        use crate::event::{Command, ModifiersState, VirtualKeyCode as VK};
+
+        let path = std::env::temp_dir().join("kas-event-config-merge-test.toml");
+        let file = r#"
+tooltip_delay_ms = 0
+scroll_bounce = true
+
+[shortcuts.ctrl]
+Q = "Exit"
+"#;
+        std::fs::write(&path, file).unwrap();
+
+        let mut config = Config::default();
+        config.chord_timeout_ms = 1500;
+        config.long_press_ms = 800;
+        config.kinetic_scrolling = false;
+        let result = config.merge_path(&path, ConfigFormat::None);
+        let _ = std::fs::remove_file(&path);
+        result.unwrap();
+
+        // Values present in the file replace existing values
+        assert_eq!(config.tooltip_delay_ms, 0);
+        assert!(config.scroll_bounce);
+        let command = config.shortcuts.get(ModifiersState::CTRL, VK::Q);
+        assert_eq!(command, Some(Command::Exit));
+
+        // Values absent from the file are unchanged
+        assert_eq!(config.chord_timeout_ms, 1500);
+        assert_eq!(config.long_press_ms, 800);
+        assert!(!config.kinetic_scrolling);
+    }
+}
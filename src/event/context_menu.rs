@@ -0,0 +1,170 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Event handling: context menus
+
+use super::Command;
+use crate::text::AccelString;
+use crate::WidgetId;
+
+/// An entry of a [`ContextMenu`]
+#[derive(Clone, Debug)]
+pub enum ContextMenuEntry {
+    /// A command entry
+    Command {
+        /// The label displayed
+        label: AccelString,
+        /// The command sent on selection
+        command: Command,
+        /// Whether the entry may be selected
+        enabled: bool,
+    },
+    /// A separator
+    Separator,
+}
+
+/// Contents of a context menu
+///
+/// Context menus are owned by the window, which constructs the contents each
+/// time the menu is opened (via an unhandled right press or
+/// [`Command::Menu`]). Each ancestor of the target widget, from the outermost,
+/// then the target widget itself may contribute to or adjust the contents via
+/// [`kas::WidgetConfig::context_menu`].
+///
+/// Each entry is associated with a [`Command`]; on selection,
+/// [`super::Event::Command`] is sent to the target widget, then (while
+/// unhandled) to each ancestor in turn. Application-specific entries may use
+/// [`Command::App`]; the handling widget may emit a message as usual. If the
+/// command remains unhandled, [`Command::Undo`] and [`Command::Redo`] apply to
+/// the window's undo history (see [`super::Manager::push_undo`]).
+#[derive(Clone, Debug)]
+pub struct ContextMenu {
+    target: WidgetId,
+    entries: Vec<ContextMenuEntry>,
+}
+
+impl ContextMenu {
+    /// Construct an empty menu for the given `target`
+    #[inline]
+    pub fn new(target: WidgetId) -> Self {
+        ContextMenu {
+            target,
+            entries: vec![],
+        }
+    }
+
+    /// The widget for which the menu is opened
+    #[inline]
+    pub fn target(&self) -> WidgetId {
+        self.target
+    }
+
+    /// True if there are no entries
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Access entries
+    #[inline]
+    pub fn entries(&self) -> &[ContextMenuEntry] {
+        &self.entries
+    }
+
+    /// Remove all entries
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Add an entry
+    ///
+    /// If an entry with this `command` already exists, it is replaced.
+    pub fn push<S: Into<AccelString>>(&mut self, label: S, command: Command) {
+        self.push_entry(label.into(), command, true);
+    }
+
+    /// Add an entry, enabled only if `enabled` is true
+    ///
+    /// If an entry with this `command` already exists, it is replaced.
+    pub fn push_enabled<S: Into<AccelString>>(
+        &mut self,
+        label: S,
+        command: Command,
+        enabled: bool,
+    ) {
+        self.push_entry(label.into(), command, enabled);
+    }
+
+    fn push_entry(&mut self, label: AccelString, command: Command, enabled: bool) {
+        let entry = ContextMenuEntry::Command {
+            label,
+            command,
+            enabled,
+        };
+        if let Some(index) = self.find(command) {
+            self.entries[index] = entry;
+        } else {
+            self.entries.push(entry);
+        }
+    }
+
+    /// Add a separator
+    ///
+    /// Separators are not added at the start or immediately following another
+    /// separator.
+    pub fn push_separator(&mut self) {
+        match self.entries.last() {
+            None | Some(ContextMenuEntry::Separator) => (),
+            Some(_) => self.entries.push(ContextMenuEntry::Separator),
+        }
+    }
+
+    /// True if an entry for `command` exists
+    #[inline]
+    pub fn contains(&self, command: Command) -> bool {
+        self.find(command).is_some()
+    }
+
+    /// Enable or disable the entry for `command`, if any
+    pub fn set_enabled(&mut self, command: Command, state: bool) {
+        if let Some(index) = self.find(command) {
+            if let ContextMenuEntry::Command { enabled, .. } = &mut self.entries[index] {
+                *enabled = state;
+            }
+        }
+    }
+
+    /// Remove the entry for `command`, if any
+    pub fn remove(&mut self, command: Command) {
+        if let Some(index) = self.find(command) {
+            self.entries.remove(index);
+        }
+    }
+
+    /// Remove leading, trailing and repeated separators
+    pub(crate) fn tidy(&mut self) {
+        let mut last_sep = true;
+        self.entries.retain(|entry| {
+            let is_sep = match entry {
+                ContextMenuEntry::Separator => true,
+                _ => false,
+            };
+            let keep = !(is_sep && last_sep);
+            last_sep = is_sep;
+            keep
+        });
+        if let Some(ContextMenuEntry::Separator) = self.entries.last() {
+            self.entries.pop();
+        }
+    }
+
+    fn find(&self, cmd: Command) -> Option<usize> {
+        self.entries.iter().position(|entry| match entry {
+            ContextMenuEntry::Command { command, .. } => *command == cmd,
+            ContextMenuEntry::Separator => false,
+        })
+    }
+}
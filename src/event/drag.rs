@@ -0,0 +1,77 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Event handling: drag and drop
+
+use std::any::Any;
+use std::fmt;
+use std::rc::Rc;
+
+#[allow(unused)]
+use super::{Event, Manager}; // for doc-links
+use crate::WidgetId;
+
+/// The data carried by a drag-and-drop operation
+///
+/// A payload may hold a value of any (`'static`) type. It is passed to
+/// [`Manager::start_drag`] and delivered to potential drop targets via
+/// [`Event::DragMove`] and [`Event::Drop`]; these should check the type of the
+/// value (via [`DragPayload::get`]) to decide whether to accept a drop.
+///
+/// The value is shared, thus cloning a payload is cheap.
+#[derive(Clone)]
+pub struct DragPayload {
+    source: WidgetId,
+    data: Rc<dyn Any>,
+}
+
+impl DragPayload {
+    /// Construct from a value
+    #[inline]
+    pub fn new<T: Any>(data: T) -> Self {
+        DragPayload {
+            source: Default::default(),
+            data: Rc::new(data),
+        }
+    }
+
+    /// The widget which started the drag
+    #[inline]
+    pub fn source(&self) -> WidgetId {
+        self.source
+    }
+
+    #[inline]
+    pub(crate) fn set_source(&mut self, id: WidgetId) {
+        self.source = id;
+    }
+
+    /// Check whether the value has type `T`
+    #[inline]
+    pub fn is<T: Any>(&self) -> bool {
+        self.data.is::<T>()
+    }
+
+    /// Get the value, if of type `T`
+    #[inline]
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.data.downcast_ref()
+    }
+}
+
+impl fmt::Debug for DragPayload {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DragPayload {{ source: {}, .. }}", self.source)
+    }
+}
+
+/// Payloads are equal if they are clones of the same payload
+impl PartialEq for DragPayload {
+    fn eq(&self, other: &Self) -> bool {
+        let p1 = Rc::as_ptr(&self.data) as *const u8;
+        let p2 = Rc::as_ptr(&other.data) as *const u8;
+        self.source == other.source && p1 == p2
+    }
+}
@@ -81,6 +81,20 @@ pub enum MouseButton {
     Other(u8),
 }
 
+/// Describes touch-screen input state.
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TouchPhase {
+    /// A finger touched the screen
+    Started,
+    /// A finger moved on the screen
+    Moved,
+    /// A finger was lifted from the screen
+    Ended,
+    /// The system cancelled tracking of the touch
+    Cancelled,
+}
+
 impl ModifiersState {
     /// Returns `true` if the shift key is pressed.
     pub fn shift(&self) -> bool {
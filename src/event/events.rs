@@ -6,11 +6,18 @@
 //! Event handling: events
 
 #[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
+use serde::de::{self, Deserialize, Deserializer, Unexpected, Visitor};
+#[cfg(feature = "serde")]
+use serde::ser::{Serialize, Serializer};
+#[cfg(feature = "serde")]
+use std::fmt;
 
+use super::{DragPayload, MouseButton, UndoRequest, UpdateHandle, VirtualKeyCode};
 #[allow(unused)]
 use super::{GrabMode, Manager, Response}; // for doc-links
-use super::{MouseButton, UpdateHandle, VirtualKeyCode};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::path::PathBuf;
 
 use crate::geom::{Coord, DVec2, Offset};
 use crate::{WidgetId, WindowId};
@@ -48,6 +55,29 @@ pub enum Event {
     LostSelFocus,
     /// Widget receives a character of text input
     ReceivedCharacter(char),
+    /// Input method pre-edit (composition) text
+    ///
+    /// This is sent to the widget with char focus while an input method
+    /// composes text. `text` is the uncommitted text, which should be
+    /// displayed at the edit position (replacing any previous pre-edit text)
+    /// but not yet inserted. `cursor` is the byte range of the input method's
+    /// cursor or selection within `text`, if any. An empty `text` ends
+    /// composition without committing anything.
+    ///
+    /// The widget should report the position of its text cursor via
+    /// [`Manager::set_ime_cursor_area`] so that the input method can place
+    /// its candidate window.
+    ImePreedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    /// Input method commit
+    ///
+    /// This is sent to the widget with char focus when an input method commits
+    /// `text`, which replaces any pre-edit text (see [`Event::ImePreedit`]).
+    /// If this event is not handled, each `char` of `text` is sent instead via
+    /// [`Event::ReceivedCharacter`].
+    ImeCommit(String),
     /// A mouse or touchpad scroll event
     Scroll(ScrollDelta),
     /// A mouse or touch-screen move/zoom/rotate event
@@ -130,6 +160,73 @@ pub enum Event {
         end_id: Option<WidgetId>,
         coord: Coord,
     },
+    /// A touch was held without moving
+    ///
+    /// This is sent to the widget which started the press once the touch has
+    /// been held for [`super::Config::long_press_ms`], but only where the
+    /// touch has a press grab with [`super::GrabMode::Grab`]. Touches used for
+    /// pan grabs never cause a long press. Each touch is timed independently.
+    /// If handled, the press continues.
+    ///
+    /// When unhandled, the press grab is cancelled (via [`Event::PressEnd`]
+    /// with `end_id == None`), thus the touch does not also result in a click,
+    /// and the context menu is opened at `coord` (see
+    /// [`Event::OpenContextMenu`]).
+    LongPress { source: PressSource, coord: Coord },
+    /// A drag-and-drop operation is over this widget
+    ///
+    /// This is sent on each movement of a drag (see [`Manager::start_drag`]),
+    /// first to the widget under the cursor (`coord`), then to each of its
+    /// ancestors until one replies with something other than
+    /// [`Response::Unhandled`]. That widget becomes the drop target: it
+    /// accepts a drop of `payload` at `coord`. If no widget accepts, the
+    /// cursor indicates that the payload may not be dropped here.
+    ///
+    /// The first `DragMove` received after becoming the drop target may be
+    /// treated as the drag entering the widget.
+    DragMove { payload: DragPayload, coord: Coord },
+    /// A drag-and-drop operation left this widget
+    ///
+    /// This is sent to the drop target when another widget (or no widget)
+    /// becomes the drop target, or when the drag is cancelled.
+    DragLeave,
+    /// A payload was dropped on this widget
+    ///
+    /// This is sent to the drop target (see [`Event::DragMove`]) when the
+    /// press ends. The widget should reply with [`Response::Unhandled`] if it
+    /// does not accept the drop after all.
+    Drop { payload: DragPayload, coord: Coord },
+    /// End of a drag-and-drop operation started by this widget
+    ///
+    /// This is sent instead of [`Event::PressEnd`] to the widget which
+    /// started the drag. `target` is the widget which accepted the drop, if
+    /// any; a widget implementing "move" semantics should remove the dragged
+    /// item only in this case.
+    DragEnd { target: Option<WidgetId> },
+    /// A file from another application is dragged over this widget
+    ///
+    /// When multiple files are dragged, this event is received once per file.
+    ///
+    /// File events are sent to the widget under the mouse cursor if it accepts
+    /// files (see [`kas::WidgetConfig::accept_files`]), otherwise to its
+    /// nearest ancestor which does. Note that some platforms do not report
+    /// cursor movement while files are dragged, in which case the last known
+    /// cursor position is used.
+    FileHover(PathBuf),
+    /// Files dragged over this widget left the window without being dropped
+    FileHoverCancelled,
+    /// A file from another application was dropped on this widget
+    ///
+    /// When multiple files are dropped, this event is received once per file.
+    /// See also [`Event::FileHover`].
+    FileDrop(PathBuf),
+    /// Undo or redo an operation in the window's history
+    ///
+    /// This is sent to the widget which pushed the operation (see
+    /// [`Manager::push_undo`]). The widget should return the result of
+    /// [`UndoRequest::apply`], thus emitting the same message as for the
+    /// corresponding user input.
+    Undo(UndoRequest),
     /// Update from a timer
     ///
     /// This event is received after requesting timed wake-up(s)
@@ -159,6 +256,36 @@ pub enum Event {
     ///
     /// The widget should reply with [`Response::Focus`].
     NavFocus,
+    /// Request to open a context menu
+    ///
+    /// This is sent to the window (the root widget) on an unhandled
+    /// right-button [`Event::PressStart`] or [`Event::LongPress`] (with the
+    /// press `coord`) or on [`Command::Menu`] (without a coordinate), when no
+    /// pop-up is open.
+    /// Other widgets do not receive this event; see instead
+    /// [`kas::WidgetConfig::context_menu`].
+    ///
+    /// The window should reply with [`Response::Unhandled`] if no menu is
+    /// opened.
+    OpenContextMenu {
+        target: WidgetId,
+        coord: Option<Coord>,
+    },
+    /// Request to show a tooltip
+    ///
+    /// This is sent to the window (the root widget) once the mouse has
+    /// hovered over `target` for the configured delay (with the mouse `coord`;
+    /// see [`super::Config::tooltip_delay_ms`]) or on [`Command::Tooltip`]
+    /// (without a coordinate). Other widgets do not receive this event; see
+    /// instead [`kas::WidgetConfig::tooltip`].
+    ///
+    /// The window should reply with [`Response::Unhandled`] if no tooltip is
+    /// shown. The tooltip should be drawn while
+    /// [`super::ManagerState::tooltip`] equals `target`.
+    OpenTooltip {
+        target: WidgetId,
+        coord: Option<Coord>,
+    },
 }
 
 /// Command input ([`Event::Command`])
@@ -173,10 +300,11 @@ pub enum Event {
 ///
 /// Handling may depend on the state of the Shift key.
 ///
-/// The exact mapping between the keyboard and these commands is OS-specific.
-/// In the future it should be customisable (see `shortcuts` module).
+/// The default mapping between the keyboard and these commands is
+/// OS-specific; it may be customised via [`Shortcuts`](super::Shortcuts).
+///
+/// Applications may define their own commands via [`Command::App`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Command {
     /// Escape key
     ///
@@ -312,6 +440,10 @@ pub enum Command {
 
     /// Show help
     Help,
+    /// Show the tooltip of the focused widget
+    ///
+    /// When unhandled, this shows the tooltip (see [`Event::OpenTooltip`]).
+    Tooltip,
     /// Rename
     Rename,
     /// Refresh
@@ -319,6 +451,9 @@ pub enum Command {
     /// Spell-check tool
     Spelling,
     /// Open the menu / activate the menubar
+    ///
+    /// When unhandled, this opens the context menu (see
+    /// [`Event::OpenContextMenu`]).
     Menu,
     /// Make view fullscreen
     Fullscreen,
@@ -327,6 +462,14 @@ pub enum Command {
     Close,
     /// Exit program (e.g. Ctrl+Q)
     Exit,
+
+    /// An application-defined command
+    ///
+    /// These may be bound via [`Shortcuts`](super::Shortcuts) and are
+    /// delivered via [`Event::Command`] like other commands. No widget
+    /// provided by KAS handles these; typically the application's window or
+    /// some parent widget handles them (see [`Manager::register_nav_fallback`]).
+    App(AppCommand),
 }
 
 impl Command {
@@ -357,11 +500,254 @@ impl Command {
             Cut => Command::Cut,
             Copy => Command::Copy,
             Paste => Command::Paste,
+            Apps => Command::Menu,
+            _ => return None,
+        })
+    }
+
+    /// Get the command's name
+    ///
+    /// This is the name of the variant or, for [`Command::App`], the name of
+    /// the [`AppCommand`] prefixed with `app:`. It is used for
+    /// (de)serialisation; see [`Command::from_name`].
+    pub fn name(self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            Command::Escape => "Escape",
+            Command::Return => "Return",
+            Command::Tab => "Tab",
+            Command::ViewUp => "ViewUp",
+            Command::ViewDown => "ViewDown",
+            Command::Left => "Left",
+            Command::Right => "Right",
+            Command::Up => "Up",
+            Command::Down => "Down",
+            Command::WordLeft => "WordLeft",
+            Command::WordRight => "WordRight",
+            Command::Home => "Home",
+            Command::End => "End",
+            Command::DocHome => "DocHome",
+            Command::DocEnd => "DocEnd",
+            Command::PageUp => "PageUp",
+            Command::PageDown => "PageDown",
+            Command::Snapshot => "Snapshot",
+            Command::ScrollLock => "ScrollLock",
+            Command::Pause => "Pause",
+            Command::Insert => "Insert",
+            Command::Delete => "Delete",
+            Command::DelBack => "DelBack",
+            Command::DelWord => "DelWord",
+            Command::DelWordBack => "DelWordBack",
+            Command::Deselect => "Deselect",
+            Command::SelectAll => "SelectAll",
+            Command::Find => "Find",
+            Command::FindReplace => "FindReplace",
+            Command::FindNext => "FindNext",
+            Command::FindPrev => "FindPrev",
+            Command::Bold => "Bold",
+            Command::Italic => "Italic",
+            Command::Underline => "Underline",
+            Command::Link => "Link",
+            Command::Cut => "Cut",
+            Command::Copy => "Copy",
+            Command::Paste => "Paste",
+            Command::Undo => "Undo",
+            Command::Redo => "Redo",
+            Command::New => "New",
+            Command::Open => "Open",
+            Command::Save => "Save",
+            Command::Print => "Print",
+            Command::NavNext => "NavNext",
+            Command::NavPrev => "NavPrev",
+            Command::NavParent => "NavParent",
+            Command::NavDown => "NavDown",
+            Command::TabNew => "TabNew",
+            Command::TabNext => "TabNext",
+            Command::TabPrev => "TabPrev",
+            Command::Help => "Help",
+            Command::Tooltip => "Tooltip",
+            Command::Rename => "Rename",
+            Command::Refresh => "Refresh",
+            Command::Spelling => "Spelling",
+            Command::Menu => "Menu",
+            Command::Fullscreen => "Fullscreen",
+            Command::Close => "Close",
+            Command::Exit => "Exit",
+            Command::App(cmd) => return Cow::Owned(format!("app:{}", cmd.name())),
+        })
+    }
+
+    /// Find a command by name
+    ///
+    /// This is the inverse of [`Command::name`]: a built-in command is found
+    /// by the name of its variant, while the name of an [`AppCommand`] must
+    /// be prefixed with `app:` (e.g. `app:ToggleSidebar`). Returns `None`
+    /// for any other name.
+    pub fn from_name(name: &str) -> Option<Command> {
+        Some(match name {
+            "Escape" => Command::Escape,
+            "Return" => Command::Return,
+            "Tab" => Command::Tab,
+            "ViewUp" => Command::ViewUp,
+            "ViewDown" => Command::ViewDown,
+            "Left" => Command::Left,
+            "Right" => Command::Right,
+            "Up" => Command::Up,
+            "Down" => Command::Down,
+            "WordLeft" => Command::WordLeft,
+            "WordRight" => Command::WordRight,
+            "Home" => Command::Home,
+            "End" => Command::End,
+            "DocHome" => Command::DocHome,
+            "DocEnd" => Command::DocEnd,
+            "PageUp" => Command::PageUp,
+            "PageDown" => Command::PageDown,
+            "Snapshot" => Command::Snapshot,
+            "ScrollLock" => Command::ScrollLock,
+            "Pause" => Command::Pause,
+            "Insert" => Command::Insert,
+            "Delete" => Command::Delete,
+            "DelBack" => Command::DelBack,
+            "DelWord" => Command::DelWord,
+            "DelWordBack" => Command::DelWordBack,
+            "Deselect" => Command::Deselect,
+            "SelectAll" => Command::SelectAll,
+            "Find" => Command::Find,
+            "FindReplace" => Command::FindReplace,
+            "FindNext" => Command::FindNext,
+            "FindPrev" => Command::FindPrev,
+            "Bold" => Command::Bold,
+            "Italic" => Command::Italic,
+            "Underline" => Command::Underline,
+            "Link" => Command::Link,
+            "Cut" => Command::Cut,
+            "Copy" => Command::Copy,
+            "Paste" => Command::Paste,
+            "Undo" => Command::Undo,
+            "Redo" => Command::Redo,
+            "New" => Command::New,
+            "Open" => Command::Open,
+            "Save" => Command::Save,
+            "Print" => Command::Print,
+            "NavNext" => Command::NavNext,
+            "NavPrev" => Command::NavPrev,
+            "NavParent" => Command::NavParent,
+            "NavDown" => Command::NavDown,
+            "TabNew" => Command::TabNew,
+            "TabNext" => Command::TabNext,
+            "TabPrev" => Command::TabPrev,
+            "Help" => Command::Help,
+            "Tooltip" => Command::Tooltip,
+            "Rename" => Command::Rename,
+            "Refresh" => Command::Refresh,
+            "Spelling" => Command::Spelling,
+            "Menu" => Command::Menu,
+            "Fullscreen" => Command::Fullscreen,
+            "Close" => Command::Close,
+            "Exit" => Command::Exit,
+            name if name.starts_with("app:") && name.len() > 4 => {
+                Command::App(AppCommand::intern(&name[4..]))
+            }
             _ => return None,
         })
     }
 }
 
+/// An application-defined command
+///
+/// This is a named command which may be bound via
+/// [`Shortcuts`](super::Shortcuts) and is delivered as
+/// [`Event::Command`]`(`[`Command::App`]`(..), _)`. Commands are compared by
+/// name. Names should be unique to the application. In configuration files,
+/// names are prefixed with `app:` to distinguish them from built-in commands
+/// (see [`Command::from_name`]).
+///
+/// Typically, commands are defined as constants:
+/// ```
+/// use kas::event::{AppCommand, Command};
+///
+/// const TOGGLE_SIDEBAR: AppCommand = AppCommand::new("ToggleSidebar");
+///
+/// let cmd = Command::from_name("app:ToggleSidebar");
+/// assert_eq!(cmd, Some(Command::App(TOGGLE_SIDEBAR)));
+/// assert_eq!(Command::from_name("ToggleSidebar"), None);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AppCommand(&'static str);
+
+impl AppCommand {
+    /// Construct from a name
+    #[inline]
+    pub const fn new(name: &'static str) -> Self {
+        AppCommand(name)
+    }
+
+    /// Construct from a (non-static) name
+    ///
+    /// Each distinct name is allocated once (per thread) and never freed.
+    pub fn intern(name: &str) -> Self {
+        thread_local! {
+            static NAMES: RefCell<Vec<&'static str>> = RefCell::new(vec![]);
+        }
+        NAMES.with(|names| {
+            let mut names = names.borrow_mut();
+            if let Some(name) = names.iter().find(|n| **n == name) {
+                return AppCommand(*name);
+            }
+            let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+            names.push(name);
+            AppCommand(name)
+        })
+    }
+
+    /// Get the name
+    #[inline]
+    pub fn name(self) -> &'static str {
+        self.0
+    }
+}
+
+impl From<AppCommand> for Command {
+    #[inline]
+    fn from(cmd: AppCommand) -> Self {
+        Command::App(cmd)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Command {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        s.serialize_str(&self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+struct CommandVisitor;
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for CommandVisitor {
+    type Value = Command;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a command name")
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        Command::from_name(name).ok_or_else(|| E::invalid_value(Unexpected::Str(name), &self))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Command {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        d.deserialize_str(CommandVisitor)
+    }
+}
+
 /// Source of `EventChild::Press`
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum PressSource {
@@ -416,9 +802,50 @@ impl PressSource {
 
 /// Type used by [`Event::Scroll`]
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ScrollDelta {
     /// Scroll a given number of lines
     LineDelta(f32, f32),
     /// Scroll a given number of pixels
     PixelDelta(Offset),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn command_names() {
+        let commands = [
+            Command::Escape,
+            Command::WordLeft,
+            Command::DelBack,
+            Command::SelectAll,
+            Command::Undo,
+            Command::Tooltip,
+            Command::Exit,
+            Command::App(AppCommand::new("ToggleSidebar")),
+        ];
+        for cmd in commands.iter() {
+            assert_eq!(Command::from_name(&cmd.name()), Some(*cmd));
+        }
+        assert_eq!(Command::Home.name(), "Home");
+        assert_eq!(
+            Command::App(AppCommand::new("ToggleSidebar")).name(),
+            "app:ToggleSidebar"
+        );
+
+        assert_eq!(Command::from_name(""), None);
+        assert_eq!(Command::from_name("app:"), None);
+        assert_eq!(Command::from_name("Exti"), None);
+        assert_eq!(Command::from_name("home"), None);
+    }
+
+    #[test]
+    fn intern() {
+        let a = AppCommand::intern(&String::from("Foo"));
+        let b = AppCommand::intern(&String::from("Foo"));
+        assert_eq!(a, AppCommand::new("Foo"));
+        assert!(std::ptr::eq(a.name(), b.name()));
+    }
+}
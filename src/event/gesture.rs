@@ -0,0 +1,442 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Event handling: gesture recognition
+
+use smallvec::SmallVec;
+use std::time::{Duration, Instant};
+
+use super::{Event, GrabMode, Manager, PressSource, Response, VoidMsg};
+use crate::geom::{Coord, DVec2, Offset};
+use crate::WidgetId;
+
+/// Maximum interval between the taps of a double-tap
+const DOUBLE_TAP_TIMEOUT: Duration = Duration::from_millis(400);
+/// Maximum distance moved by a press to still count as a tap or long press
+pub(crate) const TAP_SLOP: i32 = 8;
+
+bitflags! {
+    /// A set of gestures to recognise
+    ///
+    /// See [`GestureRecogniser`].
+    #[derive(Default)]
+    pub struct GestureSet: u8 {
+        /// Two-finger pinch, yielding [`Gesture::Zoom`]
+        const ZOOM = 1 << 0;
+        /// Two-finger rotation, yielding [`Gesture::Rotate`]
+        const ROTATE = 1 << 1;
+        /// Translation (with one or two fingers), yielding [`Gesture::Scroll`]
+        const SCROLL = 1 << 2;
+        /// A press held without motion (timed via [`Event::TimerUpdate`]),
+        /// yielding [`Gesture::LongPress`]
+        const LONG_PRESS = 1 << 3;
+        /// Two taps in quick succession, yielding [`Gesture::DoubleTap`]
+        const DOUBLE_TAP = 1 << 4;
+    }
+}
+
+/// A recognised gesture
+///
+/// Zoom, rotate and scroll gestures report the change since the previous
+/// gesture, thus an application should accumulate values. Applying these in
+/// the order reported reproduces the motion of the touches (as reported by
+/// [`Event::Pan`]).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Gesture {
+    /// Two-finger pinch: scale about `center` by factor `scale`
+    Zoom { scale: f64, center: DVec2 },
+    /// Two-finger rotation: rotate about `center` by `angle` (radians)
+    ///
+    /// As with screen coordinates, positive angles are clockwise.
+    Rotate { angle: f64, center: DVec2 },
+    /// Translation (without zoom or rotation)
+    Scroll(DVec2),
+    /// A press was held without motion for [`super::Config::long_press_ms`]
+    ///
+    /// This applies to mouse and touch presses alike and is detected by the
+    /// recogniser's own timer, not via [`Event::LongPress`] (which is only
+    /// sent for [`GrabMode::Grab`] touches). The press continues, but cannot
+    /// start a double-tap.
+    LongPress { source: PressSource, coord: Coord },
+    /// A second press closely following a tap, at (nearly) the same position
+    DoubleTap { source: PressSource, coord: Coord },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Press {
+    source: PressSource,
+    start: Coord,
+    time: Instant,
+    /// Translation since the start of the press
+    moved: DVec2,
+    /// Moved too far, held too long or joined by another touch to be a tap
+    cancelled: bool,
+}
+
+/// Logic for gesture recognition
+///
+/// This struct converts press and [`Event::Pan`] events into [`Gesture`]s,
+/// much as [`crate::widget::ScrollComponent`] converts events into scroll
+/// actions. A widget constructs the recogniser with the set of gestures it
+/// wants and passes its events to [`GestureRecogniser::handle`]:
+/// ```
+/// # use kas::prelude::*;
+/// # use kas::event::{Gesture, GestureRecogniser};
+/// fn dummy_event_handler(
+///     id: WidgetId,
+///     gestures: &mut GestureRecogniser,
+///     mgr: &mut Manager,
+///     event: Event,
+/// ) -> Response<VoidMsg> {
+///     gestures.handle(mgr, id, event, |_, gesture| match gesture {
+///         Gesture::Zoom { scale, .. } => println!("Zoom by {}", scale),
+///         _ => (),
+///     })
+/// }
+/// ```
+///
+/// On [`Event::PressStart`] the recogniser requests a pan grab (see
+/// [`GrabMode`]) with scaling and rotation as required by the gestures; the
+/// resulting [`Event::Pan`] events are decomposed into zoom, rotate and
+/// scroll gestures. Long-press detection uses [`Event::TimerUpdate`]; widgets
+/// using their own timers should also handle timer updates not used by the
+/// recogniser. Other events are returned via [`Response::Unhandled`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct GestureRecogniser {
+    gestures: GestureSet,
+    sources: SmallVec<[PressSource; 2]>,
+    press: Option<Press>,
+}
+
+impl GestureRecogniser {
+    /// Construct, recognising the given `gestures`
+    pub fn new(gestures: GestureSet) -> Self {
+        GestureRecogniser {
+            gestures,
+            sources: Default::default(),
+            press: None,
+        }
+    }
+
+    /// Get the set of gestures recognised
+    #[inline]
+    pub fn gestures(&self) -> GestureSet {
+        self.gestures
+    }
+
+    /// Set the gestures to recognise
+    #[inline]
+    pub fn set_gestures(&mut self, gestures: GestureSet) {
+        self.gestures = gestures;
+    }
+
+    /// The grab mode used for presses
+    fn grab_mode(&self) -> GrabMode {
+        let zoom = self.gestures.contains(GestureSet::ZOOM);
+        let rotate = self.gestures.contains(GestureSet::ROTATE);
+        match (zoom, rotate) {
+            (true, true) => GrabMode::PanFull,
+            (true, false) => GrabMode::PanScale,
+            (false, true) => GrabMode::PanRotate,
+            (false, false) => GrabMode::PanOnly,
+        }
+    }
+
+    /// Handle an event
+    ///
+    /// Widget `id` is the widget owning this recogniser. Recognised gestures
+    /// are passed to `on_gesture`.
+    ///
+    /// Returns [`Response::None`] if the event was used, otherwise
+    /// [`Response::Unhandled`].
+    pub fn handle<F: FnMut(&mut Manager, Gesture)>(
+        &mut self,
+        mgr: &mut Manager,
+        id: WidgetId,
+        event: Event,
+        mut on_gesture: F,
+    ) -> Response<VoidMsg> {
+        // Pan grabs do not report the end of presses, so we check for these
+        self.sources.retain(|source| mgr.has_grab(id, *source));
+
+        match event {
+            Event::PressStart { source, coord, .. } if source.is_primary() => {
+                if self.sources.len() >= 2 {
+                    return Response::Unhandled;
+                }
+                if !mgr.request_grab(id, source, coord, self.grab_mode(), None) {
+                    return Response::Unhandled;
+                }
+                self.sources.push(source);
+                let now = mgr.now();
+
+                if self.sources.len() > 1 {
+                    // A second touch starts a multi-touch gesture
+                    self.press = None;
+                    return Response::None;
+                }
+
+                if let Some(last) = self.press.take() {
+                    let d = coord - last.start;
+                    if self.gestures.contains(GestureSet::DOUBLE_TAP)
+                        && !last.cancelled
+                        && last.source.is_touch() == source.is_touch()
+                        && now - last.time < DOUBLE_TAP_TIMEOUT
+                        && d.0.abs() <= TAP_SLOP
+                        && d.1.abs() <= TAP_SLOP
+                    {
+                        on_gesture(mgr, Gesture::DoubleTap { source, coord });
+                        return Response::None;
+                    }
+                }
+
+                self.press = Some(Press {
+                    source,
+                    start: coord,
+                    time: now,
+                    moved: DVec2::ZERO,
+                    cancelled: false,
+                });
+                if self.gestures.contains(GestureSet::LONG_PRESS) {
+                    if let Some(delay) = long_press_delay(mgr) {
+                        mgr.update_on_timer(delay, id);
+                    }
+                }
+                Response::None
+            }
+            Event::Pan { alpha, delta } => {
+                if let Some(press) = self.press.as_mut() {
+                    press.moved = press.moved + delta;
+                    let slop = f64::from(TAP_SLOP);
+                    if press.moved.0.abs() > slop || press.moved.1.abs() > slop {
+                        press.cancelled = true;
+                    }
+                }
+
+                let mut used = false;
+                if alpha == DVec2(1.0, 0.0) {
+                    if self.gestures.contains(GestureSet::SCROLL) {
+                        on_gesture(mgr, Gesture::Scroll(delta));
+                        used = true;
+                    }
+                } else {
+                    // The transform p → alpha * p + delta fixes this point:
+                    let center = delta.complex_div(DVec2(1.0, 0.0) - alpha);
+                    let scale = alpha.sum_square().sqrt();
+                    if self.gestures.contains(GestureSet::ZOOM) && scale != 1.0 {
+                        on_gesture(mgr, Gesture::Zoom { scale, center });
+                        used = true;
+                    }
+                    let angle = alpha.1.atan2(alpha.0);
+                    if self.gestures.contains(GestureSet::ROTATE) && angle != 0.0 {
+                        on_gesture(mgr, Gesture::Rotate { angle, center });
+                        used = true;
+                    }
+                }
+                match used {
+                    true => Response::None,
+                    false => Response::Unhandled,
+                }
+            }
+            Event::TimerUpdate => {
+                if !self.gestures.contains(GestureSet::LONG_PRESS) {
+                    return Response::Unhandled;
+                }
+                let press = match self.press.as_mut() {
+                    Some(press) if !press.cancelled && self.sources.contains(&press.source) => {
+                        press
+                    }
+                    _ => return Response::Unhandled,
+                };
+                let delay = match long_press_delay(mgr) {
+                    Some(delay) => delay,
+                    None => return Response::Unhandled,
+                };
+                let elapsed = mgr.now() - press.time;
+                if elapsed < delay {
+                    mgr.update_on_timer(delay - elapsed, id);
+                    return Response::None;
+                }
+                press.cancelled = true;
+                let source = press.source;
+                let coord = press.start + Offset::from(press.moved);
+                on_gesture(mgr, Gesture::LongPress { source, coord });
+                Response::None
+            }
+            _ => Response::Unhandled,
+        }
+    }
+}
+
+fn long_press_delay(mgr: &Manager) -> Option<Duration> {
+    match mgr.config().long_press_ms {
+        0 => None,
+        ms => Some(Duration::from_millis(ms.into())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::event::{Config, MouseButton, TouchPhase};
+    use crate::prelude::*;
+    use crate::testing::Harness;
+    use std::cell::RefCell;
+    use std::f64::consts::FRAC_PI_2;
+    use std::rc::Rc;
+
+    #[derive(Debug, Widget)]
+    #[handler(handle=noauto)]
+    struct Area {
+        #[widget_core]
+        core: CoreData,
+        recogniser: GestureRecogniser,
+        gestures: Vec<Gesture>,
+    }
+
+    impl Layout for Area {
+        fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+            SizeRules::fixed(200, (0, 0))
+        }
+
+        fn draw(&self, _: &mut dyn DrawHandle, _: &ManagerState, _: bool) {}
+    }
+
+    impl Handler for Area {
+        type Msg = VoidMsg;
+
+        fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<VoidMsg> {
+            let id = self.id();
+            let gestures = &mut self.gestures;
+            self.recogniser
+                .handle(mgr, id, event, |_, gesture| gestures.push(gesture))
+        }
+    }
+
+    fn setup(gestures: GestureSet) -> (Harness<Area>, Coord) {
+        let mut config = Config::default();
+        config.long_press_ms = 500;
+        let area = Area {
+            core: Default::default(),
+            recogniser: GestureRecogniser::new(gestures),
+            gestures: vec![],
+        };
+        let mut harness = Harness::new_config(area, Rc::new(RefCell::new(config)));
+        // Use the virtual clock
+        harness.advance_time(Duration::ZERO);
+        let pos = harness.widget().rect().pos;
+        (harness, pos)
+    }
+
+    fn take(harness: &mut Harness<Area>) -> Vec<Gesture> {
+        std::mem::take(&mut harness.widget_mut().gestures)
+    }
+
+    fn assert_near(a: DVec2, b: DVec2) {
+        assert!((a - b).abs().lt(DVec2::splat(1e-6)), "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn pinch() {
+        let (mut harness, pos) = setup(GestureSet::ZOOM);
+        harness.touch(1, pos + Offset(10, 50), TouchPhase::Started);
+        harness.touch(2, pos + Offset(90, 50), TouchPhase::Started);
+        assert_eq!(take(&mut harness), vec![]);
+
+        harness.touch(2, pos + Offset(130, 50), TouchPhase::Moved);
+        match take(&mut harness)[..] {
+            [Gesture::Zoom { scale, center }] => {
+                assert!((scale - 1.5).abs() < 1e-6);
+                assert_near(center, DVec2::from(pos + Offset(10, 50)));
+            }
+            ref gestures => panic!("unexpected gestures: {:?}", gestures),
+        }
+    }
+
+    #[test]
+    fn held_pinch() {
+        // Holding a pan still does not make it a long press
+        let (mut harness, pos) = setup(GestureSet::ZOOM);
+        harness.touch(1, pos + Offset(10, 50), TouchPhase::Started);
+        harness.touch(2, pos + Offset(90, 50), TouchPhase::Started);
+        harness.advance_time(Duration::from_secs(1));
+        harness.touch(2, pos + Offset(130, 50), TouchPhase::Moved);
+        match take(&mut harness)[..] {
+            [Gesture::Zoom { scale, .. }] => assert!((scale - 1.5).abs() < 1e-6),
+            ref gestures => panic!("unexpected gestures: {:?}", gestures),
+        }
+    }
+
+    #[test]
+    fn rotate() {
+        let (mut harness, pos) = setup(GestureSet::ROTATE);
+        harness.touch(1, pos + Offset(10, 50), TouchPhase::Started);
+        harness.touch(2, pos + Offset(90, 50), TouchPhase::Started);
+        harness.touch(2, pos + Offset(10, 130), TouchPhase::Moved);
+        match take(&mut harness)[..] {
+            [Gesture::Rotate { angle, center }] => {
+                assert!((angle - FRAC_PI_2).abs() < 1e-6);
+                assert_near(center, DVec2::from(pos + Offset(10, 50)));
+            }
+            ref gestures => panic!("unexpected gestures: {:?}", gestures),
+        }
+    }
+
+    #[test]
+    fn double_tap() {
+        let (mut harness, pos) = setup(GestureSet::DOUBLE_TAP);
+        let coord = pos + Offset(50, 50);
+        harness.click(coord);
+        harness.advance_time(Duration::from_millis(100));
+        harness.click(coord);
+        match take(&mut harness)[..] {
+            [Gesture::DoubleTap { coord: c, .. }] => assert_eq!(c, coord),
+            ref gestures => panic!("unexpected gestures: {:?}", gestures),
+        }
+
+        // The second tap must follow within the timeout
+        harness.advance_time(Duration::from_secs(1));
+        harness.click(coord);
+        harness.advance_time(DOUBLE_TAP_TIMEOUT);
+        harness.click(coord);
+        assert_eq!(take(&mut harness), vec![]);
+
+        // ... and at (nearly) the same position
+        harness.advance_time(Duration::from_secs(1));
+        harness.click(coord);
+        harness.click(coord + Offset(TAP_SLOP + 1, 0));
+        assert_eq!(take(&mut harness), vec![]);
+    }
+
+    #[test]
+    fn long_press() {
+        let (mut harness, pos) = setup(GestureSet::DOUBLE_TAP | GestureSet::LONG_PRESS);
+        let coord = pos + Offset(50, 50);
+        harness.mouse_move(coord);
+        harness.mouse_press(MouseButton::Left);
+        harness.advance_time(Duration::from_millis(499));
+        assert_eq!(take(&mut harness), vec![]);
+        harness.advance_time(Duration::from_millis(1));
+        match take(&mut harness)[..] {
+            [Gesture::LongPress { coord: c, .. }] => assert_eq!(c, coord),
+            ref gestures => panic!("unexpected gestures: {:?}", gestures),
+        }
+
+        // A long press cannot start a double-tap
+        harness.mouse_release(MouseButton::Left);
+        harness.click(coord);
+        assert_eq!(take(&mut harness), vec![]);
+
+        // Releasing or moving the press prevents a long press
+        harness.advance_time(Duration::from_secs(1));
+        harness.click(coord);
+        harness.advance_time(Duration::from_secs(1));
+        harness.mouse_press(MouseButton::Left);
+        harness.mouse_move(coord + Offset(TAP_SLOP + 1, 0));
+        harness.advance_time(Duration::from_secs(1));
+        harness.mouse_release(MouseButton::Left);
+        assert_eq!(take(&mut harness), vec![]);
+    }
+}
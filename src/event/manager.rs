@@ -14,15 +14,13 @@ use smallvec::SmallVec;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::u16;
 
 use super::*;
 use crate::conv::Cast;
-use crate::geom::Coord;
-#[allow(unused)]
-use crate::WidgetConfig; // for doc-links
-use crate::{ShellWindow, TkAction, Widget, WidgetId, WindowId};
+use crate::geom::{Coord, Rect};
+use crate::{ShellWindow, TkAction, Widget, WidgetConfig, WidgetId, WidgetPath, WindowId};
 
 mod mgr_pub;
 mod mgr_shell;
@@ -62,6 +60,20 @@ struct TouchGrab {
     pan_grab: (u16, u16),
 }
 
+#[derive(Clone, Debug)]
+struct PendingLongPress {
+    start_id: WidgetId,
+    coord: Coord,
+    time: Instant,
+}
+
+#[derive(Clone, Debug)]
+struct DragState {
+    source: PressSource,
+    payload: DragPayload,
+    target: Option<WidgetId>,
+}
+
 const MAX_PAN_GRABS: usize = 2;
 
 #[derive(Clone, Debug)]
@@ -104,11 +116,19 @@ pub struct ManagerState {
     /// char focus is on same widget as sel_focus; otherwise its value is ignored
     char_focus: bool,
     sel_focus: Option<WidgetId>,
+    /// Path of `sel_focus`, used to restore focus if its widget is replaced
+    sel_focus_path: Option<WidgetPath>,
     nav_focus: Option<WidgetId>,
+    /// Path of `nav_focus`, used to restore focus if its widget is replaced
+    nav_focus_path: Option<WidgetPath>,
     nav_fallback: Option<WidgetId>,
     nav_stack: SmallVec<[u32; 16]>,
     hover: Option<WidgetId>,
     hover_icon: CursorIcon,
+    /// Target of the shown tooltip
+    tooltip: Option<WidgetId>,
+    /// Target and time of a tooltip to be shown
+    tooltip_pending: Option<(WidgetId, Instant)>,
     key_depress: LinearMap<u32, WidgetId>,
     last_mouse_coord: Coord,
     last_click_button: MouseButton,
@@ -117,18 +137,34 @@ pub struct ManagerState {
     mouse_grab: Option<MouseGrab>,
     touch_grab: LinearMap<u64, TouchGrab>,
     pan_grab: SmallVec<[PanGrab; 4]>,
+    /// Touches which may become a long press
+    long_press: LinearMap<u64, PendingLongPress>,
+    drag: Option<DragState>,
+    /// Target of files dragged from other applications
+    file_hover: Option<WidgetId>,
+    /// Text cursor area for input method composition (pending)
+    ime_cursor: Option<(WidgetId, Rect)>,
     accel_stack: Vec<(bool, HashMap<VirtualKeyCode, WidgetId>)>,
     accel_layers: HashMap<WidgetId, (bool, HashMap<VirtualKeyCode, WidgetId>)>,
     popups: SmallVec<[(WindowId, kas::Popup); 16]>,
     new_popups: SmallVec<[WidgetId; 16]>,
     popup_removed: SmallVec<[(WidgetId, WindowId); 16]>,
+    chord: SmallVec<[KeyStroke; 4]>,
+    chord_timeout: Instant,
+    /// Received character is part of a chord
+    chord_char: bool,
 
     time_start: Instant,
+    /// Virtual clock used in place of system time (e.g. during replay)
+    virtual_time: Option<Instant>,
+    /// Start time and events of an active recording
+    recording: Option<(Instant, Recording)>,
     time_updates: Vec<(Instant, WidgetId)>,
     // TODO(opt): consider other containers, e.g. C++ multimap
     // or sorted Vec with binary search yielding a range
     handle_updates: HashMap<UpdateHandle, LinearSet<WidgetId>>,
     pending: SmallVec<[Pending; 8]>,
+    undo: undo::UndoStack,
     action: TkAction,
 }
 
@@ -217,6 +253,15 @@ impl ManagerState {
     }
 }
 
+/// Translate `rect` from the coordinate space of widget `id` to the window's
+fn window_rect(mut widget: &dyn WidgetConfig, id: WidgetId, mut rect: Rect) -> Rect {
+    while let Some(index) = widget.find_child(id) {
+        rect -= widget.translation(index);
+        widget = widget.get_child(index).unwrap();
+    }
+    rect
+}
+
 /// Manager of event-handling and toolkit actions
 ///
 /// A `Manager` is in fact a handle around [`ManagerState`] and [`ShellWindow`]
@@ -258,6 +303,11 @@ impl<'a> Manager<'a> {
             }
             self.state.hover = w_id;
 
+            self.hide_tooltip();
+            if let Some(id) = w_id {
+                self.schedule_tooltip(widget.id(), id);
+            }
+
             if let Some(id) = w_id {
                 let mut icon = widget.cursor_icon();
                 let mut widget = widget.as_widget();
@@ -278,31 +328,99 @@ impl<'a> Manager<'a> {
         }
     }
 
+    fn clear_chord(&mut self) {
+        if !self.state.chord.is_empty() {
+            trace!("Manager: clear chord");
+            self.state.chord.clear();
+            self.send_action(TkAction::REDRAW);
+        }
+    }
+
+    // Match key against shortcuts (including chords).
+    // Returns None when the key is consumed by a chord.
+    fn match_shortcut(&mut self, vkey: VirtualKeyCode) -> Option<Option<Command>> {
+        use VirtualKeyCode as VK;
+        self.state.chord_char = false;
+        match vkey {
+            VK::LShift | VK::RShift | VK::LControl | VK::RControl => return Some(None),
+            VK::LAlt | VK::RAlt | VK::LWin | VK::RWin => return Some(None),
+            _ => (),
+        }
+
+        let now = self.state.now();
+        if !self.state.chord.is_empty() && self.state.chord_timeout <= now {
+            self.clear_chord();
+        }
+
+        let config = self.state.config.borrow();
+        let m = config
+            .shortcuts
+            .get_chord(&self.state.chord, self.state.modifiers, vkey);
+        let timeout = Duration::from_millis(config.chord_timeout_ms.into());
+        drop(config);
+
+        let in_chord = !self.state.chord.is_empty();
+        match m {
+            ShortcutMatch::Prefix => {
+                self.state.chord.push((self.state.modifiers, vkey));
+                self.state.chord_timeout = now + timeout;
+                self.state.chord_char = true;
+                self.send_action(TkAction::REDRAW);
+                trace!("Manager: chord prefix {:?}", self.state.chord);
+                None
+            }
+            ShortcutMatch::Command(cmd) => {
+                self.clear_chord();
+                self.state.chord_char = in_chord;
+                Some(Some(cmd))
+            }
+            ShortcutMatch::None if in_chord => {
+                // An unmatched chord consumes the key
+                self.clear_chord();
+                self.state.chord_char = true;
+                None
+            }
+            ShortcutMatch::None => Some(None),
+        }
+    }
+
     fn start_key_event<W>(&mut self, widget: &mut W, vkey: VirtualKeyCode, scancode: u32)
     where
         W: Widget<Msg = VoidMsg> + ?Sized,
     {
         use VirtualKeyCode as VK;
-        let config = self.state.config.borrow();
-        let opt_command = config.shortcuts.get(self.state.modifiers, vkey);
-        drop(config);
+        let opt_command = match self.match_shortcut(vkey) {
+            Some(opt_command) => opt_command,
+            None => return,
+        };
         let shift = self.state.modifiers.shift();
+        self.hide_tooltip();
 
-        if self.state.char_focus {
-            if let Some(id) = self.state.sel_focus {
-                if let Some(cmd) = opt_command {
-                    let event = Event::Command(cmd, shift);
-                    trace!("Send to {}: {:?}", id, event);
-                    match widget.send(self, id, event) {
-                        Response::Unhandled => match cmd {
-                            Command::Escape => self.set_char_focus(None),
-                            _ => (),
-                        },
+        if let Some(id) = self.char_focus_target(widget) {
+            if let Some(cmd) = opt_command {
+                let event = Event::Command(cmd, shift);
+                trace!("Send to {}: {:?}", id, event);
+                match widget.send(self, id, event) {
+                    Response::Unhandled => match cmd {
+                        Command::Escape => self.set_char_focus(None),
+                        Command::Undo => {
+                            self.apply_undo(widget, false);
+                        }
+                        Command::Redo => {
+                            self.apply_undo(widget, true);
+                        }
+                        Command::Menu => {
+                            self.open_context_menu(widget, id, None);
+                        }
+                        Command::Tooltip => {
+                            self.show_tooltip(widget, id, None);
+                        }
                         _ => (),
-                    }
+                    },
+                    _ => (),
                 }
-                return;
             }
+            return;
         }
 
         if vkey == VK::Tab {
@@ -370,11 +488,31 @@ impl<'a> Manager<'a> {
             }
         }
 
+        let undo_cmd = match opt_command {
+            Some(Command::Undo) => Some(false),
+            Some(Command::Redo) => Some(true),
+            _ => None,
+        };
+
         if let Some((id, event)) = id_action {
             let is_activate = event == Event::Activate;
             trace!("Send to {}: {:?}", id, event);
-            match widget.send(self, id, event) {
-                Response::Unhandled if vkey == VK::Escape => {
+            match (widget.send(self, id, event), undo_cmd) {
+                (Response::Unhandled, Some(redo)) if !is_activate => {
+                    self.apply_undo(widget, redo);
+                }
+                (Response::Unhandled, _) if !is_activate && opt_command == Some(Command::Menu) => {
+                    if self.state.popups.is_empty() {
+                        self.open_context_menu(widget, id, None);
+                    }
+                }
+                (Response::Unhandled, _)
+                    if opt_command == Some(Command::Tooltip)
+                        && self.state.nav_focus == Some(id) =>
+                {
+                    self.show_tooltip(widget, id, None);
+                }
+                (Response::Unhandled, _) if vkey == VK::Escape => {
                     // When unhandled, the Escape key causes other actions
                     if let Some(id) = self.state.popups.last().map(|(id, _)| *id) {
                         self.close_window(id);
@@ -396,6 +534,11 @@ impl<'a> Manager<'a> {
                 self.state.key_depress.insert(scancode, id);
                 self.redraw(id);
             }
+        } else if let Some(redo) = undo_cmd {
+            self.apply_undo(widget, redo);
+        } else if opt_command == Some(Command::Menu) && self.state.popups.is_empty() {
+            let id = self.state.sel_focus.unwrap_or_else(|| widget.id());
+            self.open_context_menu(widget, id, None);
         }
     }
 
@@ -487,6 +630,305 @@ impl<'a> Manager<'a> {
         let _ = widget.send(self, id, event);
     }
 
+    /// Undo (or redo) the last operation in the window's history
+    ///
+    /// Returns false if there is nothing to undo (redo).
+    fn apply_undo<W: Widget + ?Sized>(&mut self, widget: &mut W, redo: bool) -> bool {
+        loop {
+            let entry = match redo {
+                false => self.state.undo.pop_undo(),
+                true => self.state.undo.pop_redo(),
+            };
+            let (id, op) = match entry {
+                Some(entry) => entry,
+                None => return false,
+            };
+            // Operations on widgets which no longer exist are discarded, as
+            // are those which no longer apply (e.g. due to history limits)
+            match widget.find_leaf(id) {
+                Some(w) if op.is_applicable(w.as_any(), redo) => (),
+                _ => {
+                    trace!("Manager: discarding undo entry for {}: {:?}", id, op);
+                    continue;
+                }
+            }
+
+            let request = UndoRequest::new(op, redo);
+            self.send_event(widget, id, Event::Undo(request.clone()));
+            if let Some(w) = widget.find_leaf_mut(id) {
+                // Apply if the widget did not handle the event
+                request.apply_fallback(w.as_any_mut(), self);
+            }
+
+            if let Some(op) = request.take_op() {
+                match redo {
+                    false => self.state.undo.push_undone((id, op)),
+                    true => self.state.undo.push_redone((id, op)),
+                }
+            }
+            return true;
+        }
+    }
+
+    /// Get the widget with char focus, if active
+    ///
+    /// Char focus is suspended while a pop-up not containing the widget is
+    /// open (e.g. a context menu).
+    fn char_focus_target<W: Widget + ?Sized>(&self, widget: &W) -> Option<WidgetId> {
+        if !self.state.char_focus {
+            return None;
+        }
+        let id = self.state.sel_focus?;
+        if let Some(popup) = self.state.popups.last() {
+            match widget.find_leaf(popup.1.id) {
+                Some(w) if w.is_ancestor_of(id) => (),
+                _ => return None,
+            }
+        }
+        Some(id)
+    }
+
+    /// Request that the window opens a context menu for `target`
+    ///
+    /// Returns false if no menu was opened.
+    fn open_context_menu<W>(
+        &mut self,
+        widget: &mut W,
+        target: WidgetId,
+        coord: Option<Coord>,
+    ) -> bool
+    where
+        W: Widget + ?Sized,
+    {
+        let event = Event::OpenContextMenu { target, coord };
+        trace!("Send to {}: {:?}", widget.id(), event);
+        match widget.send(self, widget.id(), event) {
+            Response::Unhandled => false,
+            _ => true,
+        }
+    }
+
+    /// Schedule a tooltip for `target` after the configured delay
+    ///
+    /// The timer is registered for the window (`root`); see `update_timer`.
+    fn schedule_tooltip(&mut self, root: WidgetId, target: WidgetId) {
+        let delay = self.state.config.borrow().tooltip_delay_ms;
+        if delay == 0 || self.state.mouse_grab.is_some() {
+            return;
+        }
+        let delay = Duration::from_millis(delay.into());
+        self.state.tooltip_pending = Some((target, self.state.now() + delay));
+        self.update_on_timer(delay, root);
+    }
+
+    /// Request that the window shows the tooltip for `target`
+    ///
+    /// Returns false if no tooltip is shown.
+    fn show_tooltip<W>(&mut self, widget: &mut W, target: WidgetId, coord: Option<Coord>) -> bool
+    where
+        W: Widget + ?Sized,
+    {
+        self.state.tooltip_pending = None;
+        let event = Event::OpenTooltip { target, coord };
+        trace!("Send to {}: {:?}", widget.id(), event);
+        match widget.send(self, widget.id(), event) {
+            Response::Unhandled => false,
+            _ => {
+                self.state.tooltip = Some(target);
+                self.send_action(TkAction::REDRAW);
+                true
+            }
+        }
+    }
+
+    /// Hide the tooltip and cancel any pending tooltip
+    fn hide_tooltip(&mut self) {
+        self.state.tooltip_pending = None;
+        if self.state.tooltip.take().is_some() {
+            self.send_action(TkAction::REDRAW);
+        }
+    }
+
+    /// Start timing a touch which may become a long press
+    ///
+    /// Only touches with a [`GrabMode::Grab`] press grab are timed.
+    /// The timer is registered for the window (`root`); see `update_timer`.
+    fn schedule_long_press(&mut self, root: WidgetId, touch_id: u64, coord: Coord) {
+        let start_id = match self.get_touch(touch_id) {
+            Some(grab) if grab.mode == GrabMode::Grab => grab.start_id,
+            _ => return,
+        };
+        let delay = self.state.config.borrow().long_press_ms;
+        if delay == 0 {
+            return;
+        }
+        let delay = Duration::from_millis(delay.into());
+        let time = self.state.now() + delay;
+        let press = PendingLongPress {
+            start_id,
+            coord,
+            time,
+        };
+        self.state.long_press.insert(touch_id, press);
+        self.update_on_timer(delay, root);
+    }
+
+    /// Stop timing a long press for this touch
+    fn cancel_long_press(&mut self, touch_id: u64) {
+        self.state.long_press.remove(&touch_id);
+    }
+
+    /// Send [`Event::LongPress`] for a held touch
+    ///
+    /// If unhandled, this cancels the press and opens a context menu.
+    fn long_press<W>(&mut self, widget: &mut W, touch_id: u64, press: PendingLongPress)
+    where
+        W: Widget + ?Sized,
+    {
+        match self.get_touch(touch_id) {
+            Some(grab) if grab.mode == GrabMode::Grab => (),
+            _ => return,
+        }
+
+        let source = PressSource::Touch(touch_id);
+        let coord = press.coord;
+        let event = Event::LongPress { source, coord };
+        trace!("Send to {}: {:?}", press.start_id, event);
+        match widget.send(self, press.start_id, event) {
+            Response::Unhandled => (),
+            _ => return,
+        }
+
+        if let Some(grab) = self.remove_touch(touch_id) {
+            if let Some(cur_id) = grab.cur_id {
+                self.redraw(cur_id);
+            }
+            let event = Event::PressEnd {
+                source,
+                end_id: None,
+                coord: grab.coord,
+            };
+            self.send_event(widget, grab.start_id, event);
+        }
+        if self.state.popups.is_empty() {
+            self.open_context_menu(widget, press.start_id, Some(coord));
+        }
+    }
+
+    /// Is a drag active from this `source`?
+    fn is_drag_source(&self, source: PressSource) -> bool {
+        match (self.state.drag.as_ref().map(|drag| drag.source), source) {
+            (Some(PressSource::Mouse(_, _)), PressSource::Mouse(_, _)) => true,
+            (Some(PressSource::Touch(a)), PressSource::Touch(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Find the drop target under `cur_id` and update the drag state
+    fn drag_move<W>(&mut self, widget: &mut W, cur_id: Option<WidgetId>, coord: Coord)
+    where
+        W: Widget + ?Sized,
+    {
+        let (source, payload) = match self.state.drag.as_ref() {
+            Some(drag) => (drag.source, drag.payload.clone()),
+            None => return,
+        };
+
+        // Candidates are cur_id and its ancestors, nearest first
+        let mut path: SmallVec<[WidgetId; 16]> = SmallVec::new();
+        if let Some(id) = cur_id {
+            let mut w = widget.as_widget();
+            path.push(w.id());
+            while let Some(index) = w.find_child(id) {
+                w = w.get_child(index).unwrap();
+                path.push(w.id());
+            }
+        }
+
+        let mut target = None;
+        for id in path.into_iter().rev() {
+            let event = Event::DragMove {
+                payload: payload.clone(),
+                coord,
+            };
+            trace!("Send to {}: {:?}", id, event);
+            match widget.send(self, id, event) {
+                Response::Unhandled => (),
+                _ => {
+                    target = Some(id);
+                    break;
+                }
+            }
+        }
+
+        let old_target = match self.state.drag.as_mut() {
+            Some(drag) => std::mem::replace(&mut drag.target, target),
+            None => return,
+        };
+        if old_target != target {
+            if let Some(id) = old_target {
+                self.send_event(widget, id, Event::DragLeave);
+            }
+            if let PressSource::Mouse(_, _) = source {
+                let icon = match target.is_some() {
+                    true => CursorIcon::Grabbing,
+                    false => CursorIcon::NoDrop,
+                };
+                self.shell.set_cursor_icon(icon);
+            }
+        }
+    }
+
+    /// End the drag, dropping the payload on the target unless `cancel`
+    fn drag_end<W>(&mut self, widget: &mut W, coord: Coord, cancel: bool)
+    where
+        W: Widget + ?Sized,
+    {
+        let drag = match self.state.drag.take() {
+            Some(drag) => drag,
+            None => return,
+        };
+        trace!("Manager: end drag by {}", drag.payload.source());
+
+        let mut target = drag.target;
+        if let Some(id) = target {
+            if cancel {
+                self.send_event(widget, id, Event::DragLeave);
+                target = None;
+            } else {
+                let event = Event::Drop {
+                    payload: drag.payload.clone(),
+                    coord,
+                };
+                trace!("Send to {}: {:?}", id, event);
+                if let Response::Unhandled = widget.send(self, id, event) {
+                    target = None;
+                }
+            }
+        }
+
+        let id = drag.payload.source();
+        self.send_event(widget, id, Event::DragEnd { target });
+    }
+
+    /// Find the widget accepting files at the mouse cursor
+    ///
+    /// This is the hovered widget or its nearest ancestor accepting files.
+    fn file_target<W: Widget + ?Sized>(&self, widget: &W) -> Option<WidgetId> {
+        let id = widget.find_id(self.state.last_mouse_coord)?;
+        let mut w = widget.as_widget();
+        let mut target = None;
+        loop {
+            if w.accept_files() {
+                target = Some(w.id());
+            }
+            match w.find_child(id) {
+                Some(index) => w = w.get_child(index).unwrap(),
+                None => return target,
+            }
+        }
+    }
+
     fn send_popup_first<W: Widget + ?Sized>(&mut self, widget: &mut W, id: WidgetId, event: Event) {
         while let Some((wid, parent)) = self.state.popups.last().map(|(wid, p)| (*wid, p.parent)) {
             trace!("Send to popup parent: {}: {:?}", parent, event);
@@ -539,4 +981,247 @@ impl<'a: 'b, 'b> ConfigureManager<'a, 'b> {
     pub fn mgr(&mut self) -> &mut Manager<'a> {
         self.mgr
     }
+
+    /// Configure a child widget
+    ///
+    /// This assigns `name` (usually from
+    /// [`crate::WidgetChildren::child_name`]) to the child if it has no name,
+    /// then calls [`WidgetConfig::configure_recurse`] on the child. Widgets
+    /// overriding `configure_recurse` should use this for each child.
+    pub fn configure_child(&mut self, child: &mut dyn WidgetConfig, name: Option<&str>) {
+        if let (Some(name), None) = (name, child.name()) {
+            child.set_name(name);
+        }
+        child.configure_recurse(self.child());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::class::HasStr;
+    use crate::prelude::*;
+    use crate::testing::Harness;
+    use crate::widget::{Column, EditBox, Label, WithTooltip};
+    use std::path::PathBuf;
+
+    // Record file events as text
+    fn record_file_event(events: &mut Vec<String>, event: Event) -> Response<VoidMsg> {
+        events.push(match event {
+            Event::FileHover(path) => format!("hover {}", path.display()),
+            Event::FileHoverCancelled => "cancel".to_string(),
+            Event::FileDrop(path) => format!("drop {}", path.display()),
+            _ => return Response::Unhandled,
+        });
+        Response::None
+    }
+
+    #[derive(Debug, Widget)]
+    #[layout(single)]
+    #[widget(config=noauto)]
+    #[handler(handle=noauto)]
+    struct DropArea {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        label: Label<&'static str>,
+        events: Vec<String>,
+    }
+
+    impl WidgetConfig for DropArea {
+        fn accept_files(&self) -> bool {
+            true
+        }
+    }
+
+    impl Handler for DropArea {
+        type Msg = VoidMsg;
+
+        fn handle(&mut self, _: &mut Manager, event: Event) -> Response<VoidMsg> {
+            record_file_event(&mut self.events, event)
+        }
+    }
+
+    #[derive(Debug, Widget)]
+    #[layout(row)]
+    #[widget(config=noauto)]
+    #[handler(handle=noauto)]
+    struct Outer {
+        #[widget_core]
+        core: CoreData,
+        #[layout_data]
+        layout_data: <Self as LayoutData>::Data,
+        #[widget]
+        inner: DropArea,
+        #[widget]
+        label: Label<&'static str>,
+        events: Vec<String>,
+    }
+
+    impl WidgetConfig for Outer {
+        fn accept_files(&self) -> bool {
+            true
+        }
+    }
+
+    impl Handler for Outer {
+        type Msg = VoidMsg;
+
+        fn handle(&mut self, _: &mut Manager, event: Event) -> Response<VoidMsg> {
+            record_file_event(&mut self.events, event)
+        }
+    }
+
+    #[test]
+    fn chords() {
+        use VirtualKeyCode as VK;
+        const CTRL: ModifiersState = ModifiersState::CTRL;
+        let mut config = Config::default();
+        config.shortcuts = Shortcuts::new();
+        let chord = [(CTRL, VK::K), (CTRL, VK::A)];
+        config.shortcuts.bind_chord(&chord, Command::SelectAll);
+        let timeout = Duration::from_millis(config.chord_timeout_ms.into());
+        let config = Rc::new(RefCell::new(config));
+
+        let mut harness = Harness::new_config(EditBox::new("abc"), config);
+        let rect = harness.widget().rect();
+        harness.click(rect.pos + rect.size / 2);
+        harness.key(VK::End);
+
+        // Key presses with Ctrl generate control codes, which a chord consumes
+        let ctrl_key = |harness: &mut Harness<_>, vkey, c| {
+            harness.set_modifiers(CTRL);
+            harness.key(vkey);
+            harness.received_char(c);
+            harness.set_modifiers(ModifiersState::empty());
+        };
+
+        ctrl_key(&mut harness, VK::K, '\u{b}');
+        assert_eq!(harness.state().pending_chord(), &chord[..1]);
+        ctrl_key(&mut harness, VK::A, '\u{1}');
+        assert_eq!(harness.state().pending_chord(), &[]);
+        harness.type_str("x");
+        assert_eq!(harness.widget().get_str(), "x");
+
+        // After the timeout, Ctrl+A is not part of a chord (and is unbound)
+        ctrl_key(&mut harness, VK::K, '\u{b}');
+        assert_eq!(harness.state().pending_chord(), &chord[..1]);
+        harness.advance_time(timeout);
+        assert_eq!(harness.state().pending_chord(), &[]);
+        ctrl_key(&mut harness, VK::A, '\u{1}');
+        harness.type_str("y");
+        assert_eq!(harness.widget().get_str(), "xy");
+    }
+
+    #[test]
+    fn tooltip_delay() {
+        let mut config = Config::default();
+        config.tooltip_delay_ms = 100;
+        let config = Rc::new(RefCell::new(config));
+        let delay = Duration::from_millis(50);
+
+        let label = WithTooltip::new(Label::new("label"), "tip");
+        let mut harness = Harness::new_config(label, config.clone());
+        let rect = harness.widget().rect();
+        let coord = rect.pos + rect.size / 2;
+        let target = harness.find_id(coord);
+        assert!(target.is_some());
+
+        harness.mouse_move(coord);
+        harness.advance_time(delay);
+        assert_eq!(harness.state().tooltip(), None);
+        harness.advance_time(delay);
+        assert_eq!(harness.state().tooltip(), target);
+
+        // Leaving the widget hides the tooltip
+        harness.mouse_leave();
+        assert_eq!(harness.state().tooltip(), None);
+
+        // With a zero delay, hovering never shows a tooltip
+        config.borrow_mut().tooltip_delay_ms = 0;
+        harness.mouse_move(coord);
+        harness.advance_time(delay * 10);
+        assert_eq!(harness.state().tooltip(), None);
+    }
+
+    #[test]
+    fn file_drop() {
+        let outer = Outer {
+            core: Default::default(),
+            layout_data: Default::default(),
+            inner: DropArea {
+                core: Default::default(),
+                label: Label::new("inner"),
+                events: vec![],
+            },
+            label: Label::new("outer"),
+            events: vec![],
+        };
+        let mut harness = Harness::new(outer);
+        let center = |rect: Rect| rect.pos + rect.size / 2;
+        let inner = center(harness.widget().inner.rect());
+        let outer = center(harness.widget().label.rect());
+        let path = PathBuf::from("a.txt");
+        let take_events = |harness: &mut Harness<Outer>| {
+            let w = harness.widget_mut();
+            (
+                std::mem::take(&mut w.inner.events),
+                std::mem::take(&mut w.events),
+            )
+        };
+
+        // Files go to the nearest ancestor accepting files
+        harness.mouse_move(outer);
+        harness.input(InputEvent::FileHover(path.clone()));
+        assert_eq!(
+            take_events(&mut harness),
+            (vec![], vec!["hover a.txt".into()])
+        );
+        harness.mouse_move(inner);
+        harness.input(InputEvent::FileHover(path.clone()));
+        assert_eq!(
+            take_events(&mut harness),
+            (vec!["hover a.txt".into()], vec!["cancel".into()])
+        );
+        harness.input(InputEvent::FileDrop(path.clone()));
+        assert_eq!(
+            take_events(&mut harness),
+            (vec!["drop a.txt".into()], vec![])
+        );
+
+        // Hovering files may be cancelled
+        harness.input(InputEvent::FileHover(path.clone()));
+        harness.input(InputEvent::FileHoverCancelled);
+        let events = vec!["hover a.txt".into(), "cancel".into()];
+        assert_eq!(take_events(&mut harness), (events, vec![]));
+
+        // Outside of the window, files are not delivered
+        harness.mouse_leave();
+        harness.input(InputEvent::FileDrop(path));
+        assert_eq!(take_events(&mut harness), (vec![], vec![]));
+    }
+
+    #[test]
+    fn reconfigure_focus() {
+        let boxes = |names: &[&str]| -> Vec<EditBox> {
+            let boxes = names.iter().map(|name| EditBox::new(*name).with_name(name));
+            boxes.collect()
+        };
+        let mut harness = Harness::new(Column::new(boxes(&["a", "b"])));
+        let rect = harness.widget()[1].rect();
+        harness.click(rect.pos + rect.size / 2);
+
+        // Replace all boxes, inserting a new box before "b"
+        harness.with_manager(|mgr, column| {
+            *mgr |= column.clear();
+            *mgr |= column.extend(boxes(&["new", "a", "b"]));
+        });
+        let id = harness.widget()[2].id();
+        assert_eq!(harness.id_of_name("b"), Some(id));
+        assert_eq!(harness.path_of(id).unwrap().to_string(), "/@b");
+        assert!(harness.state().nav_focus(id));
+        assert_eq!(harness.state().char_focus(id), (true, true));
+        harness.type_str("c");
+        assert_eq!(harness.widget()[2].get_str(), "bc");
+    }
 }
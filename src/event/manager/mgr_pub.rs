@@ -6,15 +6,14 @@
 //! Event manager — public API
 
 use log::{debug, trace, warn};
+use std::cell::{Ref, RefMut};
 use std::time::{Duration, Instant};
 use std::u16;
 
 use super::*;
 use crate::draw::SizeHandle;
-use crate::geom::Coord;
-#[allow(unused)]
-use crate::WidgetConfig; // for doc-links
-use crate::{ThemeAction, ThemeApi, TkAction, WidgetId, WindowId};
+use crate::geom::{Coord, Rect};
+use crate::{ThemeAction, ThemeApi, TkAction, Widget, WidgetConfig, WidgetId, WindowId};
 
 impl<'a> std::ops::BitOrAssign<TkAction> for Manager<'a> {
     #[inline]
@@ -25,6 +24,28 @@ impl<'a> std::ops::BitOrAssign<TkAction> for Manager<'a> {
 
 /// Public API (around event manager state)
 impl ManagerState {
+    /// Get the pending chord prefix, if any
+    ///
+    /// When the user has pressed the first stroke(s) of a multi-stroke
+    /// shortcut (see [`Shortcuts::bind_chord`]), these strokes are returned
+    /// until the chord is completed, cancelled or times out. Otherwise this
+    /// is empty.
+    ///
+    /// The window is redrawn whenever this changes, thus a widget (e.g. a
+    /// status bar) may read this value while drawing. Use
+    /// [`Shortcuts::describe`] to format.
+    #[inline]
+    pub fn pending_chord(&self) -> &[KeyStroke] {
+        &self.chord
+    }
+
+    /// Describe the shortcut bound to a command, if any
+    ///
+    /// See [`Shortcuts::describe_command`].
+    pub fn shortcut_text(&self, command: Command) -> Option<String> {
+        self.config.borrow().shortcuts.describe_command(command)
+    }
+
     /// True when accelerator key labels should be shown
     ///
     /// (True when Alt is held and no widget has character focus.)
@@ -63,6 +84,25 @@ impl ManagerState {
         self.mouse_grab.is_none() && self.hover == Some(w_id)
     }
 
+    /// Get the target of the shown tooltip, if any
+    ///
+    /// See [`Event::OpenTooltip`]. The window is redrawn whenever this
+    /// changes.
+    #[inline]
+    pub fn tooltip(&self) -> Option<WidgetId> {
+        self.tooltip
+    }
+
+    /// Get the current time
+    ///
+    /// This is the system time unless a virtual clock is in use (see
+    /// [`Replay`]). Widgets measuring time (e.g. for animation) should use
+    /// this instead of [`Instant::now`] so that replay is deterministic.
+    #[inline]
+    pub fn now(&self) -> Instant {
+        self.virtual_time.unwrap_or_else(Instant::now)
+    }
+
     /// Check whether the given widget is visually depressed
     #[inline]
     pub fn is_depressed(&self, w_id: WidgetId) -> bool {
@@ -110,7 +150,7 @@ impl<'a> Manager<'a> {
             w_id,
             duration.as_millis()
         );
-        let time = Instant::now() + duration;
+        let time = self.state.now() + duration;
         'outer: loop {
             for row in &mut self.state.time_updates {
                 if row.1 == w_id {
@@ -203,6 +243,7 @@ impl<'a> Manager<'a> {
         self.state.new_popups.push(popup.id);
         self.state.popups.push((id, popup));
         self.state.nav_focus = None;
+        self.state.nav_focus_path = None;
         self.state.nav_stack.clear();
         id
     }
@@ -276,6 +317,98 @@ impl<'a> Manager<'a> {
         self.shell.set_clipboard(content)
     }
 
+    /// Record a reversible operation in the window's undo history
+    ///
+    /// The operation applies to the widget `id` (usually the caller). Pushing
+    /// an operation clears the redo history.
+    ///
+    /// Undo and redo are triggered by [`Command::Undo`] and [`Command::Redo`]
+    /// when not handled by the widget with focus, or by [`Manager::undo`] and
+    /// [`Manager::redo`].
+    pub fn push_undo(&mut self, id: WidgetId, op: Box<dyn UndoOp>) {
+        trace!("Manager::push_undo: {}: {:?}", id, op);
+        self.state.undo.push(id, op);
+    }
+
+    /// True if the window's undo history is not empty
+    #[inline]
+    pub fn can_undo(&self) -> bool {
+        self.state.undo.can_undo()
+    }
+
+    /// True if the window's redo history is not empty
+    #[inline]
+    pub fn can_redo(&self) -> bool {
+        self.state.undo.can_redo()
+    }
+
+    /// Undo the last operation in the window's history
+    ///
+    /// This happens after event handling completes.
+    #[inline]
+    pub fn undo(&mut self) {
+        self.state.undo.pending.push(false);
+    }
+
+    /// Redo the last undone operation in the window's history
+    ///
+    /// This happens after event handling completes.
+    #[inline]
+    pub fn redo(&mut self) {
+        self.state.undo.pending.push(true);
+    }
+
+    /// Clear the window's undo and redo history
+    #[inline]
+    pub fn clear_undo_history(&mut self) {
+        self.state.undo.clear();
+    }
+
+    /// Get the current time
+    ///
+    /// See [`ManagerState::now`].
+    #[inline]
+    pub fn now(&self) -> Instant {
+        self.state.now()
+    }
+
+    /// Start recording input events
+    ///
+    /// All input to this window is recorded (with timestamps) until
+    /// [`Manager::stop_recording`] is called. Any previous recording is
+    /// discarded.
+    pub fn start_recording(&mut self) {
+        debug!("Manager: start recording input");
+        self.state.recording = Some((self.state.now(), Recording::new()));
+    }
+
+    /// Stop recording, returning the recorded input (if recording)
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        self.state.recording.take().map(|(_, recording)| recording)
+    }
+
+    /// True if input is being recorded
+    #[inline]
+    pub fn is_recording(&self) -> bool {
+        self.state.recording.is_some()
+    }
+
+    /// Access event-handling configuration
+    #[inline]
+    pub fn config(&self) -> Ref<Config> {
+        self.state.config.borrow()
+    }
+
+    /// Access event-handling configuration mutably
+    ///
+    /// This may be used to adjust shortcuts at run-time. Changes apply to all
+    /// windows sharing this configuration. The returned guard must be dropped
+    /// before further events are handled.
+    #[inline]
+    pub fn config_mut(&mut self) -> RefMut<Config> {
+        self.state.config.borrow_mut()
+    }
+
     /// Adjust the theme
     #[inline]
     pub fn adjust_theme<F: FnMut(&mut dyn ThemeApi) -> ThemeAction>(&mut self, mut f: F) {
@@ -398,6 +531,36 @@ impl<'a> Manager<'a> {
         }
     }
 
+    /// Set the area of the text cursor used for input method composition
+    ///
+    /// Widgets supporting [`Event::ImePreedit`] should call this on gaining
+    /// char focus and whenever the text cursor moves (including by scrolling).
+    /// `rect` is in the coordinate space of widget `id` (as for its own
+    /// [`kas::Layout::set_rect`]); this is translated to window coordinates
+    /// and passed to the shell.
+    ///
+    /// This does nothing unless widget `id` has char focus.
+    pub fn set_ime_cursor_area(&mut self, id: WidgetId, rect: Rect) {
+        if self.state.char_focus && self.state.sel_focus == Some(id) {
+            self.state.ime_cursor = Some((id, rect));
+        }
+    }
+
+    /// Check whether widget `id` has a grab on the given input `source`
+    ///
+    /// This is true from a successful [`Manager::request_grab`] until the
+    /// press is released, for any [`GrabMode`].
+    pub(crate) fn has_grab(&self, id: WidgetId, source: PressSource) -> bool {
+        match source {
+            PressSource::Mouse(button, _) => (self.state.mouse_grab.as_ref())
+                .map(|grab| grab.button == button && grab.start_id == id)
+                .unwrap_or(false),
+            PressSource::Touch(touch_id) => (self.state.touch_grab.get(&touch_id))
+                .map(|grab| grab.start_id == id)
+                .unwrap_or(false),
+        }
+    }
+
     /// Request a grab on the given input `source`
     ///
     /// On success, this method returns true and corresponding mouse/touch
@@ -495,6 +658,56 @@ impl<'a> Manager<'a> {
         true
     }
 
+    /// Start a drag-and-drop operation
+    ///
+    /// This converts a [`GrabMode::Grab`] press grab by widget `id` on
+    /// `source` into a drag of `payload`; if no such grab exists, one is
+    /// requested (see [`Manager::request_grab`]). Usually a widget calls this
+    /// on [`Event::PressMove`] after acquiring a grab on [`Event::PressStart`].
+    /// Returns false if no grab could be obtained or a drag is already active.
+    ///
+    /// While the drag is active, movement of the press sends
+    /// [`Event::DragMove`] and [`Event::DragLeave`] to potential drop targets
+    /// instead of sending [`Event::PressMove`] to widget `id`. On release of
+    /// the press, [`Event::Drop`] is sent to the target accepting the drop (if
+    /// any), then [`Event::DragEnd`] is sent to widget `id` instead of
+    /// [`Event::PressEnd`].
+    pub fn start_drag(
+        &mut self,
+        id: WidgetId,
+        source: PressSource,
+        coord: Coord,
+        mut payload: DragPayload,
+    ) -> bool {
+        if self.read_only || self.state.drag.is_some() {
+            return false;
+        }
+
+        let grab = match source {
+            PressSource::Mouse(_, _) => {
+                self.state.mouse_grab.as_ref().map(|g| (g.start_id, g.mode))
+            }
+            PressSource::Touch(t) => self.state.touch_grab.get(&t).map(|g| (g.start_id, g.mode)),
+        };
+        if grab != Some((id, GrabMode::Grab))
+            && !self.request_grab(id, source, coord, GrabMode::Grab, None)
+        {
+            return false;
+        }
+
+        trace!("Manager: start drag by {}", id);
+        payload.set_source(id);
+        self.state.drag = Some(DragState {
+            source,
+            payload,
+            target: None,
+        });
+        if let PressSource::Mouse(_, _) = source {
+            self.shell.set_cursor_icon(CursorIcon::NoDrop);
+        }
+        true
+    }
+
     /// Update the mouse cursor used during a grab
     ///
     /// This only succeeds if widget `id` has an active mouse-grab (see
@@ -548,6 +761,7 @@ impl<'a> Manager<'a> {
             self.redraw(id);
         }
         self.state.nav_focus = None;
+        self.state.nav_focus_path = None;
         self.state.nav_stack.clear();
         trace!("Manager: nav_focus = None");
     }
@@ -559,10 +773,50 @@ impl<'a> Manager<'a> {
     pub fn set_nav_focus(&mut self, id: WidgetId) {
         self.redraw(id);
         self.state.nav_focus = Some(id);
+        self.state.nav_focus_path = None;
         self.state.nav_stack.clear();
         trace!("Manager: nav_focus = Some({})", id);
     }
 
+    /// Set the keyboard navigation focus to a named widget
+    ///
+    /// The first widget under `widget` with this `name` is found via
+    /// [`WidgetChildren::find_by_name`]. Returns false (leaving focus
+    /// unchanged) if no such widget is found or if the widget is disabled or
+    /// does not support navigation focus ([`WidgetConfig::key_nav`]).
+    ///
+    /// [`WidgetChildren::find_by_name`]: crate::WidgetChildren::find_by_name
+    pub fn set_nav_focus_by_name(&mut self, widget: &dyn WidgetConfig, name: &str) -> bool {
+        match widget.find_by_name(name) {
+            Some(w) if w.key_nav() && !w.is_disabled() => {
+                self.set_nav_focus(w.id());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Send an event to a named widget
+    ///
+    /// The first widget under `widget` with this `name` is found via
+    /// [`WidgetChildren::find_by_name`]; the event is sent to this widget
+    /// through `widget` (thus parents may intercept the response).
+    ///
+    /// Returns [`Response::Unhandled`] if no such widget is found.
+    ///
+    /// [`WidgetChildren::find_by_name`]: crate::WidgetChildren::find_by_name
+    pub fn send_by_name<W: Widget + ?Sized>(
+        &mut self,
+        widget: &mut W,
+        name: &str,
+        event: Event,
+    ) -> Response<W::Msg> {
+        match widget.find_by_name(name).map(|w| w.id()) {
+            Some(id) => widget.send(self, id, event),
+            None => Response::Unhandled,
+        }
+    }
+
     /// Advance the keyboard navigation focus
     ///
     /// If some widget currently has nav focus, this will give focus to the next
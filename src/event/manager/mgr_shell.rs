@@ -9,10 +9,13 @@ use log::*;
 use smallvec::SmallVec;
 use std::collections::HashMap;
 use std::mem::swap;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 use super::*;
 use crate::conv::Conv;
+use crate::event::gesture::TAP_SLOP;
+use crate::event::record::next_timer;
 use crate::geom::{Coord, DVec2, Offset};
 #[allow(unused)]
 use crate::WidgetConfig; // for doc-links
@@ -35,11 +38,15 @@ impl ManagerState {
             modifiers: ModifiersState::empty(),
             char_focus: false,
             sel_focus: None,
+            sel_focus_path: None,
             nav_focus: None,
+            nav_focus_path: None,
             nav_fallback: None,
             nav_stack: SmallVec::new(),
             hover: None,
             hover_icon: CursorIcon::Default,
+            tooltip: None,
+            tooltip_pending: None,
             key_depress: Default::default(),
             last_mouse_coord: Coord::ZERO,
             last_click_button: FAKE_MOUSE_BUTTON,
@@ -48,16 +55,26 @@ impl ManagerState {
             mouse_grab: None,
             touch_grab: Default::default(),
             pan_grab: SmallVec::new(),
+            long_press: Default::default(),
+            drag: None,
+            file_hover: None,
+            ime_cursor: None,
             accel_stack: vec![],
             accel_layers: HashMap::new(),
             popups: Default::default(),
             new_popups: Default::default(),
             popup_removed: Default::default(),
+            chord: Default::default(),
+            chord_timeout: Instant::now(), // unimportant value
+            chord_char: false,
 
             time_start: Instant::now(),
+            virtual_time: None,
+            recording: None,
             time_updates: vec![],
             handle_updates: HashMap::new(),
             pending: SmallVec::new(),
+            undo: Default::default(),
             action: TkAction::empty(),
         }
     }
@@ -71,6 +88,12 @@ impl ManagerState {
     /// [`WidgetId`] identifiers and call widgets' [`WidgetConfig::configure`]
     /// method. Additionally, it updates the [`ManagerState`] to account for
     /// renamed and removed widgets.
+    ///
+    /// Where the widget with navigation or selection (and char) focus has been
+    /// replaced, focus is given to the widget now at the same [`WidgetPath`],
+    /// if any, which supports keyboard navigation. Other state referring to
+    /// removed widgets is dropped; in particular, press grabs, pan grabs and
+    /// drags started by a replaced widget are silently dropped.
     pub fn configure<W>(&mut self, shell: &mut dyn ShellWindow, widget: &mut W)
     where
         W: Widget<Msg = VoidMsg> + ?Sized,
@@ -88,6 +111,9 @@ impl ManagerState {
         self.accel_stack.clear();
         self.accel_layers.clear();
         self.nav_fallback = None;
+        self.tooltip = None;
+        self.tooltip_pending = None;
+        self.long_press.clear();
         // These we merge later:
         let mut old_time_updates = Default::default();
         swap(&mut self.time_updates, &mut old_time_updates);
@@ -121,8 +147,26 @@ impl ManagerState {
         // of this state is adjusted within widget configure methods.
         // TODO(safety): ensure these fields cannot be updated by configure?
 
+        // The focussed widget may have been replaced: focus the widget now
+        // at the same path, if it supports focus. Char focus moves with
+        // selection focus.
+        let restore = |path: &Option<WidgetPath>| {
+            (path.as_ref())
+                .and_then(|path| widget.find_path(path))
+                .filter(|w| w.key_nav() && !w.is_disabled())
+                .map(|w| w.id())
+        };
         self.sel_focus = self.sel_focus.and_then(|id| renames.get(&id).cloned());
+        if self.sel_focus.is_none() {
+            self.sel_focus = restore(&self.sel_focus_path);
+        }
+        self.char_focus &= self.sel_focus.is_some();
         self.nav_focus = self.nav_focus.and_then(|id| renames.get(&id).cloned());
+        if self.nav_focus.is_none() {
+            self.nav_focus = restore(&self.nav_focus_path);
+        }
+        self.sel_focus_path = self.sel_focus.and_then(|id| widget.path_of(id));
+        self.nav_focus_path = self.nav_focus.and_then(|id| widget.path_of(id));
         self.mouse_grab = self.mouse_grab.as_ref().and_then(|grab| {
             renames.get(&grab.start_id).map(|id| MouseGrab {
                 button: grab.button,
@@ -156,6 +200,20 @@ impl ManagerState {
             }
         });
 
+        self.drag = self.drag.take().and_then(|mut drag| {
+            let source = renames.get(&drag.payload.source())?;
+            drag.payload.set_source(*source);
+            drag.target = drag.target.and_then(|id| renames.get(&id).cloned());
+            Some(drag)
+        });
+
+        self.file_hover = self.file_hover.and_then(|id| renames.get(&id).cloned());
+        self.ime_cursor = self
+            .ime_cursor
+            .and_then(|(id, rect)| renames.get(&id).map(|id| (*id, rect)));
+
+        self.undo.rename(&renames);
+
         self.key_depress.retain(|_, depress_id| {
             if let Some(id) = renames.get(depress_id) {
                 *depress_id = *id;
@@ -254,9 +312,42 @@ impl ManagerState {
         }
     }
 
+    /// Set or clear the virtual clock
+    ///
+    /// When set, [`ManagerState::now`] returns this time instead of the system
+    /// time. This is used by [`Replay`]. The clock should not go backwards.
+    #[inline]
+    pub fn set_virtual_time(&mut self, time: Option<Instant>) {
+        self.virtual_time = time;
+    }
+
+    /// Advance the virtual clock by `duration`, running timer updates
+    ///
+    /// If no virtual clock is in use, this starts one at the current time.
+    /// See [`Replay`].
+    pub fn advance_virtual_time<W>(
+        &mut self,
+        shell: &mut dyn ShellWindow,
+        widget: &mut W,
+        duration: Duration,
+    ) where
+        W: Widget + ?Sized,
+    {
+        let end = self.now() + duration;
+        while let Some(time) = next_timer(self).filter(|time| *time <= end) {
+            self.virtual_time = Some(time);
+            self.with(shell, |mgr| mgr.update_timer(widget));
+        }
+        self.virtual_time = Some(end);
+    }
+
     /// Get the next resume time
     pub fn next_resume(&self) -> Option<Instant> {
-        self.time_updates.last().map(|time| time.0)
+        let next = self.time_updates.last().map(|time| time.0);
+        if !self.chord.is_empty() {
+            return Some(next.map_or(self.chord_timeout, |t| t.min(self.chord_timeout)));
+        }
+        next
     }
 
     /// Set an action
@@ -358,6 +449,10 @@ impl ManagerState {
             }
         }
 
+        for redo in std::mem::take(&mut mgr.state.undo.pending) {
+            mgr.apply_undo(widget, redo);
+        }
+
         // To avoid infinite loops, we consider mgr read-only from here on.
         // Since we don't wish to duplicate Handler::handle, we don't actually
         // make mgr const, but merely pretend it is in the public API.
@@ -371,8 +466,18 @@ impl ManagerState {
             mgr.send_event(widget, id, event);
         }
 
+        if let Some((id, rect)) = mgr.state.ime_cursor.take() {
+            let rect = window_rect(widget.as_widget(), id, rect);
+            mgr.shell.set_ime_cursor_area(rect);
+        }
+
         let action = mgr.action | self.action;
         self.action = TkAction::empty();
+        if !action.contains(TkAction::RECONFIGURE) {
+            // Identifiers are valid until reconfigure, so paths may be found
+            self.sel_focus_path = self.sel_focus.and_then(|id| widget.path_of(id));
+            self.nav_focus_path = self.nav_focus.and_then(|id| widget.path_of(id));
+        }
         action
     }
 }
@@ -382,7 +487,11 @@ impl ManagerState {
 impl<'a> Manager<'a> {
     /// Update widgets due to timer
     pub fn update_timer<W: Widget + ?Sized>(&mut self, widget: &mut W) {
-        let now = Instant::now();
+        let now = self.state.now();
+
+        if !self.state.chord.is_empty() && self.state.chord_timeout <= now {
+            self.clear_chord();
+        }
 
         // assumption: time_updates are sorted in reverse order
         while !self.state.time_updates.is_empty() {
@@ -394,6 +503,34 @@ impl<'a> Manager<'a> {
             self.send_event(widget, update.1, Event::TimerUpdate);
         }
 
+        if let Some((target, time)) = self.state.tooltip_pending {
+            if time <= now {
+                let coord = self.state.last_mouse_coord;
+                self.show_tooltip(widget, target, Some(coord));
+            } else {
+                // The window's timer may have been due for an earlier target
+                self.update_on_timer(time - now, widget.id());
+            }
+        }
+
+        let mut due = vec![];
+        let mut next = None;
+        for (touch_id, press) in self.state.long_press.iter() {
+            if press.time <= now {
+                due.push(*touch_id);
+            } else if next.map(|time| press.time < time).unwrap_or(true) {
+                next = Some(press.time);
+            }
+        }
+        if let Some(time) = next {
+            self.update_on_timer(time - now, widget.id());
+        }
+        for touch_id in due {
+            if let Some(press) = self.state.long_press.remove(&touch_id) {
+                self.long_press(widget, touch_id, press);
+            }
+        }
+
         self.state.time_updates.sort_by(|a, b| b.cmp(a)); // reverse sort
     }
 
@@ -423,106 +560,45 @@ impl<'a> Manager<'a> {
     where
         W: Widget<Msg = VoidMsg> + ?Sized,
     {
-        use winit::event::{ElementState, MouseScrollDelta, TouchPhase, WindowEvent::*};
+        use winit::event::{ElementState, MouseScrollDelta, WindowEvent::*};
 
         // Note: since <W as Handler>::Msg = VoidMsg, only two values of
         // Response are possible: None and Unhandled. We don't have any use for
         // Unhandled events here, so we can freely ignore all responses.
 
-        match event {
-            CloseRequested => self.send_action(TkAction::CLOSE),
-            /* Not yet supported: see #98
-            DroppedFile(path) => ,
-            HoveredFile(path) => ,
-            HoveredFileCancelled => ,
-            */
-            ReceivedCharacter(c) => {
-                if let Some(id) = self.state.sel_focus {
-                    if self.state.char_focus {
-                        // Filter out control codes (Unicode 5.11). These may be
-                        // generated from combinations such as Ctrl+C by some other
-                        // layer. We use our own shortcut system instead.
-                        if c >= '\u{20}' && (c < '\u{7f}' || c > '\u{9f}') {
-                            let event = Event::ReceivedCharacter(c);
-                            self.send_event(widget, id, event);
-                        }
-                    }
-                }
-            }
+        let event = match event {
+            CloseRequested => return self.send_action(TkAction::CLOSE),
+            DroppedFile(path) => InputEvent::FileDrop(path),
+            HoveredFile(path) => InputEvent::FileHover(path),
+            HoveredFileCancelled => InputEvent::FileHoverCancelled,
+            ReceivedCharacter(c) => InputEvent::ReceivedCharacter(c),
             KeyboardInput {
                 input,
                 is_synthetic,
                 ..
             } => {
                 if input.state == ElementState::Pressed && !is_synthetic {
-                    if let Some(vkey) = input.virtual_keycode {
-                        self.start_key_event(widget, vkey, input.scancode);
+                    match input.virtual_keycode {
+                        Some(vkey) => InputEvent::KeyPress {
+                            vkey,
+                            scancode: input.scancode,
+                        },
+                        None => return,
                     }
                 } else if input.state == ElementState::Released {
-                    self.end_key_event(input.scancode);
-                }
-            }
-            ModifiersChanged(state) => {
-                if state.alt() != self.state.modifiers.alt() {
-                    // This controls drawing of accelerator key indicators
-                    self.state.send_action(TkAction::REDRAW);
-                }
-                self.state.modifiers = state;
-            }
-            CursorMoved { position, .. } => {
-                self.state.last_click_button = FAKE_MOUSE_BUTTON;
-                let coord = position.into();
-
-                // Update hovered widget
-                let cur_id = widget.find_id(coord);
-                let delta = coord - self.state.last_mouse_coord;
-                self.set_hover(widget, cur_id);
-
-                if let Some(grab) = self.mouse_grab() {
-                    if grab.mode == GrabMode::Grab {
-                        let source = PressSource::Mouse(grab.button, grab.repetitions);
-                        let event = Event::PressMove {
-                            source,
-                            cur_id,
-                            coord,
-                            delta,
-                        };
-                        self.send_event(widget, grab.start_id, event);
-                    } else if let Some(pan) =
-                        self.state.pan_grab.get_mut(usize::conv(grab.pan_grab.0))
-                    {
-                        pan.coords[usize::conv(grab.pan_grab.1)].1 = coord;
+                    InputEvent::KeyRelease {
+                        scancode: input.scancode,
                     }
-                } else if let Some(id) = self.state.popups.last().map(|(_, p)| p.parent) {
-                    let source = PressSource::Mouse(FAKE_MOUSE_BUTTON, 0);
-                    let event = Event::PressMove {
-                        source,
-                        cur_id,
-                        coord,
-                        delta,
-                    };
-                    self.send_event(widget, id, event);
                 } else {
-                    // We don't forward move events without a grab
+                    return;
                 }
-
-                self.state.last_mouse_coord = coord;
             }
+            ModifiersChanged(state) => InputEvent::Modifiers(state),
+            CursorMoved { position, .. } => InputEvent::CursorMoved(position.into()),
             // CursorEntered { .. },
-            CursorLeft { .. } => {
-                self.state.last_click_button = FAKE_MOUSE_BUTTON;
-
-                if self.mouse_grab().is_none() {
-                    // If there's a mouse grab, we will continue to receive
-                    // coordinates; if not, set a fake coordinate off the window
-                    self.state.last_mouse_coord = Coord(-1, -1);
-                    self.set_hover(widget, None);
-                }
-            }
+            CursorLeft { .. } => InputEvent::CursorLeft,
             MouseWheel { delta, .. } => {
-                self.state.last_click_button = FAKE_MOUSE_BUTTON;
-
-                let event = Event::Scroll(match delta {
+                let delta = match delta {
                     MouseScrollDelta::LineDelta(x, y) => ScrollDelta::LineDelta(x, y),
                     MouseScrollDelta::PixelDelta(pos) => {
                         // The delta is given as a PhysicalPosition, so we need
@@ -530,150 +606,462 @@ impl<'a> Manager<'a> {
                         let coord = Coord::from(pos);
                         ScrollDelta::PixelDelta(Offset(coord.0, coord.1))
                     }
-                });
-                if let Some(id) = self.state.hover {
-                    self.send_event(widget, id, event);
-                }
+                };
+                InputEvent::Scroll(delta)
             }
             MouseInput { state, button, .. } => {
-                let coord = self.state.last_mouse_coord;
+                let pressed = state == ElementState::Pressed;
+                InputEvent::MouseInput { button, pressed }
+            }
+            // TouchpadPressure { pressure: f32, stage: i64, },
+            // AxisMotion { axis: AxisId, value: f64, },
+            Touch(touch) => InputEvent::Touch {
+                id: touch.id,
+                coord: touch.location.into(),
+                phase: touch.phase,
+            },
+            _ => return,
+        };
+        self.handle_input(widget, event);
+    }
 
-                if state == ElementState::Pressed {
-                    let now = Instant::now();
-                    if button != self.state.last_click_button || self.state.last_click_timeout < now
-                    {
-                        self.state.last_click_button = button;
-                        self.state.last_click_repetitions = 0;
-                    }
-                    self.state.last_click_repetitions += 1;
-                    self.state.last_click_timeout = now + DOUBLE_CLICK_TIMEOUT;
+    /// Handle an input event
+    ///
+    /// This calls the corresponding `handle_*` method. Input is recorded here
+    /// when recording is active (see [`Manager::start_recording`]).
+    pub fn handle_input<W>(&mut self, widget: &mut W, event: InputEvent)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        let now = self.state.now();
+        if let Some((start, recording)) = self.state.recording.as_mut() {
+            recording.events.push(RecordedEvent {
+                time: now - *start,
+                event: event.clone(),
+            });
+        }
+
+        // Each key release and the end of each press ends an undo group
+        let end_undo_group = match event {
+            InputEvent::KeyRelease { .. } => true,
+            InputEvent::MouseInput { pressed, .. } => !pressed,
+            InputEvent::Touch { phase, .. } => match phase {
+                TouchPhase::Ended | TouchPhase::Cancelled => true,
+                TouchPhase::Started | TouchPhase::Moved => false,
+            },
+            _ => false,
+        };
+
+        match event {
+            InputEvent::ReceivedCharacter(c) => self.handle_received_char(widget, c),
+            InputEvent::ImePreedit { text, cursor } => {
+                self.handle_ime_preedit(widget, text, cursor)
+            }
+            InputEvent::ImeCommit(text) => self.handle_ime_commit(widget, text),
+            InputEvent::KeyPress { vkey, scancode } => {
+                self.handle_key_press(widget, vkey, scancode)
+            }
+            InputEvent::KeyRelease { scancode } => self.handle_key_release(scancode),
+            InputEvent::Modifiers(state) => self.handle_modifiers(state),
+            InputEvent::CursorMoved(coord) => self.handle_cursor_moved(widget, coord),
+            InputEvent::CursorLeft => self.handle_cursor_left(widget),
+            InputEvent::FileHover(path) => self.handle_file_hover(widget, path),
+            InputEvent::FileHoverCancelled => self.handle_file_hover_cancelled(widget),
+            InputEvent::FileDrop(path) => self.handle_file_drop(widget, path),
+            InputEvent::Scroll(delta) => self.handle_scroll(widget, delta),
+            InputEvent::MouseInput { button, pressed } => {
+                self.handle_mouse_input(widget, button, pressed)
+            }
+            InputEvent::Touch { id, coord, phase } => self.handle_touch(widget, id, coord, phase),
+        }
+
+        if end_undo_group {
+            self.state.undo.end_group();
+        }
+    }
+
+    /// Handle a received character
+    ///
+    /// Control codes are filtered out; shortcuts are handled via
+    /// [`Manager::handle_key_press`] instead.
+    pub fn handle_received_char<W>(&mut self, widget: &mut W, c: char)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        if self.state.chord_char {
+            // This character was generated by a key consumed by a chord
+            self.state.chord_char = false;
+            return;
+        }
+
+        if let Some(id) = self.char_focus_target(widget) {
+            // Filter out control codes (Unicode 5.11). These may be
+            // generated from combinations such as Ctrl+C by some other
+            // layer. We use our own shortcut system instead.
+            if c >= '\u{20}' && (c < '\u{7f}' || c > '\u{9f}') {
+                let event = Event::ReceivedCharacter(c);
+                self.send_event(widget, id, event);
+            }
+        }
+    }
+
+    /// Handle input method pre-edit (composition) text
+    ///
+    /// See [`Event::ImePreedit`].
+    pub fn handle_ime_preedit<W>(
+        &mut self,
+        widget: &mut W,
+        text: String,
+        cursor: Option<(usize, usize)>,
+    ) where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        if let Some(id) = self.char_focus_target(widget) {
+            self.send_event(widget, id, Event::ImePreedit { text, cursor });
+        }
+    }
+
+    /// Handle text committed by an input method
+    ///
+    /// See [`Event::ImeCommit`].
+    pub fn handle_ime_commit<W>(&mut self, widget: &mut W, text: String)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        if let Some(id) = self.char_focus_target(widget) {
+            let event = Event::ImeCommit(text.clone());
+            trace!("Send to {}: {:?}", id, event);
+            if let Response::Unhandled = widget.send(self, id, event) {
+                for c in text.chars() {
+                    self.handle_received_char(widget, c);
                 }
+            }
+        }
+    }
 
-                if let Some(grab) = self.mouse_grab() {
-                    match grab.mode {
-                        GrabMode::Grab => {
-                            // Mouse grab active: send events there
-                            debug_assert_eq!(state, ElementState::Released);
-                            let source = PressSource::Mouse(button, grab.repetitions);
-                            let event = Event::PressEnd {
-                                source,
-                                end_id: self.state.hover,
-                                coord,
-                            };
-                            self.send_event(widget, grab.start_id, event);
+    /// Handle a key press
+    ///
+    /// The key is mapped to a [`Command`] through the configured shortcuts.
+    /// The `scancode` is used to match the corresponding release event.
+    pub fn handle_key_press<W>(&mut self, widget: &mut W, vkey: VirtualKeyCode, scancode: u32)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.start_key_event(widget, vkey, scancode);
+    }
+
+    /// Handle a key release
+    pub fn handle_key_release(&mut self, scancode: u32) {
+        self.end_key_event(scancode);
+    }
+
+    /// Update modifier state
+    pub fn handle_modifiers(&mut self, state: ModifiersState) {
+        if state.alt() != self.state.modifiers.alt() {
+            // This controls drawing of accelerator key indicators
+            self.state.send_action(TkAction::REDRAW);
+        }
+        self.state.modifiers = state;
+    }
+
+    /// Handle motion of the mouse cursor to `coord`
+    pub fn handle_cursor_moved<W>(&mut self, widget: &mut W, coord: Coord)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.state.last_click_button = FAKE_MOUSE_BUTTON;
+
+        // Update hovered widget
+        let cur_id = widget.find_id(coord);
+        let delta = coord - self.state.last_mouse_coord;
+        self.set_hover(widget, cur_id);
+
+        if let Some(grab) = self.mouse_grab() {
+            let source = PressSource::Mouse(grab.button, grab.repetitions);
+            if self.is_drag_source(source) {
+                self.drag_move(widget, cur_id, coord);
+            } else if grab.mode == GrabMode::Grab {
+                let event = Event::PressMove {
+                    source,
+                    cur_id,
+                    coord,
+                    delta,
+                };
+                self.send_event(widget, grab.start_id, event);
+            } else if let Some(pan) = self.state.pan_grab.get_mut(usize::conv(grab.pan_grab.0)) {
+                pan.coords[usize::conv(grab.pan_grab.1)].1 = coord;
+            }
+        } else if let Some(id) = self.state.popups.last().map(|(_, p)| p.parent) {
+            let source = PressSource::Mouse(FAKE_MOUSE_BUTTON, 0);
+            let event = Event::PressMove {
+                source,
+                cur_id,
+                coord,
+                delta,
+            };
+            self.send_event(widget, id, event);
+        } else {
+            // We don't forward move events without a grab
+        }
+
+        self.state.last_mouse_coord = coord;
+    }
+
+    /// Handle the mouse cursor leaving the window
+    pub fn handle_cursor_left<W>(&mut self, widget: &mut W)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.state.last_click_button = FAKE_MOUSE_BUTTON;
+
+        if self.mouse_grab().is_none() {
+            // If there's a mouse grab, we will continue to receive
+            // coordinates; if not, set a fake coordinate off the window
+            self.state.last_mouse_coord = Coord(-1, -1);
+            self.set_hover(widget, None);
+        }
+    }
+
+    /// Handle a file from another application being dragged over the window
+    ///
+    /// The file applies at the last coordinate passed to
+    /// [`Manager::handle_cursor_moved`].
+    pub fn handle_file_hover<W>(&mut self, widget: &mut W, path: PathBuf)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        let target = self.file_target(widget);
+        if self.state.file_hover != target {
+            self.handle_file_hover_cancelled(widget);
+        }
+        self.state.file_hover = target;
+        if let Some(id) = target {
+            self.send_event(widget, id, Event::FileHover(path));
+        }
+    }
+
+    /// Handle dragged files leaving the window without being dropped
+    pub fn handle_file_hover_cancelled<W>(&mut self, widget: &mut W)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        if let Some(id) = self.state.file_hover.take() {
+            self.send_event(widget, id, Event::FileHoverCancelled);
+        }
+    }
+
+    /// Handle a file from another application being dropped on the window
+    ///
+    /// The file applies at the last coordinate passed to
+    /// [`Manager::handle_cursor_moved`].
+    pub fn handle_file_drop<W>(&mut self, widget: &mut W, path: PathBuf)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        let target = self.file_target(widget);
+        if self.state.file_hover != target {
+            self.handle_file_hover_cancelled(widget);
+        }
+        self.state.file_hover = None;
+        if let Some(id) = target {
+            self.send_event(widget, id, Event::FileDrop(path));
+        }
+    }
+
+    /// Handle a mouse wheel or touchpad scroll
+    pub fn handle_scroll<W>(&mut self, widget: &mut W, delta: ScrollDelta)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        self.state.last_click_button = FAKE_MOUSE_BUTTON;
+
+        let event = Event::Scroll(delta);
+        if let Some(id) = self.state.hover {
+            self.send_event(widget, id, event);
+        }
+    }
+
+    /// Handle a mouse button press (`pressed == true`) or release
+    ///
+    /// The event applies at the last coordinate passed to
+    /// [`Manager::handle_cursor_moved`].
+    pub fn handle_mouse_input<W>(&mut self, widget: &mut W, button: MouseButton, pressed: bool)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        let coord = self.state.last_mouse_coord;
+
+        if pressed {
+            self.hide_tooltip();
+            let now = self.state.now();
+            if button != self.state.last_click_button || self.state.last_click_timeout < now {
+                self.state.last_click_button = button;
+                self.state.last_click_repetitions = 0;
+            }
+            self.state.last_click_repetitions += 1;
+            self.state.last_click_timeout = now + DOUBLE_CLICK_TIMEOUT;
+        }
+
+        if let Some(grab) = self.mouse_grab() {
+            match grab.mode {
+                GrabMode::Grab => {
+                    // Mouse grab active: send events there
+                    debug_assert!(!pressed);
+                    let source = PressSource::Mouse(button, grab.repetitions);
+                    if self.is_drag_source(source) {
+                        if button == grab.button {
+                            self.drag_end(widget, coord, false);
+                            self.end_mouse_grab(button);
                         }
-                        // Pan events do not receive Start/End notifications
-                        _ => (),
+                        return;
+                    }
+                    let event = Event::PressEnd {
+                        source,
+                        end_id: self.state.hover,
+                        coord,
                     };
+                    self.send_event(widget, grab.start_id, event);
+                }
+                // Pan events do not receive Start/End notifications
+                _ => (),
+            };
 
-                    if state == ElementState::Released {
-                        self.end_mouse_grab(button);
+            if !pressed {
+                self.end_mouse_grab(button);
+            }
+        } else if let Some(start_id) = self.state.hover {
+            // No mouse grab but have a hover target
+            if pressed {
+                let source = PressSource::Mouse(button, self.state.last_click_repetitions);
+                let event = Event::PressStart {
+                    source,
+                    start_id,
+                    coord,
+                };
+                if button == MouseButton::Right && self.state.popups.is_empty() {
+                    // Unhandled right presses open a context menu
+                    trace!("Send to {}: {:?}", start_id, event);
+                    if let Response::Unhandled = widget.send(self, start_id, event) {
+                        self.open_context_menu(widget, start_id, Some(coord));
                     }
-                } else if let Some(start_id) = self.state.hover {
-                    // No mouse grab but have a hover target
-                    if state == ElementState::Pressed {
-                        let source = PressSource::Mouse(button, self.state.last_click_repetitions);
-                        let event = Event::PressStart {
+                } else {
+                    self.send_popup_first(widget, start_id, event);
+                }
+            }
+        }
+    }
+
+    /// Handle a touch event
+    pub fn handle_touch<W>(&mut self, widget: &mut W, id: u64, coord: Coord, phase: TouchPhase)
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        let source = PressSource::Touch(id);
+        match phase {
+            TouchPhase::Started => {
+                self.hide_tooltip();
+                if let Some(start_id) = widget.find_id(coord) {
+                    let event = Event::PressStart {
+                        source,
+                        start_id,
+                        coord,
+                    };
+                    self.send_popup_first(widget, start_id, event);
+                    self.schedule_long_press(widget.id(), id, coord);
+                }
+            }
+            TouchPhase::Moved => {
+                let cur_id = widget.find_id(coord);
+
+                if let Some(press) = self.state.long_press.get(&id) {
+                    let d = coord - press.coord;
+                    if d.0.abs() > TAP_SLOP || d.1.abs() > TAP_SLOP {
+                        self.state.long_press.remove(&id);
+                    }
+                }
+
+                let mut r = None;
+                let mut pan_grab = None;
+                if let Some(grab) = self.get_touch(id) {
+                    if grab.mode == GrabMode::Grab {
+                        let id = grab.start_id;
+                        let event = Event::PressMove {
                             source,
-                            start_id,
+                            cur_id,
                             coord,
+                            delta: coord - grab.coord,
                         };
-                        self.send_popup_first(widget, start_id, event);
+                        // Only when 'depressed' status changes:
+                        let redraw = grab.cur_id != cur_id
+                            && (grab.cur_id == Some(grab.start_id)
+                                || cur_id == Some(grab.start_id));
+
+                        grab.cur_id = cur_id;
+                        grab.coord = coord;
+
+                        r = Some((id, event, redraw));
+                    } else {
+                        pan_grab = Some(grab.pan_grab);
                     }
                 }
-            }
-            // TouchpadPressure { pressure: f32, stage: i64, },
-            // AxisMotion { axis: AxisId, value: f64, },
-            Touch(touch) => {
-                let source = PressSource::Touch(touch.id);
-                let coord = touch.location.into();
-                match touch.phase {
-                    TouchPhase::Started => {
-                        if let Some(start_id) = widget.find_id(coord) {
-                            let event = Event::PressStart {
-                                source,
-                                start_id,
-                                coord,
-                            };
-                            self.send_popup_first(widget, start_id, event);
-                        }
-                    }
-                    TouchPhase::Moved => {
-                        let cur_id = widget.find_id(coord);
-
-                        let mut r = None;
-                        let mut pan_grab = None;
-                        if let Some(grab) = self.get_touch(touch.id) {
-                            if grab.mode == GrabMode::Grab {
-                                let id = grab.start_id;
-                                let event = Event::PressMove {
-                                    source,
-                                    cur_id,
-                                    coord,
-                                    delta: coord - grab.coord,
-                                };
-                                // Only when 'depressed' status changes:
-                                let redraw = grab.cur_id != cur_id
-                                    && (grab.cur_id == Some(grab.start_id)
-                                        || cur_id == Some(grab.start_id));
-
-                                grab.cur_id = cur_id;
-                                grab.coord = coord;
-
-                                r = Some((id, event, redraw));
-                            } else {
-                                pan_grab = Some(grab.pan_grab);
-                            }
-                        }
 
-                        if let Some((id, event, redraw)) = r {
-                            if redraw {
-                                self.send_action(TkAction::REDRAW);
-                            }
-                            self.send_event(widget, id, event);
-                        } else if let Some(pan_grab) = pan_grab {
-                            if usize::conv(pan_grab.1) < MAX_PAN_GRABS {
-                                if let Some(pan) =
-                                    self.state.pan_grab.get_mut(usize::conv(pan_grab.0))
-                                {
-                                    pan.coords[usize::conv(pan_grab.1)].1 = coord;
-                                }
-                            }
-                        }
+                if let Some((id, event, redraw)) = r {
+                    if redraw {
+                        self.send_action(TkAction::REDRAW);
                     }
-                    TouchPhase::Ended => {
-                        if let Some(grab) = self.remove_touch(touch.id) {
-                            if grab.mode == GrabMode::Grab {
-                                let event = Event::PressEnd {
-                                    source,
-                                    end_id: grab.cur_id,
-                                    coord,
-                                };
-                                if let Some(cur_id) = grab.cur_id {
-                                    self.redraw(cur_id);
-                                }
-                                self.send_event(widget, grab.start_id, event);
-                            } else {
-                                self.state.remove_pan_grab(grab.pan_grab);
-                            }
+                    if self.is_drag_source(source) {
+                        self.drag_move(widget, cur_id, coord);
+                    } else {
+                        self.send_event(widget, id, event);
+                    }
+                } else if let Some(pan_grab) = pan_grab {
+                    if usize::conv(pan_grab.1) < MAX_PAN_GRABS {
+                        if let Some(pan) = self.state.pan_grab.get_mut(usize::conv(pan_grab.0)) {
+                            pan.coords[usize::conv(pan_grab.1)].1 = coord;
                         }
                     }
-                    TouchPhase::Cancelled => {
-                        if let Some(grab) = self.remove_touch(touch.id) {
-                            let event = Event::PressEnd {
-                                source,
-                                end_id: None,
-                                coord,
-                            };
-                            if let Some(cur_id) = grab.cur_id {
-                                self.redraw(cur_id);
-                            }
+                }
+            }
+            TouchPhase::Ended => {
+                self.cancel_long_press(id);
+                if let Some(grab) = self.remove_touch(id) {
+                    if grab.mode == GrabMode::Grab {
+                        let event = Event::PressEnd {
+                            source,
+                            end_id: grab.cur_id,
+                            coord,
+                        };
+                        if let Some(cur_id) = grab.cur_id {
+                            self.redraw(cur_id);
+                        }
+                        if self.is_drag_source(source) {
+                            self.drag_end(widget, coord, false);
+                        } else {
                             self.send_event(widget, grab.start_id, event);
                         }
+                    } else {
+                        self.state.remove_pan_grab(grab.pan_grab);
+                    }
+                }
+            }
+            TouchPhase::Cancelled => {
+                self.cancel_long_press(id);
+                if let Some(grab) = self.remove_touch(id) {
+                    let event = Event::PressEnd {
+                        source,
+                        end_id: None,
+                        coord,
+                    };
+                    if let Some(cur_id) = grab.cur_id {
+                        self.redraw(cur_id);
+                    }
+                    if self.is_drag_source(source) {
+                        self.drag_end(widget, coord, true);
+                    } else {
+                        self.send_event(widget, grab.start_id, event);
                     }
                 }
             }
-            _ => (),
         }
     }
 }
@@ -41,6 +41,8 @@
 //! This is achieved by calling [`Manager::request_grab`] and allows receiving
 //! both relative and absolute press coordinates.
 //! A special "pan" grab allows receiving two-finger scroll/scale/rotate input.
+//! Building on this, a [`GestureRecogniser`] reports pinch-zoom, rotation,
+//! scroll, long-press and double-tap as distinct [`Gesture`]s.
 //!
 //! Each touch event is considered independent. The mouse cursor and multiple
 //! fingers may all interact with different parts of a UI simultaneously. The
@@ -68,13 +70,18 @@
 //! [`WidgetId`]: crate::WidgetId
 
 mod config;
+mod context_menu;
+mod drag;
 #[cfg(not(feature = "winit"))]
 mod enums;
 mod events;
+mod gesture;
 mod handler;
 mod manager;
+mod record;
 mod response;
 mod shortcuts;
+mod undo;
 mod update;
 
 use smallvec::SmallVec;
@@ -85,17 +92,23 @@ use std::fmt::Debug;
 use kas::{draw::InputState, Layout, WidgetCore};
 
 #[cfg(feature = "winit")]
-pub use winit::event::{ModifiersState, MouseButton, VirtualKeyCode};
+pub use winit::event::{ModifiersState, MouseButton, TouchPhase, VirtualKeyCode};
 #[cfg(feature = "winit")]
 pub use winit::window::CursorIcon;
 
-pub use config::{Config, ConfigError};
+pub use config::{Config, ConfigError, ConfigFormat};
+pub use context_menu::{ContextMenu, ContextMenuEntry};
+pub use drag::DragPayload;
 #[cfg(not(feature = "winit"))]
-pub use enums::{CursorIcon, ModifiersState, MouseButton, VirtualKeyCode};
+pub use enums::{CursorIcon, ModifiersState, MouseButton, TouchPhase, VirtualKeyCode};
 pub use events::*;
+pub use gesture::{Gesture, GestureRecogniser, GestureSet};
 pub use handler::{Handler, SendEvent};
 pub use manager::{ConfigureManager, GrabMode, Manager, ManagerState};
+pub use record::{InputEvent, RecordError, RecordedEvent, Recording, Replay};
 pub use response::Response;
+pub use shortcuts::{KeyStroke, ShortcutConflict, ShortcutMatch, Shortcuts};
+pub use undo::{UndoOp, UndoRequest, UndoValue};
 pub use update::UpdateHandle;
 
 /// A type supporting a small number of key bindings
@@ -0,0 +1,357 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Event handling: recording and replay
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::config::{Config, ConfigFormat};
+use super::{ManagerState, ModifiersState, MouseButton, ScrollDelta, TouchPhase};
+use super::{VirtualKeyCode, VoidMsg};
+use crate::geom::Coord;
+use crate::{ShellWindow, Widget};
+
+/// Error reading or writing a [`Recording`]
+#[derive(Error, Debug)]
+pub enum RecordError {
+    #[cfg(feature = "yaml")]
+    #[error("recording (de)serialisation to YAML failed")]
+    Yaml(#[from] serde_yaml::Error),
+    #[cfg(feature = "json")]
+    #[error("recording (de)serialisation to JSON failed")]
+    Json(#[from] serde_json::Error),
+    #[error("error reading / writing recording file")]
+    IoError(#[from] std::io::Error),
+    #[error("format not supported: {0}")]
+    UnsupportedFormat(ConfigFormat),
+}
+
+/// Minimum interval between timer updates during replay (a simulated frame)
+const FRAME_TIME: Duration = Duration::from_millis(16);
+
+/// Time of the next timer update, limited to one update per frame
+pub(crate) fn next_timer(state: &ManagerState) -> Option<Instant> {
+    let now = state.now();
+    state.next_resume().map(|time| time.max(now + FRAME_TIME))
+}
+
+/// An input event, as received by a window's event manager
+///
+/// This is the input model used after translation from the windowing system
+/// (e.g. winit). Each variant corresponds to a shell method of
+/// [`super::Manager`] such as `handle_cursor_moved`; see
+/// [`super::Manager::handle_input`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum InputEvent {
+    /// A character was received
+    ReceivedCharacter(char),
+    /// Input method pre-edit text
+    ImePreedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    /// Input method committed text
+    ImeCommit(String),
+    /// A key was pressed
+    KeyPress { vkey: VirtualKeyCode, scancode: u32 },
+    /// A key was released
+    KeyRelease { scancode: u32 },
+    /// The state of modifier keys changed
+    Modifiers(ModifiersState),
+    /// The mouse cursor moved
+    CursorMoved(Coord),
+    /// The mouse cursor left the window
+    CursorLeft,
+    /// A file is dragged over the window
+    FileHover(PathBuf),
+    /// A file is no longer dragged over the window
+    FileHoverCancelled,
+    /// A file was dropped on the window
+    FileDrop(PathBuf),
+    /// Mouse wheel or touchpad scrolling
+    Scroll(ScrollDelta),
+    /// A mouse button was pressed or released
+    MouseInput { button: MouseButton, pressed: bool },
+    /// A touch event
+    Touch {
+        id: u64,
+        coord: Coord,
+        phase: TouchPhase,
+    },
+}
+
+/// An [`InputEvent`] with a timestamp
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RecordedEvent {
+    /// Time since the start of recording
+    pub time: Duration,
+    pub event: InputEvent,
+}
+
+/// A recorded stream of input events
+///
+/// A recording of a window's input may be made via
+/// [`super::Manager::start_recording`] and replayed against the same UI via
+/// [`Replay`] (or `kas::testing::Harness::replay`). Given the `json` or `yaml`
+/// feature, recordings may be written to and read from a file. TOML is not
+/// supported since it cannot represent most [`InputEvent`] variants.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Recording {
+    pub events: Vec<RecordedEvent>,
+}
+
+impl Recording {
+    /// Construct an empty recording
+    #[inline]
+    pub fn new() -> Self {
+        Recording::default()
+    }
+
+    /// Duration of the recording (time of the last event)
+    pub fn duration(&self) -> Duration {
+        self.events.last().map(|ev| ev.time).unwrap_or_default()
+    }
+
+    /// Read from a path
+    ///
+    /// Supported formats are JSON and YAML.
+    pub fn from_path(path: &Path, mut format: ConfigFormat) -> Result<Self, RecordError> {
+        if format == ConfigFormat::None {
+            format = Config::guess_format(path);
+        }
+
+        match format {
+            #[cfg(feature = "json")]
+            ConfigFormat::Json => {
+                let r = std::io::BufReader::new(std::fs::File::open(path)?);
+                Ok(serde_json::from_reader(r)?)
+            }
+            #[cfg(feature = "yaml")]
+            ConfigFormat::Yaml => {
+                let r = std::io::BufReader::new(std::fs::File::open(path)?);
+                Ok(serde_yaml::from_reader(r)?)
+            }
+            _ => Err(RecordError::UnsupportedFormat(format)),
+        }
+    }
+
+    /// Write to a path
+    ///
+    /// Supported formats are JSON and YAML.
+    pub fn write_path(&self, path: &Path, mut format: ConfigFormat) -> Result<(), RecordError> {
+        if format == ConfigFormat::None {
+            format = Config::guess_format(path);
+        }
+
+        match format {
+            #[cfg(feature = "json")]
+            ConfigFormat::Json => {
+                let w = std::io::BufWriter::new(std::fs::File::create(path)?);
+                serde_json::to_writer_pretty(w, self)?;
+                Ok(())
+            }
+            #[cfg(feature = "yaml")]
+            ConfigFormat::Yaml => {
+                let w = std::io::BufWriter::new(std::fs::File::create(path)?);
+                serde_yaml::to_writer(w, self)?;
+                Ok(())
+            }
+            _ => Err(RecordError::UnsupportedFormat(format)),
+        }
+    }
+}
+
+/// Replay driver for a [`Recording`]
+///
+/// Replay uses a virtual clock (see [`ManagerState::set_virtual_time`]): each
+/// step advances the clock either to the next scheduled timer update or to the
+/// time of the next recorded event, thus replay runs as fast as the caller
+/// steps it, with deterministic timing. Timer updates are simulated at most
+/// once per 16ms frame. For example:
+/// ```ignore
+/// let mut replay = Replay::new(recording);
+/// while replay.step(&mut state, &mut shell, &mut window) {
+///     // process actions as after any input
+///     let action = state.update(&mut shell, &mut window);
+/// }
+/// ```
+///
+/// After replay, the virtual clock remains in use; timers pending at the end
+/// of the recording may be run via [`ManagerState::advance_virtual_time`].
+#[derive(Clone, Debug)]
+pub struct Replay {
+    events: Vec<RecordedEvent>,
+    next: usize,
+    start: Option<Instant>,
+}
+
+impl Replay {
+    /// Construct
+    pub fn new(recording: Recording) -> Self {
+        Replay {
+            events: recording.events,
+            next: 0,
+            start: None,
+        }
+    }
+
+    /// True when all events have been delivered
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.events.len()
+    }
+
+    /// Deliver the next event or timer update
+    ///
+    /// Returns false (doing nothing) when all events have been delivered.
+    pub fn step<W>(
+        &mut self,
+        state: &mut ManagerState,
+        shell: &mut dyn ShellWindow,
+        widget: &mut W,
+    ) -> bool
+    where
+        W: Widget<Msg = VoidMsg> + ?Sized,
+    {
+        let event = match self.events.get(self.next) {
+            Some(event) => event,
+            None => return false,
+        };
+        let start = *self.start.get_or_insert_with(|| state.now());
+        let time = start + event.time;
+
+        match next_timer(state) {
+            Some(resume) if resume <= time => {
+                state.set_virtual_time(Some(resume));
+                state.with(shell, |mgr| mgr.update_timer(widget));
+            }
+            _ => {
+                let event = event.event.clone();
+                self.next += 1;
+                state.set_virtual_time(Some(time));
+                state.with(shell, |mgr| mgr.handle_input(widget, event));
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::class::HasStr;
+    use crate::geom::Offset;
+    use crate::testing::Harness;
+    use crate::widget::{EditBox, WithTooltip};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn harness() -> Harness<WithTooltip<EditBox>> {
+        let mut config = Config::default();
+        config.tooltip_delay_ms = 100;
+        let widget = WithTooltip::new(EditBox::new(""), "tip");
+        let mut harness = Harness::new_config(widget, Rc::new(RefCell::new(config)));
+        // Use the virtual clock
+        harness.advance_time(Duration::ZERO);
+        harness
+    }
+
+    #[test]
+    fn replay() {
+        let mut harness = harness();
+        let rect = harness.widget().rect();
+        let coord = rect.pos + rect.size / 2;
+
+        harness.start_recording();
+        harness.click(coord);
+        harness.type_str("abc");
+        harness.mouse_leave();
+        harness.mouse_move(coord);
+        // The tooltip is shown by a timer before the next event
+        harness.advance_time(Duration::from_millis(150));
+        harness.mouse_move(coord + Offset(1, 0));
+        let recording = harness.stop_recording().unwrap();
+        assert_eq!(harness.widget().get_str(), "abc");
+        assert!(harness.state().tooltip().is_some());
+
+        let mut replayed = self::harness();
+        replayed.start_recording();
+        replayed.replay(recording.clone());
+        assert_eq!(replayed.widget().get_str(), "abc");
+        assert_eq!(replayed.state().tooltip(), harness.state().tooltip());
+        // Replay delivers events at their recorded times
+        assert_eq!(replayed.stop_recording(), Some(recording));
+    }
+
+    #[cfg(any(feature = "json", feature = "yaml"))]
+    fn file_round_trip(name: &str) {
+        let events = vec![
+            InputEvent::Modifiers(ModifiersState::SHIFT),
+            InputEvent::CursorMoved(Coord(10, 20)),
+            InputEvent::MouseInput {
+                button: MouseButton::Left,
+                pressed: true,
+            },
+            InputEvent::KeyPress {
+                vkey: VirtualKeyCode::A,
+                scancode: 30,
+            },
+            InputEvent::ReceivedCharacter('a'),
+            InputEvent::Scroll(ScrollDelta::PixelDelta(Offset(0, -3))),
+            InputEvent::Touch {
+                id: 1,
+                coord: Coord(5, 5),
+                phase: TouchPhase::Started,
+            },
+            InputEvent::FileDrop(PathBuf::from("a.txt")),
+            InputEvent::CursorLeft,
+        ];
+        let events = events.into_iter().enumerate();
+        let recording = Recording {
+            events: events
+                .map(|(i, event)| RecordedEvent {
+                    time: Duration::from_millis(10 * i as u64),
+                    event,
+                })
+                .collect(),
+        };
+
+        let path = std::env::temp_dir().join(name);
+        recording.write_path(&path, ConfigFormat::None).unwrap();
+        let read = Recording::from_path(&path, ConfigFormat::None);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(read.unwrap(), recording);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trip() {
+        file_round_trip("kas-event-record-test.json");
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn yaml_round_trip() {
+        file_round_trip("kas-event-record-test.yaml");
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_unsupported() {
+        let path = std::env::temp_dir().join("kas-event-record-test.toml");
+        match Recording::new().write_path(&path, ConfigFormat::None) {
+            Err(RecordError::UnsupportedFormat(ConfigFormat::Toml)) => (),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+}
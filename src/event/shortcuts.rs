@@ -8,17 +8,69 @@
 use super::{Command, ModifiersState, VirtualKeyCode};
 use linear_map::LinearMap;
 #[cfg(feature = "serde")]
-use serde::de::{self, Deserialize, Deserializer, MapAccess, Unexpected, Visitor};
+use serde::de::{
+    self, Deserialize, Deserializer, IntoDeserializer, MapAccess, Unexpected, Visitor,
+};
 #[cfg(feature = "serde")]
 use serde::ser::{Serialize, SerializeMap, Serializer};
 use std::collections::HashMap;
 #[cfg(feature = "serde")]
 use std::fmt;
 
+/// A single key stroke: modifiers and key
+pub type KeyStroke = (ModifiersState, VirtualKeyCode);
+
+/// Result of [`Shortcuts::get_chord`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShortcutMatch {
+    /// The key stroke (and prefix) does not match any binding
+    None,
+    /// The key stroke is part of a chord; further strokes are expected
+    Prefix,
+    /// The key stroke (and prefix) matches a command
+    Command(Command),
+}
+
+/// A shortcut binding which was replaced or shadowed by another
+///
+/// This is reported by [`Shortcuts::bind`] and [`Shortcuts::merge`]. The
+/// latter also reports single-stroke bindings shadowed by the first stroke of
+/// a chord, in which case `new` is the command bound to the chord.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShortcutConflict {
+    /// Modifiers of the binding
+    pub modifiers: ModifiersState,
+    /// Key of the binding
+    pub vkey: VirtualKeyCode,
+    /// The previously-bound command
+    pub old: Command,
+    /// The newly-bound command
+    pub new: Command,
+}
+
 /// Shortcut manager
-#[derive(Debug)]
+///
+/// Bindings map a combination of [`ModifiersState`] and [`VirtualKeyCode`] to
+/// a [`Command`]. Besides the bindings stored here, some keys (e.g. arrow keys,
+/// Home/End) are matched to a command with and without Shift when no binding
+/// exists; see [`Shortcuts::get`] and [`Command::new`].
+///
+/// Bindings may be adjusted at run-time via [`Shortcuts::bind`] and
+/// [`Shortcuts::unbind`]. Typically, user configuration is merged over the
+/// platform defaults via [`Shortcuts::merge`] (see also
+/// [`super::Config::merge_path`]).
+///
+/// Chords (multi-stroke shortcuts like Ctrl+K, Ctrl+C) are supported via
+/// [`Shortcuts::bind_chord`]. The first stroke(s) of a chord take precedence
+/// over single-stroke bindings.
+///
+/// User configuration may remove a default binding with an `unbind` entry,
+/// e.g. `unbind = ["ctrl+Q", "F1"]`; see [`Shortcuts::unbind_on_merge`].
+#[derive(Clone, Debug, Default)]
 pub struct Shortcuts {
     map: LinearMap<ModifiersState, HashMap<VirtualKeyCode, Command>>,
+    chords: HashMap<Vec<KeyStroke>, Command>,
+    unbound: Vec<KeyStroke>,
 }
 
 impl Shortcuts {
@@ -26,6 +78,8 @@ impl Shortcuts {
     pub fn new() -> Self {
         Shortcuts {
             map: Default::default(),
+            chords: Default::default(),
+            unbound: vec![],
         }
     }
 
@@ -60,6 +114,7 @@ impl Shortcuts {
             let modifiers = ModifiersState::SHIFT;
             let map = self.map.entry(modifiers).or_insert_with(Default::default);
             map.insert(VK::F3, Command::FindPrev);
+            map.insert(VK::F10, Command::Menu);
         }
 
         // Alt (Option on MacOS)
@@ -113,6 +168,7 @@ impl Shortcuts {
             (VK::X, Command::Cut),
             (VK::Z, Command::Undo),
             (VK::Tab, Command::TabNext),
+            (VK::F1, Command::Tooltip),
         ];
         map.extend(shortcuts.iter().cloned());
         #[cfg(target_os = "macos")]
@@ -184,6 +240,166 @@ impl Shortcuts {
         }
     }
 
+    /// Bind a shortcut, replacing any existing binding
+    ///
+    /// If this replaces a different command (including a built-in command, as
+    /// matched by [`Shortcuts::get`]), the conflict is returned.
+    pub fn bind(
+        &mut self,
+        modifiers: ModifiersState,
+        vkey: VirtualKeyCode,
+        command: Command,
+    ) -> Option<ShortcutConflict> {
+        let old = self.get(modifiers, vkey);
+        self.map
+            .entry(modifiers)
+            .or_insert_with(Default::default)
+            .insert(vkey, command);
+        old.filter(|old| *old != command)
+            .map(|old| ShortcutConflict {
+                modifiers,
+                vkey,
+                old,
+                new: command,
+            })
+    }
+
+    /// Remove a binding
+    ///
+    /// Returns the command previously bound, if any. Note that built-in
+    /// bindings (see [`Command::new`]) cannot be removed.
+    pub fn unbind(&mut self, modifiers: ModifiersState, vkey: VirtualKeyCode) -> Option<Command> {
+        let map = self.map.get_mut(&modifiers)?;
+        let result = map.remove(&vkey);
+        if map.is_empty() {
+            self.map.remove(&modifiers);
+        }
+        result
+    }
+
+    /// Remove a binding when merged
+    ///
+    /// This does not affect the bindings of `self`; instead, when `self` is
+    /// merged into another [`Shortcuts`] via [`Shortcuts::merge`], the binding
+    /// of `modifiers` and `vkey` is removed from the latter (before any of
+    /// the bindings of `self` are added). This allows user configuration to
+    /// remove platform-default bindings.
+    pub fn unbind_on_merge(&mut self, modifiers: ModifiersState, vkey: VirtualKeyCode) {
+        if !self.unbound.contains(&(modifiers, vkey)) {
+            self.unbound.push((modifiers, vkey));
+        }
+    }
+
+    /// Check for a conflicting binding
+    ///
+    /// Returns the command which is currently matched by `modifiers` and
+    /// `vkey`, if this differs from `command`.
+    pub fn conflict(
+        &self,
+        modifiers: ModifiersState,
+        vkey: VirtualKeyCode,
+        command: Command,
+    ) -> Option<Command> {
+        self.get(modifiers, vkey).filter(|old| *old != command)
+    }
+
+    /// Bind a chord (multi-stroke shortcut), replacing any existing binding
+    ///
+    /// The chord is matched when all `strokes` are pressed in sequence.
+    /// Returns the command previously bound to this chord, if any.
+    ///
+    /// The first stroke of a chord shadows any single-stroke binding of the
+    /// same keys (see [`Shortcuts::get`]).
+    ///
+    /// Panics if `strokes` has fewer than two elements.
+    pub fn bind_chord(&mut self, strokes: &[KeyStroke], command: Command) -> Option<Command> {
+        assert!(strokes.len() >= 2, "Shortcuts::bind_chord: too few strokes");
+        self.chords.insert(strokes.to_vec(), command)
+    }
+
+    /// Remove a chord binding
+    ///
+    /// Returns the command previously bound, if any.
+    pub fn unbind_chord(&mut self, strokes: &[KeyStroke]) -> Option<Command> {
+        self.chords.remove(strokes)
+    }
+
+    /// Iterate over all chord bindings
+    pub fn chords(&self) -> impl Iterator<Item = (&[KeyStroke], Command)> + '_ {
+        self.chords.iter().map(|(k, v)| (k.as_slice(), *v))
+    }
+
+    /// Merge bindings from `other` over `self`
+    ///
+    /// First, all bindings marked via [`Shortcuts::unbind_on_merge`] on
+    /// `other` are removed from `self`. Next, all bindings of `other`
+    /// (including chords) are added, replacing existing bindings.
+    ///
+    /// All replaced single-stroke bindings with a different command are
+    /// returned. Additionally, where a chord of `other` begins with a stroke
+    /// bound to a single-stroke command (or a single-stroke binding of `other`
+    /// is the first stroke of a chord), the shadowed binding is returned.
+    pub fn merge(&mut self, other: &Shortcuts) -> Vec<ShortcutConflict> {
+        for (modifiers, vkey) in other.unbound.iter() {
+            self.unbind(*modifiers, *vkey);
+        }
+
+        let mut conflicts = vec![];
+        for (modifiers, map) in other.map.iter() {
+            for (vkey, command) in map.iter() {
+                if let Some(conflict) = self.bind(*modifiers, *vkey, *command) {
+                    conflicts.push(conflict);
+                }
+            }
+        }
+        for (strokes, command) in other.chords.iter() {
+            self.chords.insert(strokes.clone(), *command);
+        }
+
+        for (strokes, command) in self.chords.iter() {
+            let (modifiers, vkey) = strokes[0];
+            let from_other = other.chords.contains_key(strokes)
+                || other
+                    .map
+                    .get(&modifiers)
+                    .map_or(false, |m| m.contains_key(&vkey));
+            if !from_other {
+                continue;
+            }
+            if let Some(old) = self.get(modifiers, vkey) {
+                conflicts.push(ShortcutConflict {
+                    modifiers,
+                    vkey,
+                    old,
+                    new: *command,
+                });
+            }
+        }
+        conflicts
+    }
+
+    /// Iterate over all bindings
+    ///
+    /// Built-in bindings (see [`Command::new`]) are not included.
+    pub fn iter(&self) -> impl Iterator<Item = (ModifiersState, VirtualKeyCode, Command)> + '_ {
+        self.map.iter().flat_map(|(modifiers, map)| {
+            map.iter()
+                .map(move |(vkey, command)| (*modifiers, *vkey, *command))
+        })
+    }
+
+    /// Find all bindings of a command
+    ///
+    /// Built-in bindings (see [`Command::new`]) are not included.
+    pub fn bindings_of(
+        &self,
+        command: Command,
+    ) -> impl Iterator<Item = (ModifiersState, VirtualKeyCode)> + '_ {
+        self.iter()
+            .filter(move |(_, _, cmd)| *cmd == command)
+            .map(|(modifiers, vkey, _)| (modifiers, vkey))
+    }
+
     /// Match shortcuts
     ///
     /// Note: text-editor navigation keys (e.g. arrows, home/end) result in the
@@ -200,6 +416,108 @@ impl Shortcuts {
         }
         None
     }
+
+    /// Match shortcuts, including chords
+    ///
+    /// The `prefix` is the sequence of strokes already pressed (as part of a
+    /// chord); this is empty when no chord is in progress. If the `prefix`
+    /// followed by `modifiers` and `vkey` is the start of some chord, then
+    /// [`ShortcutMatch::Prefix`] is returned. Otherwise, a complete chord is
+    /// matched, or (with an empty `prefix`) the result of [`Shortcuts::get`].
+    pub fn get_chord(
+        &self,
+        prefix: &[KeyStroke],
+        modifiers: ModifiersState,
+        vkey: VirtualKeyCode,
+    ) -> ShortcutMatch {
+        let len = prefix.len() + 1;
+        let is_seq = |seq: &[KeyStroke]| {
+            seq.len() >= len && seq[..len - 1] == *prefix && seq[len - 1] == (modifiers, vkey)
+        };
+
+        let mut result = None;
+        for (seq, command) in self.chords.iter() {
+            if is_seq(seq) {
+                if seq.len() > len {
+                    return ShortcutMatch::Prefix;
+                }
+                result = Some(*command);
+            }
+        }
+
+        if result.is_none() && prefix.is_empty() {
+            result = self.get(modifiers, vkey);
+        }
+        match result {
+            Some(command) => ShortcutMatch::Command(command),
+            None => ShortcutMatch::None,
+        }
+    }
+
+    /// Describe the shortcut bound to a command, if any
+    ///
+    /// This uses [`Shortcuts::describe`] on the shortest single-stroke binding
+    /// or, failing that, the shortest chord. Ties are broken by comparing the
+    /// descriptions, thus the result does not depend on the order bindings
+    /// are stored in. It is intended for display of accelerators (e.g. in
+    /// menus).
+    pub fn describe_command(&self, command: Command) -> Option<String> {
+        fn shortest(iter: impl Iterator<Item = String>) -> Option<String> {
+            iter.min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+        }
+
+        let strokes = self
+            .bindings_of(command)
+            .map(|stroke| Self::describe(&[stroke]));
+        shortest(strokes).or_else(|| {
+            let chords = self
+                .chords()
+                .filter(|(_, cmd)| *cmd == command)
+                .map(|(strokes, _)| Self::describe(strokes));
+            shortest(chords)
+        })
+    }
+
+    /// Describe a sequence of key strokes, e.g. `Ctrl+K Ctrl+C`
+    ///
+    /// This is intended for display (e.g. in a status bar or menu), not for
+    /// parsing.
+    pub fn describe(strokes: &[KeyStroke]) -> String {
+        #[cfg(target_os = "macos")]
+        const NAMES: [(ModifiersState, &str); 4] = [
+            (ModifiersState::CTRL, "Ctrl+"),
+            (ModifiersState::ALT, "Option+"),
+            (ModifiersState::SHIFT, "Shift+"),
+            (ModifiersState::LOGO, "Cmd+"),
+        ];
+        #[cfg(not(target_os = "macos"))]
+        const NAMES: [(ModifiersState, &str); 4] = [
+            (ModifiersState::CTRL, "Ctrl+"),
+            (ModifiersState::ALT, "Alt+"),
+            (ModifiersState::SHIFT, "Shift+"),
+            (ModifiersState::LOGO, "Super+"),
+        ];
+
+        let mut s = String::new();
+        for (i, (modifiers, vkey)) in strokes.iter().enumerate() {
+            if i > 0 {
+                s.push(' ');
+            }
+            for (state, name) in NAMES.iter() {
+                if modifiers.contains(*state) {
+                    s.push_str(name);
+                }
+            }
+            let key = format!("{:?}", vkey);
+            // Digits are named Key1 etc.
+            if key.len() == 4 && key.starts_with("Key") {
+                s.push_str(&key[3..]);
+            } else {
+                s.push_str(&key);
+            }
+        }
+        s
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -250,9 +568,76 @@ impl Serialize for Shortcuts {
     where
         S: Serializer,
     {
-        let mut map = s.serialize_map(Some(self.map.len()))?;
+        let len = self.map.len()
+            + if self.chords.is_empty() { 0 } else { 1 }
+            + if self.unbound.is_empty() { 0 } else { 1 };
+        let mut map = s.serialize_map(Some(len))?;
+        // NOTE: TOML requires that plain values (here an array) precede tables
+        if !self.unbound.is_empty() {
+            let strokes: Vec<String> = self.unbound.iter().map(stroke_to_string).collect();
+            map.serialize_entry("unbind", &strokes)?;
+        }
         for (k, v) in &self.map {
-            map.serialize_entry(state_to_string(*k), v)?;
+            map.serialize_entry(state_to_string(*k), &KeyMap(v))?;
+        }
+        if !self.chords.is_empty() {
+            map.serialize_entry("chords", &ChordMap(&self.chords))?;
+        }
+        map.end()
+    }
+}
+
+// Key strokes are serialised like `ctrl+K` or `F1`
+#[cfg(feature = "serde")]
+fn stroke_to_string((state, vkey): &KeyStroke) -> String {
+    match state.is_empty() {
+        true => format!("{:?}", vkey),
+        false => format!("{}+{:?}", state_to_string(*state), vkey),
+    }
+}
+
+#[cfg(feature = "serde")]
+fn stroke_from_str<E: de::Error>(stroke: &str) -> Result<KeyStroke, E> {
+    let (state, vkey) = match stroke.rfind('+') {
+        Some(i) => (&stroke[..i], &stroke[i + 1..]),
+        None => ("none", stroke),
+    };
+    let state = ModifierStateVisitor(Default::default()).visit_str::<E>(state)?;
+    let de = IntoDeserializer::<E>::into_deserializer(vkey);
+    Ok((state.0, VirtualKeyCode::deserialize(de)?))
+}
+
+// Chords are serialised like `"ctrl+K ctrl+C": <command>`
+#[cfg(feature = "serde")]
+struct ChordMap<'a>(&'a HashMap<Vec<KeyStroke>, Command>);
+#[cfg(feature = "serde")]
+impl<'a> Serialize for ChordMap<'a> {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = s.serialize_map(Some(self.0.len()))?;
+        for (k, v) in self.0 {
+            let strokes: Vec<String> = k.iter().map(stroke_to_string).collect();
+            map.serialize_entry(&strokes.join(" "), v)?;
+        }
+        map.end()
+    }
+}
+
+// Some formats (TOML) only support string map keys, so we serialise keys as
+// strings explicitly. Variant names are used, as with derived serialisation.
+#[cfg(feature = "serde")]
+struct KeyMap<'a>(&'a HashMap<VirtualKeyCode, Command>);
+#[cfg(feature = "serde")]
+impl<'a> Serialize for KeyMap<'a> {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = s.serialize_map(Some(self.0.len()))?;
+        for (k, v) in self.0 {
+            map.serialize_entry(&format!("{:?}", k), v)?;
         }
         map.end()
     }
@@ -340,11 +725,104 @@ impl<'de> Visitor<'de> for ShortcutsVisitor {
         A: MapAccess<'de>,
     {
         let mut map = LinearMap::<ModifiersState, HashMap<VirtualKeyCode, Command>>::new();
-        while let Some(key) = reader.next_key::<ModifierStateVisitor>()? {
+        let mut chords = HashMap::new();
+        let mut unbound = vec![];
+        while let Some(key) = reader.next_key::<String>()? {
+            if key == "chords" {
+                let value: ChordMapVisitor = reader.next_value()?;
+                chords = value.0;
+            } else if key == "unbind" {
+                let value: Vec<String> = reader.next_value()?;
+                for stroke in value.iter() {
+                    unbound.push(stroke_from_str(stroke)?);
+                }
+            } else {
+                let state = ModifierStateVisitor(Default::default()).visit_str::<A::Error>(&key)?;
+                let value: KeyMapVisitor = reader.next_value()?;
+                map.insert(state.0, value.0);
+            }
+        }
+        Ok(Shortcuts {
+            map,
+            chords,
+            unbound,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+struct ChordMapVisitor(HashMap<Vec<KeyStroke>, Command>);
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for ChordMapVisitor {
+    type Value = ChordMapVisitor;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("{ <modifiers>+<key> <modifiers>+<key> : <command> }")
+    }
+
+    fn visit_map<A>(mut self, mut reader: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        while let Some(key) = reader.next_key::<String>()? {
+            let mut strokes = vec![];
+            for stroke in key.split_whitespace() {
+                strokes.push(stroke_from_str(stroke)?);
+            }
+            if strokes.len() < 2 {
+                return Err(de::Error::invalid_value(
+                    Unexpected::Str(&key),
+                    &"a sequence of at least two key strokes",
+                ));
+            }
+            let value = reader.next_value()?;
+            self.0.insert(strokes, value);
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for ChordMapVisitor {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        d.deserialize_map(ChordMapVisitor(Default::default()))
+    }
+}
+
+#[cfg(feature = "serde")]
+struct KeyMapVisitor(HashMap<VirtualKeyCode, Command>);
+#[cfg(feature = "serde")]
+impl<'de> Visitor<'de> for KeyMapVisitor {
+    type Value = KeyMapVisitor;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("{ <key> : <command> }")
+    }
+
+    fn visit_map<A>(mut self, mut reader: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        while let Some(key) = reader.next_key::<String>()? {
+            let de = IntoDeserializer::<A::Error>::into_deserializer(key.as_str());
+            let vkey = VirtualKeyCode::deserialize(de)?;
             let value = reader.next_value()?;
-            map.insert(key.0, value);
+            self.0.insert(vkey, value);
         }
-        Ok(Shortcuts { map })
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for KeyMapVisitor {
+    fn deserialize<D>(d: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        d.deserialize_map(KeyMapVisitor(Default::default()))
     }
 }
 
@@ -357,3 +835,178 @@ impl<'de> Deserialize<'de> for Shortcuts {
         d.deserialize_map(ShortcutsVisitor)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const CTRL: ModifiersState = ModifiersState::CTRL;
+
+    #[test]
+    fn bind() {
+        let mut shortcuts = Shortcuts::new();
+        assert_eq!(shortcuts.bind(CTRL, VirtualKeyCode::S, Command::Save), None);
+        assert_eq!(shortcuts.get(CTRL, VirtualKeyCode::S), Some(Command::Save));
+        assert_eq!(
+            shortcuts.unbind(CTRL, VirtualKeyCode::S),
+            Some(Command::Save)
+        );
+        assert_eq!(shortcuts.get(CTRL, VirtualKeyCode::S), None);
+        assert_eq!(shortcuts.iter().count(), 0);
+    }
+
+    #[test]
+    fn conflict() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.bind(CTRL, VirtualKeyCode::S, Command::Save);
+        let conflict = ShortcutConflict {
+            modifiers: CTRL,
+            vkey: VirtualKeyCode::S,
+            old: Command::Save,
+            new: Command::Print,
+        };
+        let (modifiers, vkey) = (CTRL, VirtualKeyCode::S);
+        assert_eq!(shortcuts.conflict(modifiers, vkey, Command::Save), None);
+        assert_eq!(
+            shortcuts.conflict(modifiers, vkey, Command::Print),
+            Some(Command::Save)
+        );
+        assert_eq!(shortcuts.bind(modifiers, vkey, Command::Save), None);
+        assert_eq!(
+            shortcuts.bind(modifiers, vkey, Command::Print),
+            Some(conflict)
+        );
+
+        // Built-in bindings are reported too:
+        let home = ModifiersState::empty();
+        assert!(shortcuts
+            .bind(home, VirtualKeyCode::Home, Command::Help)
+            .is_some());
+    }
+
+    #[test]
+    fn merge() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.bind(CTRL, VirtualKeyCode::Q, Command::Exit);
+        shortcuts.bind(CTRL, VirtualKeyCode::S, Command::Save);
+        shortcuts.bind(CTRL, VirtualKeyCode::W, Command::Close);
+
+        let mut user = Shortcuts::new();
+        user.unbind_on_merge(CTRL, VirtualKeyCode::Q);
+        user.bind(CTRL, VirtualKeyCode::S, Command::Print);
+        user.bind(CTRL, VirtualKeyCode::W, Command::Close);
+        user.bind(CTRL, VirtualKeyCode::N, Command::New);
+        // unbind_on_merge does not affect the bindings of user:
+        assert_eq!(user.iter().count(), 3);
+
+        let conflicts = shortcuts.merge(&user);
+        assert_eq!(
+            conflicts,
+            vec![ShortcutConflict {
+                modifiers: CTRL,
+                vkey: VirtualKeyCode::S,
+                old: Command::Save,
+                new: Command::Print,
+            }]
+        );
+        assert_eq!(shortcuts.get(CTRL, VirtualKeyCode::Q), None);
+        assert_eq!(shortcuts.get(CTRL, VirtualKeyCode::S), Some(Command::Print));
+        assert_eq!(shortcuts.get(CTRL, VirtualKeyCode::W), Some(Command::Close));
+        assert_eq!(shortcuts.get(CTRL, VirtualKeyCode::N), Some(Command::New));
+    }
+
+    #[test]
+    fn chords() {
+        use VirtualKeyCode as VK;
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.bind(CTRL, VK::K, Command::Cut);
+        shortcuts.bind_chord(&[(CTRL, VK::K), (CTRL, VK::C)], Command::Copy);
+        shortcuts.bind_chord(
+            &[(CTRL, VK::K), (CTRL, VK::V), (CTRL, VK::V)],
+            Command::Paste,
+        );
+
+        let empty = ModifiersState::empty();
+        assert_eq!(shortcuts.get_chord(&[], CTRL, VK::K), ShortcutMatch::Prefix);
+        assert_eq!(
+            shortcuts.get_chord(&[], empty, VK::Home),
+            ShortcutMatch::Command(Command::Home)
+        );
+        assert_eq!(shortcuts.get_chord(&[], CTRL, VK::C), ShortcutMatch::None);
+
+        let prefix = [(CTRL, VK::K)];
+        let m = shortcuts.get_chord(&prefix, CTRL, VK::C);
+        assert_eq!(m, ShortcutMatch::Command(Command::Copy));
+        assert_eq!(
+            shortcuts.get_chord(&prefix, CTRL, VK::V),
+            ShortcutMatch::Prefix
+        );
+        // Single-stroke bindings do not match with a prefix:
+        assert_eq!(
+            shortcuts.get_chord(&prefix, CTRL, VK::K),
+            ShortcutMatch::None
+        );
+        assert_eq!(
+            shortcuts.get_chord(&prefix, empty, VK::Home),
+            ShortcutMatch::None
+        );
+
+        let prefix = [(CTRL, VK::K), (CTRL, VK::V)];
+        let m = shortcuts.get_chord(&prefix, CTRL, VK::V);
+        assert_eq!(m, ShortcutMatch::Command(Command::Paste));
+    }
+
+    #[test]
+    fn merge_chords() {
+        use VirtualKeyCode as VK;
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.bind(CTRL, VK::K, Command::Cut);
+        shortcuts.bind_chord(&[(CTRL, VK::L), (CTRL, VK::C)], Command::Copy);
+
+        let mut user = Shortcuts::new();
+        user.bind_chord(&[(CTRL, VK::K), (CTRL, VK::C)], Command::Copy);
+        user.bind(CTRL, VK::L, Command::Paste);
+        let mut conflicts = shortcuts.merge(&user);
+        conflicts.sort_by_key(|c| c.vkey);
+        assert_eq!(
+            conflicts,
+            vec![
+                ShortcutConflict {
+                    modifiers: CTRL,
+                    vkey: VK::K,
+                    old: Command::Cut,
+                    new: Command::Copy,
+                },
+                ShortcutConflict {
+                    modifiers: CTRL,
+                    vkey: VK::L,
+                    old: Command::Paste,
+                    new: Command::Copy,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn describe_command() {
+        let mut shortcuts = Shortcuts::new();
+        assert_eq!(shortcuts.describe_command(Command::Save), None);
+
+        let chord = [(CTRL, VirtualKeyCode::K), (CTRL, VirtualKeyCode::S)];
+        shortcuts.bind_chord(&chord, Command::Save);
+        let desc = Shortcuts::describe(&chord);
+        assert_eq!(shortcuts.describe_command(Command::Save), Some(desc));
+
+        // Single strokes are preferred, then the shortest description, then
+        // the first in lexicographic order:
+        shortcuts.bind(
+            CTRL | ModifiersState::SHIFT,
+            VirtualKeyCode::S,
+            Command::Save,
+        );
+        shortcuts.bind(CTRL, VirtualKeyCode::T, Command::Save);
+        shortcuts.bind(CTRL, VirtualKeyCode::S, Command::Save);
+        let desc = Shortcuts::describe(&[(CTRL, VirtualKeyCode::S)]);
+        assert_eq!(shortcuts.describe_command(Command::Save), Some(desc));
+    }
+}
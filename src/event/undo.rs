@@ -0,0 +1,460 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Event handling: undo history
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+
+#[allow(unused)]
+use super::Event; // for doc-links
+use super::{Handler, Manager, Response};
+use crate::WidgetId;
+
+/// Maximum number of undo steps stored per window
+const UNDO_DEPTH: usize = 100;
+
+/// A reversible operation
+///
+/// Operations are recorded in a window-wide history via
+/// [`Manager::push_undo`], tagged with the [`WidgetId`] of the widget they
+/// apply to. On undo or redo, [`Event::Undo`] is sent to the target widget,
+/// which should apply the operation via [`UndoRequest::apply`]. The operation
+/// is passed the widget (as `Any`) and returns the message the widget emits,
+/// if any, just as for the corresponding user input: parents thus see the
+/// change.
+///
+/// Since widgets may be replaced, implementations should check the widget's
+/// type in [`UndoOp::is_applicable`]; inapplicable operations are discarded.
+///
+/// See also [`UndoValue`].
+pub trait UndoOp: Debug {
+    /// Check whether the operation may be applied to `widget`
+    ///
+    /// This is called before [`UndoOp::undo`] (with `redo == false`) or
+    /// [`UndoOp::redo`] (with `redo == true`). If false, the operation is
+    /// discarded and the next in the history is tried. The default
+    /// implementation returns true.
+    fn is_applicable(&self, widget: &dyn Any, redo: bool) -> bool {
+        let _ = (widget, redo);
+        true
+    }
+
+    /// Revert the operation
+    ///
+    /// Returns the message to be emitted by the `widget`, if any. This must
+    /// have the widget's [`Handler::Msg`] type.
+    fn undo(&mut self, widget: &mut dyn Any, mgr: &mut Manager) -> Option<Box<dyn Any>>;
+
+    /// Re-apply the operation (after [`UndoOp::undo`])
+    ///
+    /// Returns a message as for [`UndoOp::undo`].
+    fn redo(&mut self, widget: &mut dyn Any, mgr: &mut Manager) -> Option<Box<dyn Any>>;
+
+    /// Access as `Any`
+    fn as_any(&self) -> &dyn Any;
+
+    /// Try to merge a following operation on the same widget into `self`
+    ///
+    /// If this returns true, `next` is discarded. Merging only happens within
+    /// a group: each key release and the end of each press ends the group.
+    /// The default implementation never merges.
+    fn merge(&mut self, next: &dyn UndoOp) -> bool {
+        let _ = next;
+        false
+    }
+}
+
+/// An [`UndoOp`] which sets a value
+///
+/// This stores the value before and after an operation and uses the function
+/// `set` to apply either to a widget of type `W`. This function should emit
+/// the same message as the corresponding user input.
+///
+/// If constructed with [`UndoValue::merging`], consecutive changes to the
+/// same widget within a group are merged into a single step (e.g. for
+/// dragging a slider).
+pub struct UndoValue<W: Handler, T> {
+    old: T,
+    new: T,
+    set: fn(&mut W, &mut Manager, T) -> Option<W::Msg>,
+    merge: bool,
+}
+
+impl<W: Handler, T: Debug> Debug for UndoValue<W, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "UndoValue {{ old: {:?}, new: {:?}, merge: {:?}, .. }}",
+            self.old, self.new, self.merge
+        )
+    }
+}
+
+impl<W: Handler, T> UndoValue<W, T> {
+    /// Construct from the `old` and `new` values and a setter
+    #[inline]
+    pub fn new(old: T, new: T, set: fn(&mut W, &mut Manager, T) -> Option<W::Msg>) -> Self {
+        UndoValue {
+            old,
+            new,
+            set,
+            merge: false,
+        }
+    }
+
+    /// Construct, merging consecutive changes
+    #[inline]
+    pub fn merging(old: T, new: T, set: fn(&mut W, &mut Manager, T) -> Option<W::Msg>) -> Self {
+        UndoValue {
+            old,
+            new,
+            set,
+            merge: true,
+        }
+    }
+
+    fn apply(&self, widget: &mut dyn Any, mgr: &mut Manager, value: T) -> Option<Box<dyn Any>>
+    where
+        W: 'static,
+        W::Msg: 'static,
+    {
+        let w = widget.downcast_mut::<W>()?;
+        let msg = (self.set)(w, mgr, value)?;
+        Some(Box::new(msg))
+    }
+}
+
+impl<W: Handler + 'static, T: Clone + Debug + 'static> UndoOp for UndoValue<W, T>
+where
+    W::Msg: 'static,
+{
+    fn is_applicable(&self, widget: &dyn Any, _: bool) -> bool {
+        widget.is::<W>()
+    }
+
+    fn undo(&mut self, widget: &mut dyn Any, mgr: &mut Manager) -> Option<Box<dyn Any>> {
+        self.apply(widget, mgr, self.old.clone())
+    }
+
+    fn redo(&mut self, widget: &mut dyn Any, mgr: &mut Manager) -> Option<Box<dyn Any>> {
+        self.apply(widget, mgr, self.new.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn merge(&mut self, next: &dyn UndoOp) -> bool {
+        if !self.merge {
+            return false;
+        }
+        if let Some(next) = next.as_any().downcast_ref::<Self>() {
+            self.new = next.new.clone();
+            return true;
+        }
+        false
+    }
+}
+
+struct UndoRequestInner {
+    op: Option<Box<dyn UndoOp>>,
+    applied: bool,
+}
+
+/// An undo or redo request ([`Event::Undo`])
+///
+/// Widgets which push operations via [`Manager::push_undo`] should handle
+/// [`Event::Undo`] by returning the result of [`UndoRequest::apply`]. If the
+/// event is not handled, the operation is still applied, but any message is
+/// discarded.
+///
+/// The request is shared, thus cloning is cheap.
+#[derive(Clone)]
+pub struct UndoRequest {
+    inner: Rc<RefCell<UndoRequestInner>>,
+    redo: bool,
+}
+
+impl UndoRequest {
+    pub(crate) fn new(op: Box<dyn UndoOp>, redo: bool) -> Self {
+        let inner = UndoRequestInner {
+            op: Some(op),
+            applied: false,
+        };
+        UndoRequest {
+            inner: Rc::new(RefCell::new(inner)),
+            redo,
+        }
+    }
+
+    /// True for redo, false for undo
+    #[inline]
+    pub fn is_redo(&self) -> bool {
+        self.redo
+    }
+
+    /// Apply the operation to `widget`, returning its message (if any)
+    ///
+    /// This does nothing if the request has already been applied. Otherwise,
+    /// the result is [`Response::Msg`] if the operation returns a message of
+    /// the widget's type, else [`Response::Update`].
+    pub fn apply<W>(&self, widget: &mut W, mgr: &mut Manager) -> Response<W::Msg>
+    where
+        W: Handler + ?Sized,
+        W::Msg: 'static,
+    {
+        match self.apply_any(widget.as_any_mut(), mgr) {
+            None => Response::Update,
+            Some(msg) => match msg.downcast::<W::Msg>() {
+                Ok(msg) => Response::Msg(*msg),
+                Err(_) => {
+                    log::warn!("UndoRequest::apply: message does not match widget type");
+                    Response::Update
+                }
+            },
+        }
+    }
+
+    fn apply_any(&self, widget: &mut dyn Any, mgr: &mut Manager) -> Option<Box<dyn Any>> {
+        let mut inner = self.inner.borrow_mut();
+        if inner.applied {
+            return None;
+        }
+        inner.applied = true;
+        let op = inner.op.as_mut()?;
+        match self.redo {
+            false => op.undo(widget, mgr),
+            true => op.redo(widget, mgr),
+        }
+    }
+
+    /// Apply directly (if not already applied), discarding any message
+    pub(crate) fn apply_fallback(&self, widget: &mut dyn Any, mgr: &mut Manager) {
+        let _ = self.apply_any(widget, mgr);
+    }
+
+    pub(crate) fn take_op(&self) -> Option<Box<dyn UndoOp>> {
+        self.inner.borrow_mut().op.take()
+    }
+}
+
+impl Debug for UndoRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let inner = self.inner.borrow();
+        write!(
+            f,
+            "UndoRequest {{ op: {:?}, redo: {:?} }}",
+            inner.op, self.redo
+        )
+    }
+}
+
+/// Requests are equal if they are clones of the same request
+impl PartialEq for UndoRequest {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+/// Window-wide undo history
+#[derive(Debug, Default)]
+pub(crate) struct UndoStack {
+    undo: VecDeque<(WidgetId, Box<dyn UndoOp>)>,
+    redo: Vec<(WidgetId, Box<dyn UndoOp>)>,
+    /// True if the last undo entry may be merged with the next
+    group_open: bool,
+    /// Requests: `true` for redo, `false` for undo
+    pub(crate) pending: Vec<bool>,
+}
+
+impl UndoStack {
+    pub(crate) fn push(&mut self, id: WidgetId, op: Box<dyn UndoOp>) {
+        self.redo.clear();
+        if self.group_open {
+            if let Some(last) = self.undo.back_mut() {
+                if last.0 == id && last.1.merge(&*op) {
+                    return;
+                }
+            }
+        }
+        if self.undo.len() >= UNDO_DEPTH {
+            self.undo.pop_front();
+        }
+        self.undo.push_back((id, op));
+        self.group_open = true;
+    }
+
+    /// End the current group: the next entry is not merged
+    #[inline]
+    pub(crate) fn end_group(&mut self) {
+        self.group_open = false;
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.group_open = false;
+        self.pending.clear();
+    }
+
+    pub(crate) fn pop_undo(&mut self) -> Option<(WidgetId, Box<dyn UndoOp>)> {
+        self.group_open = false;
+        self.undo.pop_back()
+    }
+
+    pub(crate) fn pop_redo(&mut self) -> Option<(WidgetId, Box<dyn UndoOp>)> {
+        self.group_open = false;
+        self.redo.pop()
+    }
+
+    /// Record an undone entry (on the redo stack)
+    pub(crate) fn push_undone(&mut self, entry: (WidgetId, Box<dyn UndoOp>)) {
+        self.redo.push(entry);
+    }
+
+    /// Record a redone entry (on the undo stack)
+    pub(crate) fn push_redone(&mut self, entry: (WidgetId, Box<dyn UndoOp>)) {
+        self.undo.push_back(entry);
+    }
+
+    /// Update entries for renamed widgets, removing those of removed widgets
+    pub(crate) fn rename(&mut self, renames: &HashMap<WidgetId, WidgetId>) {
+        let rename = |(id, op): (WidgetId, Box<dyn UndoOp>)| renames.get(&id).map(|id| (*id, op));
+        self.undo = self.undo.drain(..).filter_map(rename).collect();
+        self.redo = self.redo.drain(..).filter_map(rename).collect();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Op(i32, bool);
+
+    impl UndoOp for Op {
+        fn undo(&mut self, _: &mut dyn Any, _: &mut Manager) -> Option<Box<dyn Any>> {
+            None
+        }
+        fn redo(&mut self, _: &mut dyn Any, _: &mut Manager) -> Option<Box<dyn Any>> {
+            None
+        }
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+        fn merge(&mut self, next: &dyn UndoOp) -> bool {
+            match next.as_any().downcast_ref::<Op>() {
+                Some(next) if self.1 => {
+                    self.0 = next.0;
+                    true
+                }
+                _ => false,
+            }
+        }
+    }
+
+    fn value(entry: Option<(WidgetId, Box<dyn UndoOp>)>) -> Option<(WidgetId, i32)> {
+        entry.map(|(id, op)| (id, op.as_any().downcast_ref::<Op>().unwrap().0))
+    }
+
+    #[test]
+    fn undo_redo() {
+        let (id1, id2) = (WidgetId::FIRST, WidgetId::FIRST.next());
+        let mut stack = UndoStack::default();
+        assert!(!stack.can_undo());
+        stack.push(id1, Box::new(Op(1, false)));
+        stack.push(id2, Box::new(Op(2, false)));
+        assert!(stack.can_undo());
+
+        let entry = stack.pop_undo().unwrap();
+        assert_eq!(entry.0, id2);
+        stack.push_undone(entry);
+        assert!(stack.can_redo());
+        let entry = stack.pop_redo().unwrap();
+        assert_eq!(entry.0, id2);
+        stack.push_redone(entry);
+        assert!(!stack.can_redo());
+
+        assert_eq!(value(stack.pop_undo()), Some((id2, 2)));
+        let entry = stack.pop_undo().unwrap();
+        stack.push_undone(entry);
+        assert!(stack.can_redo());
+        // A new entry clears the redo history
+        stack.push(id2, Box::new(Op(3, false)));
+        assert!(!stack.can_redo());
+        assert_eq!(value(stack.pop_undo()), Some((id2, 3)));
+        assert_eq!(value(stack.pop_undo()), None);
+    }
+
+    #[test]
+    fn merge() {
+        let (id1, id2) = (WidgetId::FIRST, WidgetId::FIRST.next());
+        let mut stack = UndoStack::default();
+        stack.push(id1, Box::new(Op(1, true)));
+        stack.push(id1, Box::new(Op(2, true)));
+        // Entries for another widget are not merged
+        stack.push(id2, Box::new(Op(3, true)));
+        stack.push(id1, Box::new(Op(4, true)));
+        stack.push(id1, Box::new(Op(5, true)));
+        // A new group is not merged with the last
+        stack.end_group();
+        stack.push(id1, Box::new(Op(6, true)));
+
+        assert_eq!(value(stack.pop_undo()), Some((id1, 6)));
+        assert_eq!(value(stack.pop_undo()), Some((id1, 5)));
+        assert_eq!(value(stack.pop_undo()), Some((id2, 3)));
+        assert_eq!(value(stack.pop_undo()), Some((id1, 2)));
+        assert_eq!(value(stack.pop_undo()), None);
+    }
+
+    #[test]
+    fn undo_emits_message() {
+        use crate::testing::Harness;
+        use crate::widget::CheckBoxBare;
+        use crate::WidgetCore;
+
+        let checkbox = CheckBoxBare::new_on(|_, state| Some(state));
+        let mut harness = Harness::new(checkbox);
+        let rect = harness.widget().rect();
+        harness.click(rect.pos + rect.size / 2);
+        assert_eq!(harness.take_messages(), vec![true]);
+
+        harness.with_manager(|mgr, _| mgr.undo());
+        assert_eq!(harness.take_messages(), vec![false]);
+        harness.with_manager(|mgr, _| mgr.redo());
+        assert_eq!(harness.take_messages(), vec![true]);
+    }
+
+    #[test]
+    fn depth() {
+        let id = WidgetId::FIRST;
+        let mut stack = UndoStack::default();
+        let n = UNDO_DEPTH as i32 + 10;
+        for i in 0..n {
+            stack.push(id, Box::new(Op(i, false)));
+        }
+        let mut count = 0;
+        let mut last = n;
+        while let Some((_, v)) = value(stack.pop_undo()) {
+            assert_eq!(v, last - 1);
+            last = v;
+            count += 1;
+        }
+        assert_eq!(count, UNDO_DEPTH);
+        assert_eq!(last, 10);
+    }
+}
@@ -11,6 +11,7 @@
 //! -   a [`layout`] solver and [`event`] handling for widgets
 //! -   building blocks including [`geom`] types and a [`draw`] API
 //! -   some pre-build widgets: the [`widget`] module
+//! -   a [`testing`] harness to drive widgets with synthetic input
 //!
 //! See also these external crates:
 //!
@@ -36,6 +37,7 @@ mod toolkit;
 mod traits;
 
 // public implementations:
+pub mod access;
 pub mod class;
 pub mod conv;
 pub mod dir;
@@ -44,6 +46,7 @@ pub mod event;
 pub mod geom;
 pub mod layout;
 pub mod prelude;
+pub mod testing;
 pub mod text;
 pub mod widget;
 
@@ -86,6 +86,10 @@
 //! is unable to enumerate the widgets correctly. In that case one must opt out
 //! of deriving this trait with `#[widget(children = noauto)]` on the struct.
 //!
+//! A child may be given a name with `#[widget(name = "...")]` (see
+//! [`WidgetCore::name`]); this is applied to the child on configure unless the
+//! child already has a name.
+//!
 //! ### Layout
 //!
 //! The [`Layout`] trait is used to define size, structure and appearance of a
@@ -146,6 +150,13 @@
 //! This field supports `Default` and `Clone`, thus may be constructed with
 //! `layout_data: Default::default()`.
 //!
+//! ### Accessible
+//!
+//! The [`Accessible`] trait describes the widget to assistive technologies.
+//! It is derived by default with an empty implementation (a widget without
+//! specific role). `#[widget(access = noauto)]` opts-out of deriving this
+//! trait, in which case it must be implemented manually.
+//!
 //! ### WidgetConfig
 //!
 //! The [`WidgetConfig`] trait allows additional configuration of widget
@@ -416,7 +427,8 @@
 use crate::{
     event::{Handler, SendEvent},
     layout::AlignHints,
-    CoreData, Layout, LayoutData, Widget, WidgetChildren, WidgetConfig, WidgetCore, WidgetId,
+    Accessible, CoreData, Layout, LayoutData, Widget, WidgetChildren, WidgetConfig, WidgetCore,
+    WidgetId,
 };
 
 pub use kas_macros::{make_widget, VoidMsg, Widget};
@@ -36,8 +36,8 @@ pub use kas::text::{EditableTextApi, Text, TextApi, TextApiExt};
 #[doc(no_inline)]
 pub use kas::WidgetId;
 #[doc(no_inline)]
+pub use kas::{Accessible, Layout, ThemeApi, Widget, WidgetChildren, WidgetConfig, WidgetCore};
+#[doc(no_inline)]
 pub use kas::{Boxed, TkAction};
 #[doc(no_inline)]
 pub use kas::{CoreData, LayoutData};
-#[doc(no_inline)]
-pub use kas::{Layout, ThemeApi, Widget, WidgetChildren, WidgetConfig, WidgetCore};
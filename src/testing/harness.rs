@@ -0,0 +1,590 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Widget test harness
+
+use std::cell::RefCell;
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+use std::time::Duration;
+
+use super::MockShell;
+use kas::access::AccessTree;
+use kas::event::{self, Config, Event, InputEvent, Manager, ManagerState, Response};
+use kas::event::{ModifiersState, MouseButton, ScrollDelta, TouchPhase, VirtualKeyCode};
+use kas::event::{Recording, Replay};
+use kas::geom::{Coord, Rect, Size};
+use kas::layout::SolveCache;
+use kas::prelude::*;
+use kas::{ShellWindow, ThemeAction, WidgetPath, WindowId};
+
+/// Wrapper recording all responses from the inner widget
+#[derive(Widget)]
+#[layout(single)]
+#[handler(send=noauto, msg=VoidMsg)]
+struct Capture<W: Widget + 'static> {
+    #[widget_core]
+    core: CoreData,
+    #[widget]
+    inner: W,
+    responses: Vec<Response<W::Msg>>,
+}
+
+impl<W: Widget> Debug for Capture<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Capture {{ core: {:?}, inner: {:?}, responses: [..; {}] }}",
+            self.core,
+            self.inner,
+            self.responses.len(),
+        )
+    }
+}
+
+impl<W: Widget> event::SendEvent for Capture<W> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() || id > self.inner.id() {
+            return Response::Unhandled;
+        }
+
+        let r = self.inner.send(mgr, id, event);
+        let result = match r {
+            Response::None => Response::None,
+            Response::Unhandled => Response::Unhandled,
+            Response::Focus(rect) => Response::Focus(rect),
+            Response::Update => Response::Update,
+            Response::Msg(_) => Response::None,
+        };
+        self.responses.push(r);
+        result
+    }
+}
+
+/// A test harness for widgets
+///
+/// The harness owns a widget (within a [`kas::widget::Window`]), an event
+/// manager and a mock shell. It lays out the widget at its ideal size (see
+/// [`Harness::resize`]) and allows injection of synthetic input events. After
+/// each input the harness processes pending actions as a shell would: pop-ups
+/// are opened and closed, the widget is reconfigured or resized as required
+/// and update handles are triggered. Requests made of the shell remain
+/// available through [`Harness::shell`].
+///
+/// Responses returned to the root (including messages) are recorded and may
+/// be retrieved via [`Harness::take_responses`] or [`Harness::take_messages`].
+///
+/// Mouse input is applied at the last coordinate passed to
+/// [`Harness::mouse_move`]. Key presses use the virtual key code as the
+/// scancode; shortcuts follow the event [`Config`].
+pub struct Harness<W: Widget + 'static> {
+    state: ManagerState,
+    shell: MockShell,
+    window: kas::widget::Window<Capture<W>>,
+    solve_cache: SolveCache,
+    size: Size,
+    action: TkAction,
+    popups: Vec<WindowId>,
+    num_updates: usize,
+    num_theme_actions: usize,
+}
+
+impl<W: Widget + 'static> Harness<W> {
+    /// Construct, using the default event configuration
+    #[inline]
+    pub fn new(widget: W) -> Self {
+        Self::new_config(widget, Default::default())
+    }
+
+    /// Construct with the given event configuration
+    ///
+    /// Default fonts are loaded (panicking on error), then the widget is
+    /// configured and sized to its ideal size.
+    pub fn new_config(widget: W, config: Rc<RefCell<Config>>) -> Self {
+        if let Err(e) = kas::text::fonts::fonts().load_default() {
+            panic!("Error loading font: {}", e);
+        }
+
+        let capture = Capture {
+            core: Default::default(),
+            inner: widget,
+            responses: vec![],
+        };
+        let mut window = kas::widget::Window::new("Harness", capture);
+        let mut shell = MockShell::new();
+        let mut state = ManagerState::new(config);
+        state.configure(&mut shell, &mut window);
+
+        let mut solve_cache = None;
+        shell.size_handle(&mut |size_handle| {
+            solve_cache = Some(SolveCache::find_constraints(&mut window, size_handle));
+        });
+        let solve_cache = solve_cache.unwrap();
+        let size = solve_cache.ideal(true).max(Size(1, 1));
+
+        let mut harness = Harness {
+            state,
+            shell,
+            window,
+            solve_cache,
+            size,
+            action: TkAction::empty(),
+            popups: vec![],
+            num_updates: 0,
+            num_theme_actions: 0,
+        };
+        harness.apply(TkAction::RESIZE);
+        harness.action = TkAction::empty();
+        harness
+    }
+
+    /// Access the widget
+    #[inline]
+    pub fn widget(&self) -> &W {
+        &self.window.w.inner
+    }
+
+    /// Access the widget mutably
+    ///
+    /// Changes which affect layout should be followed by a call to
+    /// [`Harness::resize`] or by sending the appropriate [`TkAction`] via
+    /// [`Harness::with_manager`].
+    #[inline]
+    pub fn widget_mut(&mut self) -> &mut W {
+        &mut self.window.w.inner
+    }
+
+    /// Access the event manager state
+    ///
+    /// This may be used to query focus and highlighting, e.g.
+    /// [`ManagerState::nav_focus`].
+    #[inline]
+    pub fn state(&self) -> &ManagerState {
+        &self.state
+    }
+
+    /// Call `f` with a [`Manager`] and the widget, then process actions
+    pub fn with_manager<F: FnOnce(&mut Manager, &mut W)>(&mut self, f: F) {
+        let inner = &mut self.window.w.inner;
+        self.state.with(&mut self.shell, |mgr| f(mgr, inner));
+        self.apply(TkAction::empty());
+    }
+
+    /// The current window size
+    #[inline]
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// Resize the window
+    ///
+    /// Size rules are recalculated, thus this may also be used after changes
+    /// to the widget which affect its size requirements.
+    pub fn resize(&mut self, size: Size) {
+        self.size = size;
+        self.apply(TkAction::RESIZE);
+    }
+
+    /// Access the shell
+    ///
+    /// This may be used to inspect pop-ups, the clipboard, etc.
+    #[inline]
+    pub fn shell(&self) -> &MockShell {
+        &self.shell
+    }
+
+    /// Access the shell mutably
+    ///
+    /// This may be used to set the clipboard contents. Pop-ups should not be
+    /// opened or closed directly.
+    #[inline]
+    pub fn shell_mut(&mut self) -> &mut MockShell {
+        &mut self.shell
+    }
+
+    /// Take all responses returned to the root since the last call
+    pub fn take_responses(&mut self) -> Vec<Response<W::Msg>> {
+        std::mem::take(&mut self.window.w.responses)
+    }
+
+    /// Take all messages returned to the root since the last call
+    ///
+    /// Other responses are discarded.
+    pub fn take_messages(&mut self) -> Vec<W::Msg> {
+        self.take_responses()
+            .into_iter()
+            .filter_map(|r| match r {
+                Response::Msg(msg) => Some(msg),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Take the union of all actions sent since the last call
+    ///
+    /// Actions are already handled by the harness; this allows tests to check
+    /// that, for example, a redraw was requested.
+    pub fn take_action(&mut self) -> TkAction {
+        std::mem::replace(&mut self.action, TkAction::empty())
+    }
+
+    /// Get the widget under `coord`, if any
+    ///
+    /// This may be the root (the harness window) if no other widget is found.
+    #[inline]
+    pub fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        self.window.find_id(coord)
+    }
+
+    /// Get the path of the widget with this `id`, relative to the widget
+    ///
+    /// Unlike identifiers, paths are stable across reconfigures; see
+    /// [`WidgetPath`].
+    pub fn path_of(&self, id: WidgetId) -> Option<WidgetPath> {
+        self.window.w.inner.path_of(id)
+    }
+
+    /// Get the identifier of the widget at this `path`, relative to the widget
+    pub fn id_of(&self, path: &WidgetPath) -> Option<WidgetId> {
+        self.window.w.inner.find_path(path).map(|w| w.id())
+    }
+
+    /// Get the identifier of the first widget with this `name`, if any
+    ///
+    /// See [`WidgetChildren::find_by_name`].
+    pub fn id_of_name(&self, name: &str) -> Option<WidgetId> {
+        self.window.w.inner.find_by_name(name).map(|w| w.id())
+    }
+
+    /// Send an event directly to the first widget with this `name`
+    ///
+    /// Panics if no such widget is found.
+    pub fn send_event_by_name(&mut self, name: &str, event: Event) {
+        let id = match self.id_of_name(name) {
+            Some(id) => id,
+            None => panic!("Harness: no widget named {:?}", name),
+        };
+        self.send_event(id, event);
+    }
+
+    /// Build a snapshot of the accessibility tree
+    ///
+    /// The root of this tree is the harness window. Via `Display`, the tree
+    /// may be compared against expected text.
+    pub fn access_tree(&self) -> AccessTree {
+        AccessTree::new(&self.state, &self.window)
+    }
+
+    /// Send an event directly to the widget with the given `id`
+    pub fn send_event(&mut self, id: WidgetId, event: Event) {
+        let window = &mut self.window;
+        self.state.with(&mut self.shell, |mgr| {
+            let _ = window.send(mgr, id, event);
+        });
+        self.apply(TkAction::empty());
+    }
+
+    /// Inject an input event, as from a shell
+    ///
+    /// The methods below (e.g. [`Harness::mouse_move`]) are shortcuts for
+    /// this.
+    pub fn input(&mut self, event: InputEvent) {
+        let window = &mut self.window;
+        self.state
+            .with(&mut self.shell, |mgr| mgr.handle_input(window, event));
+        self.apply(TkAction::empty());
+    }
+
+    /// Move the mouse cursor to `coord`
+    pub fn mouse_move(&mut self, coord: Coord) {
+        self.input(InputEvent::CursorMoved(coord));
+    }
+
+    /// Move the mouse cursor out of the window
+    pub fn mouse_leave(&mut self) {
+        self.input(InputEvent::CursorLeft);
+    }
+
+    /// Press a mouse button
+    pub fn mouse_press(&mut self, button: MouseButton) {
+        self.mouse_input(button, true);
+    }
+
+    /// Release a mouse button
+    pub fn mouse_release(&mut self, button: MouseButton) {
+        self.mouse_input(button, false);
+    }
+
+    fn mouse_input(&mut self, button: MouseButton, pressed: bool) {
+        self.input(InputEvent::MouseInput { button, pressed });
+    }
+
+    /// Click (press and release) the left mouse button at `coord`
+    pub fn click(&mut self, coord: Coord) {
+        self.mouse_move(coord);
+        self.mouse_press(MouseButton::Left);
+        self.mouse_release(MouseButton::Left);
+    }
+
+    /// Scroll the mouse wheel (or touchpad)
+    pub fn scroll(&mut self, delta: ScrollDelta) {
+        self.input(InputEvent::Scroll(delta));
+    }
+
+    /// Inject a touch event
+    ///
+    /// Each touch is identified by `id`; a touch should start with phase
+    /// [`TouchPhase::Started`] and finish with [`TouchPhase::Ended`] or
+    /// [`TouchPhase::Cancelled`].
+    pub fn touch(&mut self, id: u64, coord: Coord, phase: TouchPhase) {
+        self.input(InputEvent::Touch { id, coord, phase });
+    }
+
+    /// Set the state of modifier keys
+    pub fn set_modifiers(&mut self, state: ModifiersState) {
+        self.input(InputEvent::Modifiers(state));
+    }
+
+    /// Press a key
+    pub fn key_press(&mut self, vkey: VirtualKeyCode) {
+        let scancode = vkey as u32;
+        self.input(InputEvent::KeyPress { vkey, scancode });
+    }
+
+    /// Release a key
+    pub fn key_release(&mut self, vkey: VirtualKeyCode) {
+        let scancode = vkey as u32;
+        self.input(InputEvent::KeyRelease { scancode });
+    }
+
+    /// Press and release a key
+    pub fn key(&mut self, vkey: VirtualKeyCode) {
+        self.key_press(vkey);
+        self.key_release(vkey);
+    }
+
+    /// Send a received character
+    ///
+    /// This is delivered to the widget with character focus, if any.
+    pub fn received_char(&mut self, c: char) {
+        self.input(InputEvent::ReceivedCharacter(c));
+    }
+
+    /// Send each character of `text` via [`Harness::received_char`]
+    pub fn type_str(&mut self, text: &str) {
+        for c in text.chars() {
+            self.received_char(c);
+        }
+    }
+
+    /// Send input method pre-edit text
+    ///
+    /// This is delivered to the widget with character focus, if any.
+    pub fn ime_preedit(&mut self, text: &str, cursor: Option<(usize, usize)>) {
+        let text = text.to_string();
+        self.input(InputEvent::ImePreedit { text, cursor });
+    }
+
+    /// Send input method committed text
+    ///
+    /// This is delivered to the widget with character focus, if any.
+    pub fn ime_commit(&mut self, text: &str) {
+        self.input(InputEvent::ImeCommit(text.to_string()));
+    }
+
+    /// Process timer updates which are due
+    pub fn update_timers(&mut self) {
+        let window = &mut self.window;
+        self.state
+            .with(&mut self.shell, |mgr| mgr.update_timer(window));
+        self.apply(TkAction::empty());
+    }
+
+    /// Start recording input
+    ///
+    /// See [`Manager::start_recording`].
+    pub fn start_recording(&mut self) {
+        self.state
+            .with(&mut self.shell, |mgr| mgr.start_recording());
+    }
+
+    /// Stop recording input, returning the recording (if any)
+    pub fn stop_recording(&mut self) -> Option<Recording> {
+        let mut recording = None;
+        self.state
+            .with(&mut self.shell, |mgr| recording = mgr.stop_recording());
+        recording
+    }
+
+    /// Replay a recording of input
+    ///
+    /// Events are delivered in order using a virtual clock (see [`Replay`]),
+    /// processing actions after each event or timer update as a shell would.
+    /// The virtual clock remains in use afterwards; see
+    /// [`Harness::advance_time`].
+    pub fn replay(&mut self, recording: Recording) {
+        let mut replay = Replay::new(recording);
+        while replay.step(&mut self.state, &mut self.shell, &mut self.window) {
+            self.apply(TkAction::empty());
+        }
+    }
+
+    /// Advance the virtual clock, processing timer updates
+    ///
+    /// This starts a virtual clock if none is in use, thus timing no longer
+    /// depends on the system clock (see [`Harness::update_timers`]).
+    pub fn advance_time(&mut self, duration: Duration) {
+        let window = &mut self.window;
+        self.state
+            .advance_virtual_time(&mut self.shell, window, duration);
+        self.apply(TkAction::empty());
+    }
+
+    /// Process pending actions as a shell would after input
+    fn apply(&mut self, mut action: TkAction) {
+        let window = &mut self.window;
+        let shell = &mut self.shell;
+        let popups = &mut self.popups;
+        let rect = Rect::new(Coord::ZERO, self.size);
+
+        // Limit iterations: a misbehaving widget could repeatedly request action
+        for _ in 0..8 {
+            let mut idle = true;
+
+            for id in popups.clone().into_iter() {
+                if !shell.popups().iter().any(|p| p.0 == id) {
+                    idle = false;
+                    popups.retain(|p| *p != id);
+                    self.state
+                        .with(shell, |mgr| kas::Window::remove_popup(window, mgr, id));
+                }
+            }
+            let new: Vec<_> = shell
+                .popups()
+                .iter()
+                .filter(|p| !popups.contains(&p.0))
+                .cloned()
+                .collect();
+            for (id, popup) in new.into_iter() {
+                idle = false;
+                popups.push(id);
+                self.state
+                    .with(shell, |mgr| kas::Window::add_popup(window, mgr, id, popup));
+            }
+
+            let updates = shell.updates()[self.num_updates..].to_vec();
+            self.num_updates += updates.len();
+            for (handle, payload) in updates.into_iter() {
+                idle = false;
+                self.state
+                    .with(shell, |mgr| mgr.update_handle(window, handle, payload));
+            }
+
+            action |= self.state.update(shell, window);
+            for theme_action in &shell.theme_actions()[self.num_theme_actions..] {
+                if *theme_action == ThemeAction::ThemeResize {
+                    action |= TkAction::RESIZE;
+                }
+            }
+            self.num_theme_actions = shell.theme_actions().len();
+            self.action |= action;
+
+            if action.contains(TkAction::RECONFIGURE) {
+                self.state.configure(shell, window);
+                action |= TkAction::RESIZE;
+            }
+            if action.contains(TkAction::RESIZE) {
+                let solve_cache = &mut self.solve_cache;
+                shell.size_handle(&mut |size_handle| {
+                    *solve_cache = SolveCache::find_constraints(window, size_handle);
+                });
+            }
+            if action.intersects(TkAction::RESIZE | TkAction::SET_SIZE) {
+                // Resizing may cause widgets to send further actions
+                idle = false;
+                let solve_cache = &mut self.solve_cache;
+                self.state.with(shell, |mgr| {
+                    solve_cache.apply_rect(window, mgr, rect, true);
+                    kas::Window::resize_popups(window, mgr);
+                });
+                action |= TkAction::REGION_MOVED;
+            }
+            if action.contains(TkAction::REGION_MOVED) {
+                self.state.region_moved(shell, window);
+            }
+
+            action = TkAction::empty();
+            if idle {
+                break;
+            }
+        }
+    }
+}
+
+impl<W: Widget + 'static> Debug for Harness<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Harness {{ size: {:?}, action: {:?}, widget: {:?} }}",
+            self.size,
+            self.action,
+            self.widget(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kas::class::HasStr;
+    use kas::widget::{EditBox, TextButton};
+
+    fn centre(rect: Rect) -> Coord {
+        rect.pos + rect.size / 2
+    }
+
+    #[test]
+    fn click() {
+        let mut harness = Harness::new(TextButton::new_msg("&Click", 7));
+        let coord = centre(harness.widget().rect());
+        harness.click(coord);
+        assert_eq!(harness.take_messages(), vec![7]);
+
+        // A press released outside the button does not activate it
+        harness.mouse_press(MouseButton::Left);
+        harness.mouse_move(Coord(-1, -1));
+        harness.mouse_release(MouseButton::Left);
+        assert_eq!(harness.take_messages(), vec![]);
+    }
+
+    #[test]
+    fn keys() {
+        let mut harness = Harness::new(TextButton::new_msg("&Click", 7));
+        harness.key(VirtualKeyCode::Tab);
+        assert!(harness.state().nav_focus(harness.widget().id()));
+        harness.key(VirtualKeyCode::Return);
+        assert_eq!(harness.take_messages(), vec![7]);
+
+        let mut harness = Harness::new(EditBox::new(""));
+        let coord = centre(harness.widget().rect());
+        harness.click(coord);
+        harness.type_str("abc");
+        harness.key(VirtualKeyCode::Back);
+        assert_eq!(harness.widget().get_str(), "ab");
+    }
+
+    #[test]
+    fn touch() {
+        let mut harness = Harness::new(TextButton::new_msg("&Click", 7));
+        let coord = centre(harness.widget().rect());
+        harness.touch(1, coord, TouchPhase::Started);
+        harness.touch(1, coord, TouchPhase::Ended);
+        assert_eq!(harness.take_messages(), vec![7]);
+
+        harness.touch(2, coord, TouchPhase::Started);
+        harness.touch(2, coord, TouchPhase::Cancelled);
+        assert_eq!(harness.take_messages(), vec![]);
+    }
+}
@@ -0,0 +1,31 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Testing utilities
+//!
+//! The [`Harness`] drives a widget without any windowing system, shell or
+//! graphics: input is injected synthetically (e.g. [`Harness::click`],
+//! [`Harness::type_str`]) and the resulting responses and messages may be
+//! inspected. Layout uses [`TestSizeHandle`], which approximates the metrics
+//! of `kas_theme::FlatTheme`.
+//!
+//! ```
+//! use kas::prelude::*;
+//! use kas::testing::Harness;
+//! use kas::widget::TextButton;
+//!
+//! let mut harness = Harness::new(TextButton::new_msg("&Click", 7));
+//! let coord = harness.widget().rect().pos;
+//! harness.click(coord);
+//! assert_eq!(harness.take_messages(), vec![7]);
+//! ```
+
+mod harness;
+mod shell;
+mod size;
+
+pub use harness::Harness;
+pub use shell::{MockShell, MockTheme};
+pub use size::TestSizeHandle;
@@ -0,0 +1,359 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Mock shell
+
+use std::borrow::Cow;
+use std::fmt;
+use std::num::NonZeroU32;
+
+use super::TestSizeHandle;
+use kas::draw::SizeHandle;
+use kas::event::{CursorIcon, UpdateHandle};
+use kas::geom::Rect;
+use kas::{Popup, ShellWindow, ThemeAction, ThemeApi, WindowId};
+
+/// Theme state recorded by the [`MockShell`]
+///
+/// Only the font size affects layout (via [`TestSizeHandle`]); colour scheme
+/// and theme names are merely recorded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockTheme {
+    font_size: f32,
+    colours: Option<String>,
+    theme: Option<String>,
+}
+
+impl Default for MockTheme {
+    fn default() -> Self {
+        MockTheme {
+            font_size: 12.0,
+            colours: None,
+            theme: None,
+        }
+    }
+}
+
+impl MockTheme {
+    /// The font size (points per Em)
+    #[inline]
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    /// The last colour scheme set, if any
+    #[inline]
+    pub fn colours(&self) -> Option<&str> {
+        self.colours.as_deref()
+    }
+
+    /// The last theme set, if any
+    #[inline]
+    pub fn theme(&self) -> Option<&str> {
+        self.theme.as_deref()
+    }
+}
+
+impl ThemeApi for MockTheme {
+    fn set_font_size(&mut self, pt_size: f32) -> ThemeAction {
+        self.font_size = pt_size;
+        ThemeAction::ThemeResize
+    }
+
+    fn set_colours(&mut self, scheme: &str) -> ThemeAction {
+        self.colours = Some(scheme.to_string());
+        ThemeAction::RedrawAll
+    }
+
+    fn set_theme(&mut self, theme: &str) -> ThemeAction {
+        self.theme = Some(theme.to_string());
+        ThemeAction::ThemeResize
+    }
+}
+
+/// An in-memory implementation of [`ShellWindow`]
+///
+/// This shell does not create any windows, but records all requests made of
+/// it. Together with [`ManagerState`](kas::event::ManagerState) it allows
+/// construction of a [`Manager`](kas::event::Manager) without a windowing
+/// system:
+/// ```
+/// use kas::event::ManagerState;
+/// use kas::testing::MockShell;
+///
+/// let mut shell = MockShell::new();
+/// let mut state = ManagerState::new(Default::default());
+/// state.with(&mut shell, |mgr| {
+///     mgr.set_clipboard("text".into());
+/// });
+/// assert_eq!(shell.clipboard(), Some("text"));
+/// ```
+///
+/// [`ShellWindow::size_handle`] uses a [`TestSizeHandle`], which requires that
+/// fonts have been loaded.
+///
+/// Pop-ups are recorded but not sized or drawn; this is left to the caller
+/// (typically via [`kas::Window::add_popup`]). The [`Harness`] does so
+/// automatically.
+///
+/// [`Harness`]: super::Harness
+pub struct MockShell {
+    scale_factor: f32,
+    next_id: u32,
+    theme: MockTheme,
+    theme_actions: Vec<ThemeAction>,
+    popups: Vec<(WindowId, Popup)>,
+    windows: Vec<(WindowId, Box<dyn kas::Window>)>,
+    closed: Vec<WindowId>,
+    updates: Vec<(UpdateHandle, u64)>,
+    clipboard: Option<String>,
+    cursor_icons: Vec<CursorIcon>,
+    ime_cursor_area: Option<Rect>,
+}
+
+impl Default for MockShell {
+    fn default() -> Self {
+        MockShell::new()
+    }
+}
+
+impl fmt::Debug for MockShell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let windows: Vec<_> = self.windows.iter().map(|w| w.0).collect();
+        f.debug_struct("MockShell")
+            .field("scale_factor", &self.scale_factor)
+            .field("theme", &self.theme)
+            .field("theme_actions", &self.theme_actions)
+            .field("popups", &self.popups)
+            .field("windows", &windows)
+            .field("closed", &self.closed)
+            .field("updates", &self.updates)
+            .field("clipboard", &self.clipboard)
+            .field("cursor_icons", &self.cursor_icons)
+            .field("ime_cursor_area", &self.ime_cursor_area)
+            .finish()
+    }
+}
+
+impl MockShell {
+    /// Construct, with scale factor 1
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_scale_factor(1.0)
+    }
+
+    /// Construct with the given scale factor
+    pub fn new_scale_factor(scale_factor: f32) -> Self {
+        MockShell {
+            scale_factor,
+            next_id: 1,
+            theme: Default::default(),
+            theme_actions: vec![],
+            popups: vec![],
+            windows: vec![],
+            closed: vec![],
+            updates: vec![],
+            clipboard: None,
+            cursor_icons: vec![],
+            ime_cursor_area: None,
+        }
+    }
+
+    /// The scale factor
+    #[inline]
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Open pop-ups, in the order opened
+    ///
+    /// Pop-ups are removed from this list by [`ShellWindow::close_window`].
+    #[inline]
+    pub fn popups(&self) -> &[(WindowId, Popup)] {
+        &self.popups
+    }
+
+    /// Windows added via [`ShellWindow::add_window`] and not yet closed
+    #[inline]
+    pub fn windows(&self) -> &[(WindowId, Box<dyn kas::Window>)] {
+        &self.windows
+    }
+
+    /// Take windows added via [`ShellWindow::add_window`]
+    pub fn take_windows(&mut self) -> Vec<(WindowId, Box<dyn kas::Window>)> {
+        std::mem::take(&mut self.windows)
+    }
+
+    /// Identifiers passed to [`ShellWindow::close_window`], in order
+    #[inline]
+    pub fn closed(&self) -> &[WindowId] {
+        &self.closed
+    }
+
+    /// Updates passed to [`ShellWindow::trigger_update`], in order
+    #[inline]
+    pub fn updates(&self) -> &[(UpdateHandle, u64)] {
+        &self.updates
+    }
+
+    /// Contents of the clipboard
+    #[inline]
+    pub fn clipboard(&self) -> Option<&str> {
+        self.clipboard.as_deref()
+    }
+
+    /// Set (or clear) the contents of the clipboard
+    #[inline]
+    pub fn replace_clipboard(&mut self, content: Option<String>) {
+        self.clipboard = content;
+    }
+
+    /// The last cursor icon set, if any
+    #[inline]
+    pub fn cursor_icon(&self) -> Option<CursorIcon> {
+        self.cursor_icons.last().cloned()
+    }
+
+    /// All cursor icons set, in order
+    #[inline]
+    pub fn cursor_icons(&self) -> &[CursorIcon] {
+        &self.cursor_icons
+    }
+
+    /// The last area passed to [`ShellWindow::set_ime_cursor_area`], if any
+    #[inline]
+    pub fn ime_cursor_area(&self) -> Option<Rect> {
+        self.ime_cursor_area
+    }
+
+    /// The theme state
+    #[inline]
+    pub fn theme(&self) -> &MockTheme {
+        &self.theme
+    }
+
+    /// Actions returned by theme adjustments, in order
+    #[inline]
+    pub fn theme_actions(&self) -> &[ThemeAction] {
+        &self.theme_actions
+    }
+
+    fn next_window_id(&mut self) -> WindowId {
+        self.next_id += 1;
+        WindowId::new(NonZeroU32::new(self.next_id).unwrap())
+    }
+}
+
+impl ShellWindow for MockShell {
+    fn add_popup(&mut self, popup: Popup) -> WindowId {
+        let id = self.next_window_id();
+        self.popups.push((id, popup));
+        id
+    }
+
+    fn add_window(&mut self, widget: Box<dyn kas::Window>) -> WindowId {
+        let id = self.next_window_id();
+        self.windows.push((id, widget));
+        id
+    }
+
+    fn close_window(&mut self, id: WindowId) {
+        self.popups.retain(|p| p.0 != id);
+        self.windows.retain(|w| w.0 != id);
+        self.closed.push(id);
+    }
+
+    fn trigger_update(&mut self, handle: UpdateHandle, payload: u64) {
+        self.updates.push((handle, payload));
+    }
+
+    #[inline]
+    fn get_clipboard(&mut self) -> Option<String> {
+        self.clipboard.clone()
+    }
+
+    #[inline]
+    fn set_clipboard<'c>(&mut self, content: Cow<'c, str>) {
+        self.clipboard = Some(content.into());
+    }
+
+    fn adjust_theme(&mut self, f: &mut dyn FnMut(&mut dyn ThemeApi) -> ThemeAction) {
+        let action = f(&mut self.theme);
+        self.theme_actions.push(action);
+    }
+
+    fn size_handle(&mut self, f: &mut dyn FnMut(&mut dyn SizeHandle)) {
+        let mut size_handle = TestSizeHandle::new(self.theme.font_size, self.scale_factor);
+        f(&mut size_handle);
+    }
+
+    #[inline]
+    fn set_cursor_icon(&mut self, icon: CursorIcon) {
+        self.cursor_icons.push(icon);
+    }
+
+    #[inline]
+    fn set_ime_cursor_area(&mut self, rect: Rect) {
+        self.ime_cursor_area = Some(rect);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kas::dir::Direction;
+    use kas::event::ManagerState;
+    use kas::WidgetId;
+
+    #[test]
+    fn popups() {
+        let mut shell = MockShell::new();
+        let popup = Popup {
+            id: WidgetId::FIRST.next(),
+            parent: WidgetId::FIRST,
+            direction: Direction::Down,
+        };
+        let id1 = shell.add_popup(popup.clone());
+        let id2 = shell.add_popup(popup);
+        assert_ne!(id1, id2);
+        let ids = |shell: &MockShell| shell.popups().iter().map(|p| p.0).collect::<Vec<_>>();
+        assert_eq!(ids(&shell), vec![id1, id2]);
+
+        shell.close_window(id1);
+        assert_eq!(ids(&shell), vec![id2]);
+        assert_eq!(shell.closed(), [id1]);
+    }
+
+    #[test]
+    fn clipboard() {
+        let mut shell = MockShell::new();
+        let mut state = ManagerState::new(Default::default());
+        state.with(&mut shell, |mgr| {
+            assert_eq!(mgr.get_clipboard(), None);
+            mgr.set_clipboard("text".into());
+        });
+        assert_eq!(shell.clipboard(), Some("text"));
+
+        shell.replace_clipboard(Some("other".to_string()));
+        state.with(&mut shell, |mgr| {
+            assert_eq!(mgr.get_clipboard(), Some("other".to_string()));
+        });
+    }
+
+    #[test]
+    fn theme() {
+        let mut shell = MockShell::new();
+        let mut state = ManagerState::new(Default::default());
+        state.with(&mut shell, |mgr| {
+            mgr.adjust_theme(|theme| theme.set_font_size(16.0));
+            mgr.adjust_theme(|theme| theme.set_colours("dark"));
+        });
+        assert_eq!(shell.theme().font_size(), 16.0);
+        assert_eq!(shell.theme().colours(), Some("dark"));
+        assert_eq!(shell.theme().theme(), None);
+        let actions = [ThemeAction::ThemeResize, ThemeAction::RedrawAll];
+        assert_eq!(shell.theme_actions(), actions);
+    }
+}
@@ -0,0 +1,181 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Size handle for testing
+
+use kas::conv::{Cast, CastFloat, ConvFloat};
+use kas::draw::{SizeHandle, TextClass};
+use kas::geom::Size;
+use kas::layout::{AxisInfo, FrameRules, Margins, SizeRules, StretchPolicy};
+use kas::text::{TextApi, TextApiExt};
+
+/// Widget metrics used by the [`Harness`]
+///
+/// Dimensions match those of `kas_theme::FlatTheme` with the default font.
+/// Fonts must be loaded before use (this is done by [`Harness::new`]).
+#[derive(Clone, Debug)]
+pub struct TestSizeHandle {
+    scale_factor: f32,
+    dpp: f32,
+    pt_size: f32,
+    line_height: i32,
+    min_line_length: i32,
+    ideal_line_length: i32,
+    outer_margin: u16,
+    inner_margin: u16,
+    text_margin: u16,
+    frame: i32,
+}
+
+impl TestSizeHandle {
+    /// Construct for the given font size (points per Em) and scale factor
+    pub fn new(pt_size: f32, scale_factor: f32) -> Self {
+        let dpp = scale_factor * (96.0 / 72.0);
+        let dpem = dpp * pt_size;
+        let font = kas::text::fonts::fonts().get(Default::default());
+        TestSizeHandle {
+            scale_factor,
+            dpp,
+            pt_size,
+            line_height: i32::conv_ceil(font.height(dpem)),
+            min_line_length: (8.0 * dpem).cast_nearest(),
+            ideal_line_length: (24.0 * dpem).cast_nearest(),
+            outer_margin: (8.0 * scale_factor).cast_nearest(),
+            inner_margin: (1.0 * scale_factor).cast_nearest(),
+            text_margin: (2.0 * scale_factor).cast_nearest(),
+            frame: (4.0 * scale_factor).cast_nearest(),
+        }
+    }
+
+    fn scaled(&self, x: f32, y: f32) -> Size {
+        Size(
+            (x * self.scale_factor).cast_nearest(),
+            (y * self.scale_factor).cast_nearest(),
+        )
+    }
+}
+
+impl SizeHandle for TestSizeHandle {
+    fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    fn frame(&self, _vert: bool) -> FrameRules {
+        FrameRules::new_sym(self.frame, 0, (0, 0))
+    }
+    fn menu_frame(&self, vert: bool) -> FrameRules {
+        let size = if vert { self.frame / 2 } else { self.frame };
+        FrameRules::new_sym(size, 0, (0, 0))
+    }
+    fn separator(&self) -> Size {
+        Size::splat(self.frame)
+    }
+
+    fn inner_margin(&self) -> Size {
+        Size::splat(self.inner_margin.into())
+    }
+
+    fn outer_margins(&self) -> Margins {
+        Margins::splat(self.outer_margin)
+    }
+
+    fn line_height(&self, _: TextClass) -> i32 {
+        self.line_height
+    }
+
+    fn text_bound(
+        &mut self,
+        text: &mut dyn TextApi,
+        class: TextClass,
+        axis: AxisInfo,
+    ) -> SizeRules {
+        let required = text.update_env(|env| {
+            env.set_dpp(self.dpp);
+            env.set_pt_size(self.pt_size);
+
+            let mut bounds = kas::text::Vec2::INFINITY;
+            if let Some(size) = axis.size_other_if_fixed(false) {
+                bounds.1 = size.cast();
+            } else if let Some(size) = axis.size_other_if_fixed(true) {
+                bounds.0 = size.cast();
+            }
+            env.set_bounds(bounds);
+
+            env.set_wrap(match class {
+                TextClass::Label | TextClass::EditMulti => true,
+                _ => false,
+            });
+        });
+
+        let margins = (self.text_margin, self.text_margin);
+        if axis.is_horizontal() {
+            let bound = i32::conv_ceil(required.0);
+            let (min, ideal) = match class {
+                TextClass::Edit | TextClass::EditMulti => {
+                    (self.min_line_length, self.ideal_line_length)
+                }
+                _ => (
+                    bound.min(self.min_line_length),
+                    bound.min(self.ideal_line_length),
+                ),
+            };
+            let stretch = match class {
+                TextClass::LabelFixed => StretchPolicy::Fixed,
+                TextClass::Button => StretchPolicy::Filler,
+                _ => StretchPolicy::LowUtility,
+            };
+            SizeRules::new(min, ideal, margins, stretch)
+        } else {
+            let min = match class {
+                TextClass::Label => i32::conv_ceil(required.1),
+                TextClass::LabelFixed | TextClass::Button | TextClass::Edit => self.line_height,
+                TextClass::EditMulti => self.line_height * 3,
+            };
+            let ideal = i32::conv_ceil(required.1).max(min);
+            let stretch = match class {
+                TextClass::Button | TextClass::Edit | TextClass::LabelFixed => StretchPolicy::Fixed,
+                TextClass::EditMulti => StretchPolicy::HighUtility,
+                _ => StretchPolicy::Filler,
+            };
+            SizeRules::new(min, ideal, margins, stretch)
+        }
+    }
+
+    fn edit_marker_width(&self) -> f32 {
+        (1.6 * self.scale_factor).round().max(1.0)
+    }
+
+    fn button_surround(&self, _vert: bool) -> FrameRules {
+        let outer = self.outer_margin;
+        FrameRules::new_sym(self.frame, self.inner_margin.into(), (outer, outer))
+    }
+
+    fn edit_surround(&self, _vert: bool) -> FrameRules {
+        FrameRules::new_sym(self.frame, self.inner_margin.into(), (0, 0))
+    }
+
+    fn checkbox(&self) -> Size {
+        let inner = i32::from(self.inner_margin);
+        Size::splat(i32::conv_nearest(9.0 * self.dpp) + 2 * (inner + self.frame))
+    }
+
+    fn radiobox(&self) -> Size {
+        self.checkbox()
+    }
+
+    fn scrollbar(&self) -> (Size, i32) {
+        let size = self.scaled(8.0, 8.0);
+        (size, 2 * size.0)
+    }
+
+    fn slider(&self) -> (Size, i32) {
+        let size = self.scaled(12.0, 25.0);
+        (size, 2 * size.0)
+    }
+
+    fn progress_bar(&self) -> Size {
+        self.scaled(12.0, 12.0)
+    }
+}
@@ -13,6 +13,15 @@
 
 pub use kas_text::*;
 
+mod find;
+pub use find::{find_all, FindOptions};
+
+mod history;
+pub use history::{EditHistory, EditKind, EditState};
+
+mod rich;
+pub use rich::{FormatRun, RichText, TextFormat};
+
 mod selection;
 pub use selection::SelectionHelper;
 
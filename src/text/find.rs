@@ -0,0 +1,115 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Text search
+
+use std::ops::Range;
+
+/// Options for [`find_all`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FindOptions {
+    /// Match case exactly (otherwise, compare lower-case forms)
+    pub case_sensitive: bool,
+    /// Only match whole words
+    ///
+    /// A match is a whole word if not directly preceded or followed by an
+    /// alphanumeric character or `_`.
+    pub whole_word: bool,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Find all non-overlapping occurrences of `pattern` in `text`
+///
+/// Returns byte ranges into `text`, in order. Returns an empty list if
+/// `pattern` is empty.
+///
+/// This is a simple search with `O(n m)` cost, intended for short texts like
+/// those of [`crate::widget::EditField`].
+pub fn find_all(text: &str, pattern: &str, options: FindOptions) -> Vec<Range<usize>> {
+    let mut matches = vec![];
+    if pattern.is_empty() {
+        return matches;
+    }
+
+    let eq = |a: char, b: char| match options.case_sensitive {
+        true => a == b,
+        false => a == b || a.to_lowercase().eq(b.to_lowercase()),
+    };
+
+    let mut start = 0;
+    while start < text.len() {
+        let mut end = None;
+        let mut chars = text[start..].char_indices();
+        let mut pattern_chars = pattern.chars();
+        loop {
+            let p = match pattern_chars.next() {
+                Some(p) => p,
+                None => {
+                    end = Some(chars.next().map(|(i, _)| start + i).unwrap_or(text.len()));
+                    break;
+                }
+            };
+            match chars.next() {
+                Some((_, c)) if eq(c, p) => (),
+                _ => break,
+            }
+        }
+
+        if let Some(end) = end {
+            let whole_word = !options.whole_word
+                || (!text[..start]
+                    .chars()
+                    .next_back()
+                    .map(is_word_char)
+                    .unwrap_or(false)
+                    && !text[end..]
+                        .chars()
+                        .next()
+                        .map(is_word_char)
+                        .unwrap_or(false));
+            if whole_word {
+                matches.push(start..end);
+                start = end;
+                continue;
+            }
+        }
+
+        start += text[start..]
+            .chars()
+            .next()
+            .map(|c| c.len_utf8())
+            .unwrap_or(1);
+    }
+    matches
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_options() {
+        let text = "Foo foo_bar food, FOO";
+        let mut options = FindOptions::default();
+        assert_eq!(find_all(text, "foo", options), [0..3, 4..7, 12..15, 18..21]);
+        options.case_sensitive = true;
+        assert_eq!(find_all(text, "foo", options), [4..7, 12..15]);
+        options.whole_word = true;
+        assert_eq!(find_all(text, "foo", options), []);
+        options.case_sensitive = false;
+        assert_eq!(find_all(text, "foo", options), [0..3, 18..21]);
+        assert_eq!(find_all(text, "", options), []);
+    }
+
+    #[test]
+    fn find_non_overlapping() {
+        let options = FindOptions::default();
+        assert_eq!(find_all("aaaa", "aa", options), [0..2, 2..4]);
+        assert_eq!(find_all("ääxÄ", "ä", options), [0..2, 2..4, 5..7]);
+    }
+}
@@ -0,0 +1,264 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Edit history (undo / redo)
+
+use std::collections::VecDeque;
+
+/// Classification of an edit
+///
+/// Consecutive edits of the same kind may be coalesced into a single undo
+/// step; see [`EditHistory::record`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditKind {
+    /// Insertion of typed characters (coalesced)
+    Insert,
+    /// Deletion of characters (coalesced)
+    Delete,
+    /// Insertion from the clipboard (never coalesced)
+    Paste,
+    /// Any other edit (never coalesced)
+    Other,
+}
+
+impl EditKind {
+    fn coalesces(self) -> bool {
+        match self {
+            EditKind::Insert | EditKind::Delete => true,
+            EditKind::Paste | EditKind::Other => false,
+        }
+    }
+}
+
+/// A saved text state
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditState {
+    /// Text contents
+    pub text: String,
+    /// Formatting (see [`super::RichText`]); empty for plain text
+    pub format: Vec<super::FormatRun>,
+    /// Edit position (see [`super::SelectionHelper`])
+    pub edit_pos: usize,
+    /// Selection position (see [`super::SelectionHelper`])
+    pub sel_pos: usize,
+}
+
+/// Multi-level undo / redo history for text edits
+///
+/// This stores whole-text snapshots and is therefore intended for short
+/// texts (like [`crate::widget::EditField`]).
+///
+/// Usage: call [`EditHistory::record`] *before* each edit with the state
+/// prior to the edit. To undo, call [`EditHistory::undo`] with the current
+/// state and apply the returned state (if any); redo is similar.
+///
+/// A new undo step is started when the edit kind changes, when the kind does
+/// not coalesce ([`EditKind::Paste`], [`EditKind::Other`]) or after
+/// [`EditHistory::break_group`] (e.g. when the edit cursor is moved). Any new
+/// edit clears the redo stack. The oldest undo steps are discarded once the
+/// depth limit is reached.
+#[derive(Clone, Debug)]
+pub struct EditHistory {
+    undo: VecDeque<EditState>,
+    redo: Vec<EditState>,
+    depth: usize,
+    last: Option<EditKind>,
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        EditHistory::new()
+    }
+}
+
+impl EditHistory {
+    /// Default number of undo steps
+    pub const DEFAULT_DEPTH: usize = 100;
+
+    /// Construct with [`EditHistory::DEFAULT_DEPTH`]
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_depth(Self::DEFAULT_DEPTH)
+    }
+
+    /// Construct with the given maximum number of undo steps
+    ///
+    /// A depth of zero disables the history.
+    pub fn with_depth(depth: usize) -> Self {
+        EditHistory {
+            undo: VecDeque::new(),
+            redo: vec![],
+            depth,
+            last: None,
+        }
+    }
+
+    /// Get the maximum number of undo steps
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Set the maximum number of undo steps
+    ///
+    /// If necessary, the steps furthest from the current state are discarded:
+    /// the oldest undo steps and the last redo steps.
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        while self.undo.len() > depth {
+            self.undo.pop_front();
+        }
+        if self.redo.len() > depth {
+            // The next redo step is at the end
+            let excess = self.redo.len() - depth;
+            self.redo.drain(..excess);
+        }
+    }
+
+    /// True if there is anything to undo
+    #[inline]
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// True if there is anything to redo
+    #[inline]
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Clear all history
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.last = None;
+    }
+
+    /// Start a new undo step on the next edit, regardless of kind
+    #[inline]
+    pub fn break_group(&mut self) {
+        self.last = None;
+    }
+
+    /// Record the state prior to an edit
+    ///
+    /// The closure `state` is only called when a new undo step is started.
+    /// If `replaces_selection` is true, a new step is always started.
+    ///
+    /// Returns true when a new undo step was started.
+    pub fn record<F>(&mut self, kind: EditKind, replaces_selection: bool, state: F) -> bool
+    where
+        F: FnOnce() -> EditState,
+    {
+        self.redo.clear();
+        if self.depth == 0 {
+            return false;
+        }
+
+        let coalesce = !replaces_selection && kind.coalesces() && self.last == Some(kind);
+        self.last = Some(kind);
+        if coalesce {
+            return false;
+        }
+
+        if self.undo.len() >= self.depth {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(state());
+        true
+    }
+
+    /// Undo: given the current state, return the state to restore
+    ///
+    /// Returns `None` if there is nothing to undo.
+    pub fn undo(&mut self, current: EditState) -> Option<EditState> {
+        let state = self.undo.pop_back()?;
+        self.redo.push(current);
+        self.last = None;
+        Some(state)
+    }
+
+    /// Redo: given the current state, return the state to restore
+    ///
+    /// Returns `None` if there is nothing to redo.
+    pub fn redo(&mut self, current: EditState) -> Option<EditState> {
+        let state = self.redo.pop()?;
+        self.undo.push_back(current);
+        self.last = None;
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn state(text: &str) -> EditState {
+        EditState {
+            text: text.to_string(),
+            edit_pos: text.len(),
+            sel_pos: text.len(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut history = EditHistory::new();
+        assert!(!history.can_undo());
+        assert!(history.record(EditKind::Insert, false, || state("")));
+        // Consecutive insertions coalesce
+        assert!(!history.record(EditKind::Insert, false, || state("a")));
+        assert!(history.record(EditKind::Delete, false, || state("ab")));
+        assert!(history.record(EditKind::Paste, false, || state("a")));
+
+        assert_eq!(history.undo(state("axy")), Some(state("a")));
+        assert_eq!(history.undo(state("a")), Some(state("ab")));
+        assert!(history.can_redo());
+        assert_eq!(history.redo(state("ab")), Some(state("a")));
+        assert_eq!(history.redo(state("a")), Some(state("axy")));
+        assert_eq!(history.redo(state("axy")), None);
+
+        assert_eq!(history.undo(state("axy")), Some(state("a")));
+        // A new edit clears the redo stack
+        history.record(EditKind::Other, false, || state("a"));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn depth() {
+        let mut history = EditHistory::with_depth(2);
+        for text in &["", "a", "ab"] {
+            history.record(EditKind::Other, false, || state(text));
+        }
+        assert_eq!(history.undo(state("abc")), Some(state("ab")));
+        assert_eq!(history.undo(state("ab")), Some(state("a")));
+        assert_eq!(history.undo(state("a")), None);
+
+        let mut history = EditHistory::with_depth(0);
+        assert!(!history.record(EditKind::Other, false, || state("")));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn set_depth() {
+        let mut history = EditHistory::new();
+        for text in &["", "a", "ab", "abc"] {
+            history.record(EditKind::Other, false, || state(text));
+        }
+        let mut current = state("abcd");
+        for _ in 0..4 {
+            current = history.undo(current).unwrap();
+        }
+        assert_eq!(current, state(""));
+
+        // Keep the redo steps nearest the current state
+        history.set_depth(2);
+        assert_eq!(history.redo(current), Some(state("a")));
+        assert_eq!(history.redo(state("a")), Some(state("ab")));
+        assert_eq!(history.redo(state("ab")), None);
+        assert_eq!(history.depth(), 2);
+    }
+}
@@ -0,0 +1,429 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Rich (formatted) text
+
+use std::ops::{Deref, Range};
+
+use kas::conv::Conv;
+use kas::text::fonts::{fonts, FontId, FontSelector, Style, Weight};
+use kas::text::format::{EditableText, FontToken, FormattableText};
+#[cfg(not(feature = "gat"))]
+use kas::text::OwningVecIter;
+use kas::text::{Effect, EffectFlags};
+
+#[cfg(feature = "markdown")]
+mod markdown;
+
+/// Formatting of a span of [`RichText`]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TextFormat {
+    /// Bold font
+    pub bold: bool,
+    /// Italic font
+    pub italic: bool,
+    /// Underlined
+    pub underline: bool,
+    /// Link target (drawn underlined)
+    pub link: Option<String>,
+}
+
+const PLAIN: TextFormat = TextFormat {
+    bold: false,
+    italic: false,
+    underline: false,
+    link: None,
+};
+
+impl TextFormat {
+    fn effect_flags(&self) -> EffectFlags {
+        match self.underline || self.link.is_some() {
+            true => EffectFlags::UNDERLINE,
+            false => EffectFlags::empty(),
+        }
+    }
+}
+
+/// A run of [`RichText`] with uniform formatting
+///
+/// The run extends from `start` to the start of the next run, or to the end
+/// of the text.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FormatRun {
+    /// Index of the first byte
+    pub start: u32,
+    /// Formatting
+    pub format: TextFormat,
+}
+
+/// Text with formatting
+///
+/// This is a [`String`] with a list of [`FormatRun`]s. Text before the first
+/// run is unformatted; a `RichText` without runs is thus plain text and is
+/// displayed exactly as a `String` would be.
+///
+/// Edits via [`EditableText`] preserve the formatting of surrounding text;
+/// inserted text takes the formatting of the replaced text or else the
+/// preceding character.
+///
+/// With the `markdown` feature, `RichText` may be converted from and to
+/// Markdown (see `RichText::from_markdown`). Underlining is represented
+/// by the HTML tag `<u>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RichText {
+    text: String,
+    runs: Vec<FormatRun>,
+    effects: Vec<Effect<()>>,
+}
+
+impl RichText {
+    /// Construct from text and formatting runs
+    ///
+    /// Runs should be sorted by `start`; redundant runs are removed.
+    pub fn from_runs(text: String, runs: Vec<FormatRun>) -> Self {
+        let mut rich = RichText {
+            text,
+            runs,
+            effects: vec![],
+        };
+        rich.normalise();
+        rich
+    }
+
+    /// Access formatting runs
+    #[inline]
+    pub fn runs(&self) -> &[FormatRun] {
+        &self.runs
+    }
+
+    /// True if the text has no formatting
+    #[inline]
+    pub fn is_plain(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    /// Get the formatting at byte `index`
+    pub fn format_at(&self, index: usize) -> &TextFormat {
+        (self.runs.iter().rev())
+            .find(|run| usize::conv(run.start) <= index)
+            .map(|run| &run.format)
+            .unwrap_or(&PLAIN)
+    }
+
+    /// Get the link target at byte `index`, if any
+    pub fn link_at(&self, index: usize) -> Option<&str> {
+        self.format_at(index).link.as_deref()
+    }
+
+    /// True if `f` holds for the formatting of all text in `range`
+    pub fn all_in_range<F: Fn(&TextFormat) -> bool>(&self, range: Range<usize>, f: F) -> bool {
+        f(self.format_at(range.start))
+            && (self.runs.iter())
+                .filter(|run| {
+                    let start = usize::conv(run.start);
+                    range.start < start && start < range.end
+                })
+                .all(|run| f(&run.format))
+    }
+
+    /// Adjust the formatting of `range` via `f`
+    pub fn set_format<F: Fn(&mut TextFormat)>(&mut self, range: Range<usize>, f: F) {
+        if range.start >= range.end {
+            return;
+        }
+        let i0 = self.split_at(range.start);
+        let i1 = self.split_at(range.end);
+        for run in &mut self.runs[i0..i1] {
+            f(&mut run.format);
+        }
+        self.normalise();
+    }
+
+    /// Copy a sub-range, with formatting
+    pub fn slice(&self, range: Range<usize>) -> RichText {
+        let text = self.text[range.clone()].to_string();
+        let mut runs = vec![FormatRun {
+            start: 0,
+            format: self.format_at(range.start).clone(),
+        }];
+        for run in &self.runs {
+            let start = usize::conv(run.start);
+            if range.start < start && start < range.end {
+                runs.push(FormatRun {
+                    start: u32::conv(start - range.start),
+                    format: run.format.clone(),
+                });
+            }
+        }
+        RichText::from_runs(text, runs)
+    }
+
+    /// Replace `range` with `replace_with`, including its formatting
+    pub fn replace_range_rich(&mut self, range: Range<usize>, replace_with: &RichText) {
+        let start = range.start;
+        self.replace_range(range, &replace_with.text);
+        if replace_with.text.is_empty() {
+            return;
+        }
+
+        let i0 = self.split_at(start);
+        let i1 = self.split_at(start + replace_with.text.len());
+        let runs = std::iter::once(FormatRun {
+            start: u32::conv(start),
+            format: PLAIN,
+        })
+        .chain(replace_with.runs.iter().map(|run| FormatRun {
+            start: run.start + u32::conv(start),
+            format: run.format.clone(),
+        }));
+        self.runs.splice(i0..i1, runs);
+        self.normalise();
+    }
+
+    /// Ensure a run starts at `index`, returning its index in `self.runs`
+    ///
+    /// Returns `self.runs.len()` if `index` is not within the text.
+    fn split_at(&mut self, index: usize) -> usize {
+        if index >= self.text.len() {
+            return self.runs.len();
+        }
+        let i = (self.runs.iter())
+            .position(|run| usize::conv(run.start) >= index)
+            .unwrap_or(self.runs.len());
+        if i < self.runs.len() && usize::conv(self.runs[i].start) == index {
+            return i;
+        }
+        let format = match i {
+            0 => PLAIN,
+            i => self.runs[i - 1].format.clone(),
+        };
+        let start = u32::conv(index);
+        self.runs.insert(i, FormatRun { start, format });
+        i
+    }
+
+    /// Remove redundant runs and update effects
+    fn normalise(&mut self) {
+        let len = self.text.len();
+        self.runs.retain(|run| usize::conv(run.start) < len);
+        // Of runs with equal start, the last applies
+        self.runs.dedup_by(|next, prev| {
+            if next.start == prev.start {
+                std::mem::swap(&mut next.format, &mut prev.format);
+                true
+            } else {
+                false
+            }
+        });
+        self.runs.dedup_by(|next, prev| next.format == prev.format);
+        if self.runs.first().map(|run| run.format == PLAIN) == Some(true) {
+            self.runs.remove(0);
+        }
+
+        self.effects.clear();
+        let mut flags = EffectFlags::empty();
+        for run in &self.runs {
+            let run_flags = run.format.effect_flags();
+            if run_flags != flags {
+                self.effects.push(Effect {
+                    start: run.start,
+                    flags: run_flags,
+                    aux: (),
+                });
+                flags = run_flags;
+            }
+        }
+    }
+}
+
+fn select_font(bold: bool, italic: bool) -> FontId {
+    let mut selector = FontSelector::new();
+    if bold {
+        selector.set_weight(Weight::BOLD);
+    }
+    if italic {
+        selector.set_style(Style::Italic);
+    }
+    fonts().select_font(&selector).unwrap_or_default()
+}
+
+impl Deref for RichText {
+    type Target = str;
+    #[inline]
+    fn deref(&self) -> &str {
+        &self.text
+    }
+}
+
+impl From<String> for RichText {
+    #[inline]
+    fn from(text: String) -> Self {
+        RichText {
+            text,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&str> for RichText {
+    #[inline]
+    fn from(text: &str) -> Self {
+        RichText::from(text.to_string())
+    }
+}
+
+impl FormattableText for RichText {
+    #[cfg(feature = "gat")]
+    type FontTokenIter<'a> = std::vec::IntoIter<FontToken>;
+
+    #[inline]
+    fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    #[cfg(feature = "gat")]
+    #[inline]
+    fn font_tokens<'a>(&'a self, dpp: f32, pt_size: f32) -> Self::FontTokenIter<'a> {
+        self.font_token_vec(dpp, pt_size).into_iter()
+    }
+    #[cfg(not(feature = "gat"))]
+    #[inline]
+    fn font_tokens(&self, dpp: f32, pt_size: f32) -> OwningVecIter<FontToken> {
+        OwningVecIter::new(self.font_token_vec(dpp, pt_size))
+    }
+
+    fn effect_tokens(&self) -> &[Effect<()>] {
+        &self.effects
+    }
+}
+
+impl RichText {
+    fn font_token_vec(&self, dpp: f32, pt_size: f32) -> Vec<FontToken> {
+        let dpem = dpp * pt_size;
+        let mut tokens = vec![];
+        let mut font = (false, false);
+        for run in &self.runs {
+            let run_font = (run.format.bold, run.format.italic);
+            if run_font != font {
+                if tokens.is_empty() && run.start > 0 {
+                    tokens.push(FontToken {
+                        start: 0,
+                        font_id: Default::default(),
+                        dpem,
+                    });
+                }
+                tokens.push(FontToken {
+                    start: run.start,
+                    font_id: select_font(run_font.0, run_font.1),
+                    dpem,
+                });
+                font = run_font;
+            }
+        }
+        tokens
+    }
+}
+
+impl EditableText for RichText {
+    fn set_string(&mut self, string: String) {
+        self.text = string;
+        self.runs.clear();
+        self.effects.clear();
+    }
+
+    fn insert_char(&mut self, index: usize, c: char) {
+        let mut buf = [0u8; 4];
+        self.replace_range(index..index, c.encode_utf8(&mut buf));
+    }
+
+    fn replace_range(&mut self, range: Range<usize>, replace_with: &str) {
+        if self.runs.is_empty() {
+            self.text.replace_range(range, replace_with);
+            return;
+        }
+
+        // Inserted text takes the format of the replaced text or else the
+        // preceding char; text following the range keeps its format.
+        let format = match range.start {
+            start if start < range.end || start == 0 => self.format_at(start),
+            start => self.format_at(start - 1),
+        };
+        let format = format.clone();
+        let end_format = self.format_at(range.end).clone();
+
+        let (start, end) = (u32::conv(range.start), u32::conv(range.end));
+        let len = u32::conv(replace_with.len());
+        let mut runs = Vec::with_capacity(self.runs.len() + 2);
+        runs.extend(self.runs.iter().filter(|run| run.start < start).cloned());
+        runs.push(FormatRun { start, format });
+        runs.push(FormatRun {
+            start: start + len,
+            format: end_format,
+        });
+        runs.extend(
+            (self.runs.iter())
+                .filter(|run| run.start > end)
+                .map(|run| FormatRun {
+                    start: run.start - (end - start) + len,
+                    format: run.format.clone(),
+                }),
+        );
+
+        self.text.replace_range(range, replace_with);
+        self.runs = runs;
+        self.normalise();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bold() -> TextFormat {
+        TextFormat {
+            bold: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn edit_formatted() {
+        let mut text = RichText::from("one two three");
+        text.set_format(4..7, |f| f.bold = true);
+        assert_eq!(text.runs().len(), 2);
+        assert!(text.all_in_range(4..7, |f| f.bold));
+        assert!(!text.all_in_range(3..7, |f| f.bold));
+
+        // Insertion takes the format of the preceding char
+        text.insert_char(7, 's');
+        assert_eq!(&*text, "one twos three");
+        assert!(text.all_in_range(4..8, |f| f.bold));
+        assert_eq!(text.format_at(8), &TextFormat::default());
+
+        text.replace_range(0..4, "");
+        assert_eq!(
+            text.runs(),
+            [
+                FormatRun {
+                    start: 0,
+                    format: bold()
+                },
+                FormatRun {
+                    start: 4,
+                    format: TextFormat::default()
+                },
+            ]
+        );
+
+        let copy = text.slice(2..6);
+        assert_eq!(&*copy, "os t");
+        text.replace_range_rich(10..10, &copy);
+        assert_eq!(&*text, "twos threeos t");
+        assert!(text.all_in_range(10..12, |f| f.bold));
+
+        text.set_format(0..14, |f| f.bold = false);
+        assert!(text.is_plain());
+    }
+}
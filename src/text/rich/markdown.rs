@@ -0,0 +1,307 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Markdown conversion for [`RichText`]
+
+use super::{FormatRun, RichText, TextFormat};
+use kas::conv::Conv;
+use pulldown_cmark::{Event, Parser, Tag};
+
+impl RichText {
+    /// Parse Markdown
+    ///
+    /// Supported formatting is emphasis (italic), strong emphasis (bold),
+    /// links and underlining via the inline HTML tag `<u>`. Headings are
+    /// displayed bold and list items are prefixed with a bullet or number.
+    /// Other formatting is ignored; blocks are separated by blank lines.
+    ///
+    /// This is the inverse of [`RichText::to_markdown`].
+    pub fn from_markdown(input: &str) -> Self {
+        let mut text = String::with_capacity(input.len());
+        let mut runs: Vec<FormatRun> = vec![];
+        let (mut bold, mut italic, mut underline) = (0u32, 0u32, 0u32);
+        let mut links: Vec<String> = vec![];
+        let mut lists: Vec<Option<u64>> = vec![];
+        let mut item: Option<String> = None;
+        let mut block_break = false;
+
+        let mut push = |text: &mut String, s: &str, format: TextFormat| {
+            if runs.last().map(|run| &run.format) != Some(&format) {
+                let start = u32::conv(text.len());
+                runs.push(FormatRun { start, format });
+            }
+            text.push_str(s);
+        };
+
+        for event in Parser::new(input) {
+            let s = match event {
+                Event::Start(tag) => {
+                    match tag {
+                        Tag::Heading(_) | Tag::Strong => bold += 1,
+                        Tag::Emphasis => italic += 1,
+                        Tag::Link(_, dest, _) => links.push(dest.to_string()),
+                        Tag::List(start) => {
+                            lists.push(start);
+                            block_break = true;
+                        }
+                        Tag::Item => {
+                            let indent = "    ".repeat(lists.len().saturating_sub(1));
+                            item = Some(match lists.last_mut() {
+                                Some(Some(n)) => {
+                                    *n += 1;
+                                    format!("{}{}. ", indent, *n - 1)
+                                }
+                                _ => format!("{}• ", indent),
+                            });
+                        }
+                        _ => (),
+                    }
+                    continue;
+                }
+                Event::End(tag) => {
+                    match tag {
+                        Tag::Heading(_) => {
+                            bold -= 1;
+                            block_break = true;
+                        }
+                        Tag::Strong => bold -= 1,
+                        Tag::Emphasis => italic -= 1,
+                        Tag::Link(..) => {
+                            links.pop();
+                        }
+                        Tag::CodeBlock(_) => {
+                            if text.ends_with('\n') {
+                                text.pop();
+                            }
+                            block_break = true;
+                        }
+                        Tag::List(_) => {
+                            lists.pop();
+                            block_break = true;
+                        }
+                        Tag::Paragraph | Tag::Item => block_break = true,
+                        _ => (),
+                    }
+                    continue;
+                }
+                Event::Html(html) => {
+                    match html.trim() {
+                        "<u>" => underline += 1,
+                        "</u>" => underline = underline.saturating_sub(1),
+                        _ => (),
+                    }
+                    continue;
+                }
+                Event::Text(s) | Event::Code(s) => s,
+                Event::SoftBreak => " ".into(),
+                Event::HardBreak => "\n".into(),
+                Event::Rule => {
+                    block_break = true;
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if block_break && !text.is_empty() {
+                push(&mut text, "\n\n", TextFormat::default());
+            }
+            block_break = false;
+            if let Some(marker) = item.take() {
+                push(&mut text, &marker, TextFormat::default());
+            }
+            let format = TextFormat {
+                bold: bold > 0,
+                italic: italic > 0,
+                underline: underline > 0,
+                link: links.last().cloned(),
+            };
+            push(&mut text, &s, format);
+        }
+
+        RichText::from_runs(text, runs)
+    }
+
+    /// Write as Markdown
+    ///
+    /// Line breaks are written as hard breaks (`\` at the end of the line) and
+    /// Markdown syntax is escaped, thus [`RichText::from_markdown`] yields the
+    /// same text and formatting (except in a few corner cases, such as
+    /// emphasis ending in punctuation directly followed by a letter).
+    pub fn to_markdown(&self) -> String {
+        let mut w = Writer {
+            out: String::with_capacity(self.text.len() * 5 / 4),
+            text_end: self.text.trim_end_matches(' ').len(),
+            line_start: true,
+            line_digits: true,
+            link: None,
+            underline: false,
+            bold: false,
+            italic: false,
+        };
+
+        let mut starts: Vec<usize> = self.runs.iter().map(|run| usize::conv(run.start)).collect();
+        if starts.first() != Some(&0) {
+            starts.insert(0, 0);
+        }
+        starts.push(self.text.len());
+        for pair in starts.windows(2) {
+            let range = pair[0]..pair[1];
+            let format = self.format_at(range.start);
+            let link = format.link.as_deref();
+            let s = &self.text[range.clone()];
+
+            // Emphasis must not start after or end before white-space
+            let core_start = range.start + (s.len() - s.trim_start().len());
+            let core_end = range.start + s.trim_end().len();
+            if core_start >= core_end {
+                w.set_format(link, format.underline, false, false);
+                w.write(&self.text, range);
+            } else {
+                w.set_format(link, format.underline, false, false);
+                w.write(&self.text, range.start..core_start);
+                w.set_format(link, format.underline, format.bold, format.italic);
+                w.write(&self.text, core_start..core_end);
+                w.set_format(link, format.underline, false, false);
+                w.write(&self.text, core_end..range.end);
+            }
+        }
+        w.set_format(None, false, false, false);
+        w.out
+    }
+}
+
+struct Writer<'a> {
+    out: String,
+    text_end: usize,
+    line_start: bool,
+    line_digits: bool,
+    link: Option<&'a str>,
+    underline: bool,
+    bold: bool,
+    italic: bool,
+}
+
+impl<'a> Writer<'a> {
+    fn set_format(&mut self, link: Option<&'a str>, underline: bool, bold: bool, italic: bool) {
+        // Markers nest in this order: link, underline, bold, italic.
+        let level = if self.link != link {
+            0
+        } else if self.underline != underline {
+            1
+        } else if self.bold != bold {
+            2
+        } else if self.italic != italic {
+            3
+        } else {
+            return;
+        };
+
+        if self.italic {
+            self.out.push('*');
+            self.italic = false;
+        }
+        if self.bold && level <= 2 {
+            self.out.push_str("**");
+            self.bold = false;
+        }
+        if self.underline && level <= 1 {
+            self.out.push_str("</u>");
+            self.underline = false;
+        }
+        if level == 0 {
+            if let Some(dest) = self.link.take() {
+                self.out.push_str("](");
+                if dest.contains(|c| " ()<>".contains(c)) {
+                    self.out.push('<');
+                    for c in dest.chars() {
+                        if c == '<' || c == '>' || c == '\\' {
+                            self.out.push('\\');
+                        }
+                        self.out.push(c);
+                    }
+                    self.out.push('>');
+                } else {
+                    self.out.push_str(&dest.replace('\\', "\\\\"));
+                }
+                self.out.push(')');
+            }
+            if let Some(link) = link {
+                self.out.push('[');
+                self.link = Some(link);
+            }
+        }
+        if underline && !self.underline {
+            self.out.push_str("<u>");
+            self.underline = true;
+        }
+        if bold && !self.bold {
+            self.out.push_str("**");
+            self.bold = true;
+        }
+        if italic {
+            self.out.push('*');
+            self.italic = true;
+        }
+    }
+
+    fn write(&mut self, text: &str, range: std::ops::Range<usize>) {
+        let base = range.start;
+        for (i, c) in text[range].char_indices() {
+            let at_end = base + i >= self.text_end;
+            match c {
+                '\n' if base + i + 1 == text.len() => self.out.push_str("&#10;"),
+                '\n' => self.out.push_str("\\\n"),
+                ' ' if self.line_start || at_end => self.out.push_str("&#32;"),
+                '\t' if self.line_start => self.out.push_str("&#9;"),
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '&' => {
+                    self.out.push('\\');
+                    self.out.push(c);
+                }
+                '-' | '+' | '=' | '#' | '~' if self.line_start => {
+                    self.out.push('\\');
+                    self.out.push(c);
+                }
+                '.' | ')' if self.line_digits && !self.line_start => {
+                    self.out.push('\\');
+                    self.out.push(c);
+                }
+                c => self.out.push(c),
+            }
+
+            let is_space = c == ' ' || c == '\t';
+            if c == '\n' {
+                self.line_start = true;
+                self.line_digits = true;
+            } else if !(self.line_start && is_space) {
+                self.line_digits = self.line_digits && c.is_ascii_digit();
+                self.line_start = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn markdown_round_trip() {
+        let text =
+            RichText::from_markdown("Some *italic*, **bold** and <u>under</u> [link](x.org)");
+        assert_eq!(&*text, "Some italic, bold and under link");
+        assert!(text.format_at(5).italic);
+        assert!(text.format_at(13).bold);
+        assert!(text.format_at(22).underline);
+        assert_eq!(text.link_at(28), Some("x.org"));
+
+        let text = RichText::from_markdown("-   one\n-   two\n\n3.  three");
+        assert_eq!(&*text, "• one\n\n• two\n\n3. three");
+
+        let mut text = RichText::from("  1. *not* a list\n# nor a heading\n");
+        text.set_format(5..9, |f| f.bold = true);
+        let back = RichText::from_markdown(&text.to_markdown());
+        assert_eq!(back, text);
+    }
+}
@@ -15,6 +15,7 @@
 use std::num::NonZeroU32;
 
 use crate::draw::SizeHandle;
+use crate::geom::Rect;
 use crate::{event, ThemeAction, ThemeApi};
 
 /// Identifier for a window or pop-up
@@ -137,4 +138,14 @@ pub trait ShellWindow {
 
     /// Set the mouse cursor
     fn set_cursor_icon(&mut self, icon: event::CursorIcon);
+
+    /// Set the area of the text cursor used for input method composition
+    ///
+    /// The input method's candidate window should be placed next to (usually
+    /// below) this `rect`, which is in window coordinates.
+    ///
+    /// The default implementation does nothing.
+    fn set_ime_cursor_area(&mut self, rect: Rect) {
+        let _ = rect;
+    }
 }
@@ -6,10 +6,12 @@
 //! Trait impls
 
 use super::*;
+use crate::access::{AccessActions, Role};
 use crate::draw::{DrawHandle, SizeHandle};
 use crate::event::{self, Event, Manager, Response};
 use crate::geom::{Coord, Rect};
 use crate::layout::{AlignHints, AxisInfo, SizeRules};
+use crate::text::RichText;
 use crate::{CoreData, WidgetId};
 
 impl<M: 'static> WidgetCore for Box<dyn Widget<Msg = M>> {
@@ -55,6 +57,12 @@ impl<M: 'static> WidgetChildren for Box<dyn Widget<Msg = M>> {
     fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
         self.as_mut().get_child_mut(index)
     }
+    fn child_name(&self, index: usize) -> Option<&'static str> {
+        self.as_ref().child_name(index)
+    }
+    fn child_key(&self, index: usize) -> Option<String> {
+        self.as_ref().child_key(index)
+    }
 
     fn find_child(&self, id: WidgetId) -> Option<usize> {
         self.as_ref().find_child(id)
@@ -74,6 +82,27 @@ impl<M: 'static> WidgetChildren for Box<dyn Widget<Msg = M>> {
     }
 }
 
+impl<M: 'static> Accessible for Box<dyn Widget<Msg = M>> {
+    fn access_role(&self) -> Role {
+        self.as_ref().access_role()
+    }
+    fn access_label(&self) -> Option<String> {
+        self.as_ref().access_label()
+    }
+    fn access_value(&self) -> Option<String> {
+        self.as_ref().access_value()
+    }
+    fn access_checked(&self) -> Option<bool> {
+        self.as_ref().access_checked()
+    }
+    fn access_actions(&self) -> AccessActions {
+        self.as_ref().access_actions()
+    }
+    fn access_child_hidden(&self, index: usize) -> bool {
+        self.as_ref().access_child_hidden(index)
+    }
+}
+
 impl<M: 'static> WidgetConfig for Box<dyn Widget<Msg = M>> {
     fn configure(&mut self, mgr: &mut Manager) {
         self.as_mut().configure(mgr);
@@ -88,6 +117,15 @@ impl<M: 'static> WidgetConfig for Box<dyn Widget<Msg = M>> {
     fn cursor_icon(&self) -> event::CursorIcon {
         self.as_ref().cursor_icon()
     }
+    fn context_menu(&mut self, mgr: &mut Manager, menu: &mut event::ContextMenu) {
+        self.as_mut().context_menu(mgr, menu);
+    }
+    fn tooltip(&self) -> Option<RichText> {
+        self.as_ref().tooltip()
+    }
+    fn accept_files(&self) -> bool {
+        self.as_ref().accept_files()
+    }
 }
 
 impl<M: 'static> Layout for Box<dyn Widget<Msg = M>> {
@@ -8,11 +8,13 @@
 use std::any::Any;
 use std::fmt;
 
+use crate::access::{AccessActions, Role};
 use crate::draw::{DrawHandle, InputState, SizeHandle};
 use crate::event::{self, ConfigureManager, Manager, ManagerState};
 use crate::geom::{Coord, Offset, Rect};
 use crate::layout::{AlignHints, AxisInfo, SizeRules};
-use crate::{CoreData, TkAction, WidgetId};
+use crate::text::RichText;
+use crate::{CoreData, PathSegment, TkAction, WidgetId, WidgetPath};
 
 impl dyn WidgetCore {
     /// Forwards to the method defined on the type `Any`.
@@ -101,6 +103,40 @@ pub trait WidgetCore: Any + fmt::Debug {
         self
     }
 
+    /// Get the widget's user-assigned name, if any
+    ///
+    /// Names are optional strings used to find widgets (see
+    /// [`WidgetChildren::find_by_name`]), e.g. from tests or scripts. Unlike
+    /// identifiers, names are not required to be unique; searches return the
+    /// first match.
+    ///
+    /// A name may be set at run-time via [`WidgetCore::set_name`] or assigned
+    /// to a child field via `#[widget(name = "...")]` (see
+    /// [`WidgetChildren::child_name`]).
+    #[inline]
+    fn name(&self) -> Option<&str> {
+        self.core_data().name.as_deref()
+    }
+
+    /// Set the widget's name
+    #[inline]
+    fn set_name(&mut self, name: &str) {
+        self.core_data_mut().name = Some(name.to_string());
+    }
+
+    /// Set the widget's name (chaining)
+    ///
+    /// This is identical to [`WidgetCore::set_name`], but can be called in
+    /// chaining fashion.
+    #[inline]
+    fn with_name(mut self, name: &str) -> Self
+    where
+        Self: Sized,
+    {
+        self.core_data_mut().name = Some(name.to_string());
+        self
+    }
+
     /// Get the widget's region, relative to its parent.
     #[inline]
     fn rect(&self) -> Rect {
@@ -195,6 +231,62 @@ pub trait WidgetChildren: WidgetCore {
     /// This method may be removed in the future.
     fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig>;
 
+    /// Get the name assigned to a child by its parent, if any
+    ///
+    /// This is implemented by [`derive(Widget)`] for child fields with a
+    /// `#[widget(name = "...")]` attribute. During configure, this name is
+    /// assigned to children without a [`WidgetCore::name`].
+    ///
+    /// The default implementation returns `None`.
+    ///
+    /// [`derive(Widget)`]: macros/index.html#the-derivewidget-macro
+    #[inline]
+    fn child_name(&self, _index: usize) -> Option<&'static str> {
+        None
+    }
+
+    /// Get a key identifying child `index`, if any
+    ///
+    /// Unlike the index, a key should not change when siblings are inserted
+    /// or removed; it is used by [`WidgetPath`] for unnamed children. For
+    /// example, [`crate::widget::view::ListView`] uses the data key of each
+    /// row. Keys must be unique among children.
+    ///
+    /// The default implementation returns `None`.
+    #[inline]
+    fn child_key(&self, _index: usize) -> Option<String> {
+        None
+    }
+
+    /// Get the [`PathSegment`] identifying child `index`
+    ///
+    /// This is the child's name if no earlier sibling has the same name, else
+    /// its [`WidgetChildren::child_key`], else its index.
+    fn child_segment(&self, index: usize) -> PathSegment {
+        if let Some(name) = self.get_child(index).and_then(|w| w.name()) {
+            let first = (0..index).all(|i| self.get_child(i).and_then(|w| w.name()) != Some(name));
+            if first {
+                return PathSegment::Name(name.to_string());
+            }
+        }
+        match self.child_key(index) {
+            Some(key) => PathSegment::Key(key),
+            None => PathSegment::Index(index),
+        }
+    }
+
+    /// Find the index of the child identified by `segment`, if any
+    fn find_child_segment(&self, segment: &PathSegment) -> Option<usize> {
+        match segment {
+            PathSegment::Index(index) => Some(*index).filter(|i| *i < self.num_children()),
+            PathSegment::Name(name) => (0..self.num_children())
+                .find(|i| self.get_child(*i).and_then(|w| w.name()) == Some(name.as_str())),
+            PathSegment::Key(key) => {
+                (0..self.num_children()).find(|i| self.child_key(*i).as_ref() == Some(key))
+            }
+        }
+    }
+
     /// Check whether `id` is a descendant
     ///
     /// This function assumes that `id` is a valid widget.
@@ -259,6 +351,70 @@ pub trait WidgetChildren: WidgetCore {
         }
     }
 
+    /// Find the path of the widget with this `id`, relative to self
+    ///
+    /// Returns `None` if `id` is neither self nor a descendant. When called on
+    /// the window root, this is the widget's [`WidgetPath`].
+    ///
+    /// This requires that the widget tree has already been configured by
+    /// [`ManagerState::configure`].
+    fn path_of(&self, id: WidgetId) -> Option<WidgetPath> {
+        let mut path = WidgetPath::root();
+        let mut widget = self.as_widget();
+        while let Some(index) = widget.find_child(id) {
+            path.push(widget.child_segment(index));
+            widget = widget.get_child(index).unwrap();
+        }
+        if widget.id() == id {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Find the widget at this `path`, relative to self, if any
+    fn find_path(&self, path: &WidgetPath) -> Option<&dyn WidgetConfig> {
+        let mut widget = self.as_widget();
+        for segment in path.segments() {
+            let index = widget.find_child_segment(segment)?;
+            widget = widget.get_child(index)?;
+        }
+        Some(widget)
+    }
+
+    /// Find the widget at this `path`, relative to self, if any
+    fn find_path_mut(&mut self, path: &WidgetPath) -> Option<&mut dyn WidgetConfig> {
+        let mut widget = self.as_widget_mut();
+        for segment in path.segments() {
+            let index = widget.find_child_segment(segment)?;
+            widget = widget.get_child_mut(index)?;
+        }
+        Some(widget)
+    }
+
+    /// Find the first widget with this `name` (self or a descendant), if any
+    ///
+    /// Widgets are searched depth-first, parents before children.
+    fn find_by_name(&self, name: &str) -> Option<&dyn WidgetConfig> {
+        if self.name() == Some(name) {
+            return Some(self.as_widget());
+        }
+        (0..self.num_children())
+            .filter_map(|i| self.get_child(i))
+            .find_map(|w| w.find_by_name(name))
+    }
+
+    /// Find the first widget with this `name` (self or a descendant), if any
+    ///
+    /// Widgets are searched depth-first, parents before children.
+    fn find_by_name_mut(&mut self, name: &str) -> Option<&mut dyn WidgetConfig> {
+        let mut indices = vec![];
+        if !find_name_indices(self.as_widget(), name, &mut indices) {
+            return None;
+        }
+        self.find_path_mut(&WidgetPath::from_indices(indices))
+    }
+
     /// Walk through all widgets, calling `f` once on each.
     ///
     /// This walk is iterative (nonconcurrent), depth-first, and always calls
@@ -302,6 +458,91 @@ pub trait WidgetChildren: WidgetCore {
     }
 }
 
+// Find the child indices leading to the first widget with this `name`
+fn find_name_indices(widget: &dyn WidgetConfig, name: &str, indices: &mut Vec<usize>) -> bool {
+    if widget.name() == Some(name) {
+        return true;
+    }
+    for index in 0..widget.num_children() {
+        if let Some(child) = widget.get_child(index) {
+            indices.push(index);
+            if find_name_indices(child, name, indices) {
+                return true;
+            }
+            indices.pop();
+        }
+    }
+    false
+}
+
+/// Accessibility
+///
+/// This trait is part of the [`Widget`] family and is derived by
+/// [`derive(Widget)`] unless `#[widget(access = noauto)]` is used, in which
+/// case it must be implemented manually.
+///
+/// This trait describes a widget to assistive technologies such as screen
+/// readers; see the [`crate::access`] module. All methods have default
+/// implementations describing a [`Role::Generic`] widget, which is omitted from
+/// an [`AccessTree`] (its children being reported instead).
+///
+/// State which is tracked elsewhere is not reported via this trait: for
+/// example, the disabled state is read from [`WidgetCore::is_disabled`] and
+/// focus from the event manager.
+///
+/// [`derive(Widget)`]: macros/index.html#the-derivewidget-macro
+/// [`AccessTree`]: crate::access::AccessTree
+pub trait Accessible: WidgetChildren {
+    /// The widget's role
+    ///
+    /// Defaults to [`Role::Generic`].
+    #[inline]
+    fn access_role(&self) -> Role {
+        Role::Generic
+    }
+
+    /// The widget's accessible name, if any
+    ///
+    /// Usually this is the widget's visible label (without accelerator-key
+    /// markup, as given by [`crate::text::AccelString::text`]).
+    #[inline]
+    fn access_label(&self) -> Option<String> {
+        None
+    }
+
+    /// The widget's value, if any
+    ///
+    /// For example, the text of an editable field or the position of a slider.
+    #[inline]
+    fn access_value(&self) -> Option<String> {
+        None
+    }
+
+    /// The checked state, if applicable
+    #[inline]
+    fn access_checked(&self) -> Option<bool> {
+        None
+    }
+
+    /// Actions supported by the widget
+    ///
+    /// [`AccessActions::FOCUS`] need not be included: it is added for widgets
+    /// where [`WidgetConfig::key_nav`] returns true.
+    #[inline]
+    fn access_actions(&self) -> AccessActions {
+        AccessActions::empty()
+    }
+
+    /// Is child `index` hidden?
+    ///
+    /// Hidden children (e.g. closed pop-ups or inactive pages) and their
+    /// descendants are omitted from the accessibility tree. Defaults to false.
+    #[inline]
+    fn access_child_hidden(&self, _index: usize) -> bool {
+        false
+    }
+}
+
 /// Widget configuration
 ///
 /// This trait is part of the [`Widget`] family and is derived by
@@ -317,7 +558,7 @@ pub trait WidgetChildren: WidgetCore {
 //
 // TODO(specialization): provide a blanket implementation, so that users only
 // need implement manually when they have something to configure.
-pub trait WidgetConfig: Layout {
+pub trait WidgetConfig: Layout + Accessible {
     /// Configure widget
     ///
     /// Widgets are *configured* on window creation and when
@@ -340,12 +581,14 @@ pub trait WidgetConfig: Layout {
     ///
     /// In most cases one should not override the default implementation of this
     /// method but instead use [`WidgetConfig::configure`]; the exception is
-    /// widgets with pop-ups.
+    /// widgets with pop-ups. Overrides should configure each child via
+    /// [`ConfigureManager::configure_child`].
     fn configure_recurse<'a, 'b>(&mut self, mut cmgr: ConfigureManager<'a, 'b>) {
         self.record_first_id(cmgr.peek_next());
         for i in 0..self.num_children() {
+            let name = self.child_name(i);
             if let Some(w) = self.get_child_mut(i) {
-                w.configure_recurse(cmgr.child());
+                cmgr.configure_child(w, name);
             }
         }
         self.core_data_mut().id = cmgr.next_id(self.id());
@@ -373,6 +616,40 @@ pub trait WidgetConfig: Layout {
     fn cursor_icon(&self) -> event::CursorIcon {
         event::CursorIcon::Default
     }
+
+    /// Contribute to a context menu
+    ///
+    /// When a context menu is opened for some target widget, this method is
+    /// called on each ancestor of the target (outermost first) and finally on
+    /// the target itself. Entries may be added, enabled, disabled or removed.
+    /// See [`event::ContextMenu`].
+    ///
+    /// The default implementation does nothing.
+    fn context_menu(&mut self, _: &mut Manager, _: &mut event::ContextMenu) {}
+
+    /// Get tooltip text
+    ///
+    /// When a tooltip is shown for some target widget (see
+    /// [`event::Event::OpenTooltip`]), the tooltip of the target is used or,
+    /// if `None`, that of the nearest ancestor. Any widget may be given a
+    /// tooltip via [`crate::widget::WithTooltip`].
+    ///
+    /// The default implementation returns `None`.
+    fn tooltip(&self) -> Option<RichText> {
+        None
+    }
+
+    /// Does this widget accept files dropped from other applications?
+    ///
+    /// If true, this widget receives [`event::Event::FileHover`],
+    /// [`event::Event::FileHoverCancelled`] and [`event::Event::FileDrop`]
+    /// when files are dragged over it or over a descendant which does not
+    /// itself accept files.
+    ///
+    /// Defaults to `false`.
+    fn accept_files(&self) -> bool {
+        false
+    }
 }
 
 /// Positioning and drawing routines for widgets
@@ -510,6 +787,7 @@ pub trait Layout: WidgetChildren {
 /// -   [`WidgetChildren`] — enumerates children and provides methods derived
 ///     from this
 /// -   [`Layout`] — handles sizing and positioning of self and children
+/// -   [`Accessible`] — describes the widget to assistive technologies
 /// -   [`WidgetConfig`] — the last unparametrised trait allows customisation of
 ///     some aspects of widget behaviour
 /// -   [`event::Handler`] — parametrised widgets over a `Msg` type and handles
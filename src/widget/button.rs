@@ -8,6 +8,7 @@
 use std::fmt::{self, Debug};
 use std::rc::Rc;
 
+use kas::access::{AccessActions, Role};
 use kas::draw::TextClass;
 use kas::event::{self, VirtualKeyCode, VirtualKeyCodes};
 use kas::prelude::*;
@@ -15,7 +16,7 @@ use kas::prelude::*;
 /// A push-button with a text label
 #[derive(Clone, Widget)]
 #[handler(handle=noauto)]
-#[widget(config=noauto)]
+#[widget(config=noauto, access=noauto)]
 pub struct TextButton<M: 'static> {
     #[widget_core]
     core: kas::CoreData,
@@ -50,6 +51,18 @@ impl<M: 'static> WidgetConfig for TextButton<M> {
     }
 }
 
+impl<M: 'static> Accessible for TextButton<M> {
+    fn access_role(&self) -> Role {
+        Role::Button
+    }
+    fn access_label(&self) -> Option<String> {
+        Some(self.label.as_str().to_string())
+    }
+    fn access_actions(&self) -> AccessActions {
+        AccessActions::CLICK
+    }
+}
+
 impl<M: 'static> Layout for TextButton<M> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let frame_rules = size_handle.button_surround(axis.is_vertical());
@@ -9,11 +9,13 @@ use std::fmt::{self, Debug};
 use std::rc::Rc;
 
 use super::AccelLabel;
-use kas::{event, prelude::*};
+use kas::access::{AccessActions, Role};
+use kas::event::{self, UndoValue};
+use kas::prelude::*;
 
 /// A bare checkbox (no label)
 #[derive(Clone, Default, Widget)]
-#[widget(config(key_nav = true, hover_highlight = true))]
+#[widget(config(key_nav = true, hover_highlight = true), access=noauto)]
 #[handler(handle=noauto)]
 pub struct CheckBoxBare<M: 'static> {
     #[widget_core]
@@ -32,6 +34,18 @@ impl<M: 'static> Debug for CheckBoxBare<M> {
     }
 }
 
+impl<M: 'static> Accessible for CheckBoxBare<M> {
+    fn access_role(&self) -> Role {
+        Role::CheckBox
+    }
+    fn access_checked(&self) -> Option<bool> {
+        Some(self.get_bool())
+    }
+    fn access_actions(&self) -> AccessActions {
+        AccessActions::CLICK
+    }
+}
+
 impl<M: 'static> Layout for CheckBoxBare<M> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let size = size_handle.checkbox();
@@ -129,8 +143,15 @@ impl<M: 'static> event::Handler for CheckBoxBare<M> {
             Event::Activate => {
                 self.state = !self.state;
                 mgr.redraw(self.id());
+                let set = |w: &mut Self, mgr: &mut Manager, state| {
+                    *mgr |= w.set_bool(state);
+                    w.on_toggle.as_ref().and_then(|f| f(mgr, state))
+                };
+                let op = UndoValue::new(!self.state, self.state, set);
+                mgr.push_undo(self.id(), Box::new(op));
                 Response::update_or_msg(self.on_toggle.as_ref().and_then(|f| f(mgr, self.state)))
             }
+            Event::Undo(undo) => undo.apply(self, mgr),
             _ => Response::Unhandled,
         }
     }
@@ -141,7 +162,7 @@ impl<M: 'static> event::Handler for CheckBoxBare<M> {
 #[derive(Clone, Default, Widget)]
 #[layout(row, area=checkbox)]
 #[handler(msg = M, generics = <> where M: From<VoidMsg>)]
-#[widget(config=noauto)]
+#[widget(config=noauto, access=noauto)]
 pub struct CheckBox<M: 'static> {
     #[widget_core]
     core: CoreData,
@@ -230,6 +251,21 @@ impl<M: 'static> WidgetConfig for CheckBox<M> {
     }
 }
 
+impl<M: 'static> Accessible for CheckBox<M> {
+    fn access_role(&self) -> Role {
+        Role::CheckBox
+    }
+    fn access_label(&self) -> Option<String> {
+        Some(self.label.get_str().to_string())
+    }
+    fn access_checked(&self) -> Option<bool> {
+        Some(self.get_bool())
+    }
+    fn access_actions(&self) -> AccessActions {
+        AccessActions::CLICK
+    }
+}
+
 impl<M: 'static> HasBool for CheckBox<M> {
     #[inline]
     fn get_bool(&self) -> bool {
@@ -9,6 +9,7 @@ use std::fmt::Debug;
 use std::iter::FromIterator;
 
 use super::{Column, MenuEntry, MenuFrame};
+use kas::access::{AccessActions, Role};
 use kas::draw::TextClass;
 use kas::event::{self, Command, GrabMode};
 use kas::prelude::*;
@@ -16,7 +17,7 @@ use kas::WindowId;
 
 /// A pop-up multiple choice menu
 #[derive(Clone, Debug, Widget)]
-#[widget(config(key_nav = true, hover_highlight = true))]
+#[widget(config(key_nav = true, hover_highlight = true), access=noauto)]
 #[handler(noauto)]
 pub struct ComboBox<M: Clone + Debug + 'static> {
     #[widget_core]
@@ -31,6 +32,21 @@ pub struct ComboBox<M: Clone + Debug + 'static> {
     popup_id: Option<WindowId>,
 }
 
+impl<M: Clone + Debug + 'static> Accessible for ComboBox<M> {
+    fn access_role(&self) -> Role {
+        Role::ComboBox
+    }
+    fn access_value(&self) -> Option<String> {
+        Some(self.label.as_str().to_string())
+    }
+    fn access_actions(&self) -> AccessActions {
+        AccessActions::CLICK
+    }
+    fn access_child_hidden(&self, _: usize) -> bool {
+        self.popup_id.is_none()
+    }
+}
+
 impl<M: Clone + Debug + 'static> kas::Layout for ComboBox<M> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let frame_rules = size_handle.button_surround(axis.is_vertical());
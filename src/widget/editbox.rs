@@ -5,37 +5,63 @@
 
 //! Text widgets
 
+use std::any::Any;
+use std::cell::RefCell;
 use std::fmt::{self, Debug};
+use std::marker::PhantomData;
 use std::ops::Range;
 use std::time::Duration;
 use unicode_segmentation::{GraphemeCursor, UnicodeSegmentation};
 
+use kas::access::Role;
 use kas::draw::TextClass;
-use kas::event::{self, Command, GrabMode, PressSource, ScrollDelta};
+use kas::event::{self, Command, GrabMode, PressSource, ScrollDelta, UndoOp};
 use kas::geom::Vec2;
 use kas::macros::*;
 use kas::prelude::*;
-use kas::text::SelectionHelper;
+use kas::text::format::{EditableText, FormattableText};
+use kas::text::{find_all, Effect, EffectFlags, FindOptions};
+use kas::text::{EditHistory, EditKind, EditState, RichText, SelectionHelper};
 
-#[derive(Clone, Debug, PartialEq)]
-enum LastEdit {
+enum EditAction {
     None,
-    Insert,
-    Delete,
-    Paste,
+    Unhandled,
+    Activate,
+    Edit,
 }
 
-impl Default for LastEdit {
-    fn default() -> Self {
-        LastEdit::None
+/// Entry in the window's undo history, referring to an [`EditHistory`] step
+struct EditUndo<G>(PhantomData<G>);
+
+impl<G> Debug for EditUndo<G> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "EditUndo")
     }
 }
 
-enum EditAction {
-    None,
-    Unhandled,
-    Activate,
-    Edit,
+impl<G: EditGuard> UndoOp for EditUndo<G> {
+    fn is_applicable(&self, widget: &dyn Any, redo: bool) -> bool {
+        // The field's history may have been cleared or trimmed
+        match widget.downcast_ref::<EditField<G>>() {
+            Some(edit) if redo => edit.history.can_redo(),
+            Some(edit) => edit.history.can_undo(),
+            None => false,
+        }
+    }
+
+    fn undo(&mut self, widget: &mut dyn Any, mgr: &mut Manager) -> Option<Box<dyn Any>> {
+        let edit = widget.downcast_mut::<EditField<G>>()?;
+        edit.apply_history(mgr, false)
+    }
+
+    fn redo(&mut self, widget: &mut dyn Any, mgr: &mut Manager) -> Option<Box<dyn Any>> {
+        let edit = widget.downcast_mut::<EditField<G>>()?;
+        edit.apply_history(mgr, true)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
 }
 
 /// A *guard* around an [`EditField`]
@@ -54,7 +80,7 @@ enum EditAction {
 /// This trait is implemented for `()` (does nothing; Msg = VoidMsg).
 pub trait EditGuard: Debug + Sized + 'static {
     /// The [`event::Handler::Msg`] type
-    type Msg;
+    type Msg: 'static;
 
     /// Activation guard
     ///
@@ -177,6 +203,36 @@ impl<F: FnMut(&str)> Debug for EditUpdate<F> {
 
 const TOUCH_DUR: Duration = Duration::from_secs(1);
 
+thread_local! {
+    // Formatted copy of the clipboard contents, if copied from a rich-text
+    // EditField; used only while the clipboard text matches.
+    static RICH_CLIPBOARD: RefCell<Option<RichText>> = RefCell::new(None);
+}
+
+fn is_url(s: &str) -> bool {
+    !s.contains(char::is_whitespace) && (s.contains("://") || s.starts_with("mailto:"))
+}
+
+// Merge effect lists (each sorted by start), combining flags
+fn merge_effects(a: &[Effect<()>], b: &[Effect<()>]) -> Vec<Effect<()>> {
+    let flags_at = |effects: &[Effect<()>], start| {
+        (effects.iter().rev())
+            .find(|e| e.start <= start)
+            .map(|e| e.flags)
+            .unwrap_or_default()
+    };
+    let mut starts: Vec<u32> = a.iter().chain(b).map(|e| e.start).collect();
+    starts.sort_unstable();
+    starts.dedup();
+    (starts.into_iter())
+        .map(|start| Effect {
+            start,
+            flags: flags_at(a, start) | flags_at(b, start),
+            aux: (),
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum TouchPhase {
     None,
@@ -317,6 +373,24 @@ impl<G: EditGuard> EditBox<G> {
         self
     }
 
+    /// Set whether this `EditBox` supports formatted text
+    ///
+    /// See [`EditField::rich`].
+    #[inline]
+    pub fn rich(mut self, rich: bool) -> Self {
+        self.inner = self.inner.rich(rich);
+        self
+    }
+
+    /// Set the maximum number of undo steps (inline)
+    ///
+    /// See [`EditField::with_history_depth`].
+    #[inline]
+    pub fn with_history_depth(mut self, depth: usize) -> Self {
+        self.inner = self.inner.with_history_depth(depth);
+        self
+    }
+
     /// Get whether the input state is erroneous
     #[inline]
     pub fn has_error(&self) -> bool {
@@ -404,13 +478,18 @@ impl<G: EditGuard> std::ops::DerefMut for EditBox<G> {
 /// error states. The parent widget is responsible for this.
 ///
 /// This widget is intended for use with short input strings. Internally it
-/// uses a [`String`], for which edits have `O(n)` cost.
+/// uses a [`RichText`] (a [`String`] with optional formatting), for which
+/// edits have `O(n)` cost. Formatting is only editable in [`EditField::rich`]
+/// mode.
 ///
 /// Optionally, [`EditField::multi_line`] mode can be activated (enabling
 /// line-wrapping and a larger vertical height). This mode is only recommended
 /// for short texts for performance reasons.
+///
+/// Input method composition ([`Event::ImePreedit`]) is displayed underlined at
+/// the edit position; it is not part of the edit history until committed.
 #[derive(Clone, Default, Debug, Widget)]
-#[widget(config(key_nav = true, cursor_icon = event::CursorIcon::Text))]
+#[widget(config=noauto, access=noauto)]
 #[handler(handle=noauto, generics = <> where G: EditGuard)]
 pub struct EditField<G: EditGuard = ()> {
     #[widget_core]
@@ -419,18 +498,63 @@ pub struct EditField<G: EditGuard = ()> {
     editable: bool,
     multi_line: bool,
     ideal_height: i32,
-    text: Text<String>,
+    rich: bool,
+    text: Text<RichText>,
     required: Vec2,
     selection: SelectionHelper,
     edit_x_coord: Option<f32>,
-    old_state: Option<(String, usize, usize)>,
-    last_edit: LastEdit,
+    history: EditHistory,
+    find: Option<(String, FindOptions)>,
+    matches: Vec<Range<usize>>,
     error_state: bool,
     touch_phase: TouchPhase,
+    /// Range of input method pre-edit text within `text`
+    preedit: Option<Range<usize>>,
     /// The associated [`EditGuard`] implementation
     pub guard: G,
 }
 
+impl<G: EditGuard> WidgetConfig for EditField<G> {
+    fn key_nav(&self) -> bool {
+        true
+    }
+
+    fn cursor_icon(&self) -> event::CursorIcon {
+        event::CursorIcon::Text
+    }
+
+    fn context_menu(&mut self, mgr: &mut Manager, menu: &mut event::ContextMenu) {
+        if !self.editable {
+            return;
+        }
+        let have_sel = !self.selection.is_empty();
+        menu.push_enabled("&Undo", Command::Undo, mgr.can_undo());
+        menu.push_enabled("&Redo", Command::Redo, mgr.can_redo());
+        menu.push_separator();
+        menu.push_enabled("Cu&t", Command::Cut, have_sel);
+        menu.push_enabled("&Copy", Command::Copy, have_sel);
+        menu.push("&Paste", Command::Paste);
+        menu.push_separator();
+        menu.push("Select &All", Command::SelectAll);
+        if self.rich {
+            menu.push_separator();
+            menu.push_enabled("&Bold", Command::Bold, have_sel);
+            menu.push_enabled("&Italic", Command::Italic, have_sel);
+            menu.push_enabled("U&nderline", Command::Underline, have_sel);
+            menu.push_enabled("Lin&k", Command::Link, have_sel);
+        }
+    }
+}
+
+impl<G: EditGuard> Accessible for EditField<G> {
+    fn access_role(&self) -> Role {
+        Role::TextInput
+    }
+    fn access_value(&self) -> Option<String> {
+        Some(self.text.as_str().to_string())
+    }
+}
+
 impl<G: EditGuard> Layout for EditField<G> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let class = if self.multi_line {
@@ -478,7 +602,40 @@ impl<G: EditGuard> Layout for EditField<G> {
             TextClass::Edit
         };
         let bounds = self.text.env().bounds.into();
-        if self.selection.is_empty() {
+        let mut underlined: Vec<&Range<usize>> =
+            self.matches.iter().chain(self.preedit.iter()).collect();
+        underlined.sort_by_key(|range| range.start);
+        let mut effects = Vec::with_capacity(2 * underlined.len());
+        for m in underlined {
+            effects.push(Effect {
+                start: m.start.cast(),
+                flags: EffectFlags::UNDERLINE,
+                aux: (),
+            });
+            effects.push(Effect {
+                start: m.end.cast(),
+                flags: EffectFlags::empty(),
+                aux: (),
+            });
+        }
+        let rich_effects = self.text.text().effect_tokens();
+        if !rich_effects.is_empty() {
+            effects = match effects.is_empty() {
+                true => rich_effects.to_vec(),
+                false => merge_effects(rich_effects, &effects),
+            };
+        }
+        if !effects.is_empty() {
+            draw_handle.text_offset_effects(
+                self.rect().pos,
+                bounds,
+                self.view_offset,
+                self.text.as_ref(),
+                self.selection.range(),
+                &effects,
+                class,
+            );
+        } else if self.selection.is_empty() {
             draw_handle.text_offset(
                 self.rect().pos,
                 bounds,
@@ -524,14 +681,17 @@ impl EditField<()> {
             editable: true,
             multi_line: false,
             ideal_height: 0,
+            rich: false,
             text: Text::new(Default::default(), text.into()),
             required: Vec2::ZERO,
             selection: SelectionHelper::new(len, len),
             edit_x_coord: None,
-            old_state: None,
-            last_edit: LastEdit::None,
+            history: EditHistory::new(),
+            find: None,
+            matches: vec![],
             error_state: false,
             touch_phase: TouchPhase::None,
+            preedit: None,
             guard: (),
         }
     }
@@ -551,14 +711,17 @@ impl EditField<()> {
             editable: self.editable,
             multi_line: self.multi_line,
             ideal_height: self.ideal_height,
+            rich: self.rich,
             text: self.text,
             required: self.required,
             selection: self.selection,
             edit_x_coord: self.edit_x_coord,
-            old_state: self.old_state,
-            last_edit: self.last_edit,
+            history: self.history,
+            find: self.find,
+            matches: self.matches,
             error_state: self.error_state,
             touch_phase: self.touch_phase,
+            preedit: self.preedit,
             guard,
         };
         let _ = G::update(&mut edit);
@@ -646,6 +809,69 @@ impl<G: EditGuard> EditField<G> {
         self
     }
 
+    /// Set whether this `EditField` supports formatted text (inline)
+    ///
+    /// In rich-text mode, [`Command::Bold`], [`Command::Italic`],
+    /// [`Command::Underline`] and [`Command::Link`] toggle formatting of the
+    /// selection, and copy & paste between rich-text fields preserves
+    /// formatting. [`Command::Link`] links the selection to its own text if
+    /// this is a URL, otherwise to a URL on the clipboard.
+    ///
+    /// See also [`EditField::rich_text`] and [`RichText`] (including Markdown
+    /// conversion).
+    #[inline]
+    pub fn rich(mut self, rich: bool) -> Self {
+        self.rich = rich;
+        self
+    }
+
+    /// Get whether this `EditField` supports formatted text
+    #[inline]
+    pub fn is_rich(&self) -> bool {
+        self.rich
+    }
+
+    /// Access the text with formatting
+    #[inline]
+    pub fn rich_text(&self) -> &RichText {
+        self.text.text()
+    }
+
+    /// Set the text with formatting
+    ///
+    /// Like [`HasString::set_string`], this is a programmatic update.
+    pub fn set_rich_text(&mut self, text: RichText) -> TkAction {
+        let avail = self.core.rect.size;
+        let action = kas::text::util::set_text_and_prepare(&mut self.text, text, avail);
+        self.update_matches();
+        let _ = G::update(self);
+        action
+    }
+
+    /// Set the maximum number of undo steps (inline)
+    ///
+    /// The default is [`EditHistory::DEFAULT_DEPTH`]; zero disables undo.
+    #[inline]
+    pub fn with_history_depth(mut self, depth: usize) -> Self {
+        self.history.set_depth(depth);
+        self
+    }
+
+    /// Access the edit history
+    #[inline]
+    pub fn history(&self) -> &EditHistory {
+        &self.history
+    }
+
+    /// Access the edit history mutably
+    ///
+    /// This may be used to adjust the depth or to clear the history, e.g.
+    /// after a programmatic change of contents.
+    #[inline]
+    pub fn history_mut(&mut self) -> &mut EditHistory {
+        &mut self.history
+    }
+
     /// Get whether the input state is erroneous
     pub fn has_error(&self) -> bool {
         self.error_state
@@ -659,52 +885,239 @@ impl<G: EditGuard> EditField<G> {
         self.error_state = error_state;
     }
 
+    /// Set the search pattern
+    ///
+    /// All matches of `pattern` are highlighted (underlined) until the search
+    /// is cleared; matches are updated on each edit. An empty `pattern` clears
+    /// the search. See also [`EditField::find_next`] and [`FindBar`].
+    ///
+    /// [`FindBar`]: crate::widget::FindBar
+    pub fn set_find(&mut self, pattern: &str, options: FindOptions) -> TkAction {
+        self.find = match pattern.is_empty() {
+            false => Some((pattern.to_string(), options)),
+            true => None,
+        };
+        self.update_matches();
+        TkAction::REDRAW
+    }
+
+    /// Clear the search pattern
+    pub fn clear_find(&mut self) -> TkAction {
+        self.set_find("", Default::default())
+    }
+
+    /// Get the selected text
+    #[inline]
+    pub fn selected_text(&self) -> &str {
+        &self.text.text()[self.selection.range()]
+    }
+
+    /// Get ranges of all matches of the search pattern
+    #[inline]
+    pub fn find_matches(&self) -> &[Range<usize>] {
+        &self.matches
+    }
+
+    /// Select the next match of the search pattern (or previous if `reverse`)
+    ///
+    /// The search starts from the current selection and wraps around.
+    /// Returns false if there are no matches.
+    pub fn find_next(&mut self, mgr: &mut Manager, reverse: bool) -> bool {
+        let sel = self.selection.range();
+        let next = match reverse {
+            false => (self.matches.iter())
+                .find(|m| m.start >= sel.end)
+                .or(self.matches.first()),
+            true => (self.matches.iter().rev())
+                .find(|m| m.start < sel.start)
+                .or(self.matches.last()),
+        };
+        let next = match next {
+            Some(m) => m.clone(),
+            None => return false,
+        };
+
+        self.selection.set_sel_pos(next.start);
+        self.selection.set_edit_pos(next.end);
+        self.edit_x_coord = None;
+        self.history.break_group();
+        self.set_view_offset_from_edit_pos();
+        mgr.redraw(self.id());
+        true
+    }
+
+    /// Replace the selected match of the search pattern, then select the next
+    ///
+    /// If the selection is not a match, this only selects the next match.
+    /// The replacement is recorded in the undo history and reported as a user
+    /// edit: the result is [`Response::Update`] or the message from
+    /// [`EditGuard::edit`], or [`Response::None`] if nothing was replaced.
+    pub fn replace(&mut self, mgr: &mut Manager, replacement: &str) -> Response<G::Msg> {
+        let sel = self.selection.range();
+        if !self.editable || sel.start == sel.end || !self.matches.contains(&sel) {
+            self.find_next(mgr, false);
+            return Response::None;
+        }
+
+        self.record_edit(mgr, EditKind::Other, true);
+        self.text.replace_range(sel.clone(), replacement);
+        self.selection.set_pos(sel.start + replacement.len());
+        self.text.prepare();
+        self.update_matches();
+        if !self.find_next(mgr, false) {
+            self.set_view_offset_from_edit_pos();
+            mgr.redraw(self.id());
+        }
+        Response::update_or_msg(G::edit(self, mgr))
+    }
+
+    /// Replace all matches of the search pattern
+    ///
+    /// All replacements form a single step in the undo history. The result is
+    /// as for [`EditField::replace`].
+    pub fn replace_all(&mut self, mgr: &mut Manager, replacement: &str) -> Response<G::Msg> {
+        if !self.editable || self.matches.is_empty() {
+            return Response::None;
+        }
+
+        self.record_edit(mgr, EditKind::Other, true);
+        let mut text = self.text.text().clone();
+        for m in self.matches.iter().rev() {
+            text.replace_range(m.clone(), replacement);
+        }
+        let removed: usize = self.matches.iter().map(|m| m.end - m.start).sum();
+        let last = self.matches.last().unwrap().end;
+        let pos = last + self.matches.len() * replacement.len() - removed;
+
+        self.text.set_text(text);
+        self.selection.set_pos(pos);
+        self.edit_x_coord = None;
+        self.text.prepare();
+        self.update_matches();
+        self.set_view_offset_from_edit_pos();
+        mgr.redraw(self.id());
+        Response::update_or_msg(G::edit(self, mgr))
+    }
+
+    fn update_matches(&mut self) {
+        self.matches = match self.find {
+            Some((ref pattern, options)) => find_all(self.text.text(), pattern, options),
+            None => vec![],
+        };
+    }
+
     // returns true on success, false on unhandled event
     fn received_char(&mut self, mgr: &mut Manager, c: char) -> bool {
+        let mut buf = [0u8; 4];
+        self.insert_str(mgr, c.encode_utf8(&mut buf))
+    }
+
+    // returns true on success, false on unhandled event
+    fn insert_str(&mut self, mgr: &mut Manager, s: &str) -> bool {
         if !self.editable {
             return false;
         }
+        self.clear_preedit();
 
-        let pos = self.selection.edit_pos();
         let selection = self.selection.range();
         let have_sel = selection.start < selection.end;
-        if self.last_edit != LastEdit::Insert || have_sel {
-            self.old_state = Some((self.text.clone_string(), pos, self.selection.sel_pos()));
-            self.last_edit = LastEdit::Insert;
+        self.record_edit(mgr, EditKind::Insert, have_sel);
+        let _ = self.text.replace_range(selection.clone(), s);
+        self.selection.set_pos(selection.start + s.len());
+        self.edit_x_coord = None;
+        self.text.prepare();
+        self.update_matches();
+        self.set_view_offset_from_edit_pos();
+        self.update_ime_cursor(mgr);
+        mgr.redraw(self.id());
+        true
+    }
+
+    // Remove pre-edit text (if any), returning true if the text changed.
+    // The caller is responsible for preparing the text.
+    fn clear_preedit(&mut self) -> bool {
+        match self.preedit.take() {
+            Some(range) => {
+                let changed = range.start < range.end;
+                let _ = self.text.replace_range(range.clone(), "");
+                self.selection.set_pos(range.start);
+                changed
+            }
+            None => false,
         }
-        if have_sel {
-            let mut buf = [0u8; 4];
-            let s = c.encode_utf8(&mut buf);
-            let _ = self.text.replace_range(selection.clone(), s);
-            self.selection.set_pos(selection.start + s.len());
-        } else {
-            let _ = self.text.insert_char(pos, c);
-            self.selection.set_pos(pos + c.len_utf8());
+    }
+
+    // Replace pre-edit text. This is not recorded in the history since
+    // composition ends with either a commit or removal of pre-edit text.
+    // Returns true on success, false on unhandled event.
+    fn set_preedit(&mut self, mgr: &mut Manager, s: &str, cursor: Option<(usize, usize)>) -> bool {
+        if !self.editable {
+            return false;
+        }
+
+        if self.preedit.is_none() {
+            let selection = self.selection.range();
+            if selection.start < selection.end {
+                // Composition replaces the selection
+                self.record_edit(mgr, EditKind::Delete, true);
+                let _ = self.text.replace_range(selection.clone(), "");
+                self.selection.set_pos(selection.start);
+            }
+        }
+        self.clear_preedit();
+
+        let start = self.selection.edit_pos();
+        if !s.is_empty() {
+            let _ = self.text.replace_range(start..start, s);
+            self.preedit = Some(start..start + s.len());
         }
+        let (sel_pos, edit_pos) = cursor.unwrap_or((s.len(), s.len()));
+        self.selection.set_sel_pos(start + sel_pos.min(s.len()));
+        self.selection.set_edit_pos(start + edit_pos.min(s.len()));
         self.edit_x_coord = None;
         self.text.prepare();
         self.set_view_offset_from_edit_pos();
+        self.update_ime_cursor(mgr);
         mgr.redraw(self.id());
         true
     }
 
+    // Report the caret position to the input method. This is called on gaining
+    // char focus and whenever the caret or view offset may have moved.
+    fn update_ime_cursor(&self, mgr: &mut Manager) {
+        let edit_pos = self.selection.edit_pos();
+        if let Some(marker) = self.text.text_glyph_pos(edit_pos).next_back() {
+            let pos = Offset(
+                marker.pos.0.cast_floor(),
+                (marker.pos.1 - marker.ascent).cast_floor(),
+            );
+            let height: i32 = (marker.ascent - marker.descent).cast_ceil();
+            let rect = Rect::new(self.rect().pos + pos - self.view_offset, Size(1, height));
+            mgr.set_ime_cursor_area(self.id(), rect);
+        }
+    }
+
     fn control_key(&mut self, mgr: &mut Manager, key: Command, mut shift: bool) -> EditAction {
         if !self.editable {
             return EditAction::Unhandled;
         }
+        if self.clear_preedit() {
+            self.text.prepare();
+        }
 
         let mut buf = [0u8; 4];
         let pos = self.selection.edit_pos();
         let selection = self.selection.range();
         let have_sel = selection.end > selection.start;
         let string;
+        let mut fragment = None;
 
         enum Action<'a> {
             None,
             Unhandled,
             Activate,
             Edit,
-            Insert(&'a str, LastEdit),
+            Insert(&'a str, EditKind),
             Delete(Range<usize>),
             Move(usize, Option<f32>),
         }
@@ -721,9 +1134,9 @@ impl<G: EditGuard> EditField<G> {
             }
             Command::Return if shift || !self.multi_line => Action::Activate,
             Command::Return if self.multi_line => {
-                Action::Insert('\n'.encode_utf8(&mut buf), LastEdit::Insert)
+                Action::Insert('\n'.encode_utf8(&mut buf), EditKind::Insert)
             }
-            Command::Tab => Action::Insert('\t'.encode_utf8(&mut buf), LastEdit::Insert),
+            Command::Tab => Action::Insert('\t'.encode_utf8(&mut buf), EditKind::Insert),
             Command::Left => {
                 let mut cursor = GraphemeCursor::new(pos, self.text.str_len(), true);
                 cursor
@@ -883,11 +1296,11 @@ impl<G: EditGuard> EditField<G> {
                 Action::Move(self.text.str_len(), None)
             }
             Command::Cut if have_sel => {
-                mgr.set_clipboard((self.text.text()[selection.clone()]).into());
+                self.copy_range(mgr, selection.clone());
                 Action::Delete(selection.clone())
             }
             Command::Copy if have_sel => {
-                mgr.set_clipboard((self.text.text()[selection.clone()]).into());
+                self.copy_range(mgr, selection.clone());
                 Action::None
             }
             Command::Paste => {
@@ -906,24 +1319,25 @@ impl<G: EditGuard> EditField<G> {
                     }
 
                     string = content;
-                    Action::Insert(&string[0..end], LastEdit::Paste)
+                    if self.rich && end == string.len() {
+                        fragment = RICH_CLIPBOARD.with(|cb| {
+                            (cb.borrow().as_ref())
+                                .filter(|fragment| fragment[..] == string[..])
+                                .cloned()
+                        });
+                    }
+                    Action::Insert(&string[0..end], EditKind::Paste)
                 } else {
                     Action::None
                 }
             }
-            Command::Undo | Command::Redo => {
-                // TODO: maintain full edit history (externally?)
-                if let Some((state, pos2, sel_pos)) = self.old_state.as_mut() {
-                    self.text.swap_string(state);
-                    self.selection.set_edit_pos(*pos2);
-                    *pos2 = pos;
-                    let pos = *sel_pos;
-                    *sel_pos = self.selection.sel_pos();
-                    self.selection.set_sel_pos(pos);
-                    self.edit_x_coord = None;
-                    self.last_edit = LastEdit::None;
+            Command::Bold | Command::Italic | Command::Underline | Command::Link
+                if self.rich && have_sel =>
+            {
+                match self.toggle_format(mgr, key, selection.clone()) {
+                    true => Action::Edit,
+                    false => Action::None,
                 }
-                Action::Edit
             }
             _ => Action::Unhandled,
         };
@@ -934,40 +1348,32 @@ impl<G: EditGuard> EditField<G> {
             Action::Activate => EditAction::Activate,
             Action::Edit => EditAction::Edit,
             Action::Insert(s, edit) => {
-                let mut pos = pos;
-                if have_sel {
-                    self.old_state =
-                        Some((self.text.clone_string(), pos, self.selection.sel_pos()));
-                    self.last_edit = edit;
-
-                    self.text.replace_range(selection.clone(), s);
-                    pos = selection.start;
+                self.record_edit(mgr, edit, have_sel);
+                let range = match have_sel {
+                    true => selection.clone(),
+                    false => pos..pos,
+                };
+                let start = range.start;
+                if let Some(fragment) = fragment {
+                    let mut text = self.text.text().clone();
+                    text.replace_range_rich(range, &fragment);
+                    self.text.set_text(text);
                 } else {
-                    if self.last_edit != edit {
-                        self.old_state =
-                            Some((self.text.clone_string(), pos, self.selection.sel_pos()));
-                        self.last_edit = edit;
-                    }
-
-                    self.text.replace_range(pos..pos, s);
+                    self.text.replace_range(range, s);
                 }
-                self.selection.set_pos(pos + s.len());
+                self.selection.set_pos(start + s.len());
                 self.edit_x_coord = None;
                 EditAction::Edit
             }
             Action::Delete(sel) => {
-                if self.last_edit != LastEdit::Delete {
-                    self.old_state =
-                        Some((self.text.clone_string(), pos, self.selection.sel_pos()));
-                    self.last_edit = LastEdit::Delete;
-                }
-
+                self.record_edit(mgr, EditKind::Delete, have_sel);
                 self.text.replace_range(sel.clone(), "");
                 self.selection.set_pos(sel.start);
                 self.edit_x_coord = None;
                 EditAction::Edit
             }
             Action::Move(pos, x_coord) => {
+                self.history.break_group();
                 self.selection.set_edit_pos(pos);
                 if !shift {
                     self.selection.set_empty();
@@ -984,6 +1390,9 @@ impl<G: EditGuard> EditField<G> {
             set_offset = true;
             mgr.redraw(self.id());
         }
+        if let EditAction::Edit = result {
+            self.update_matches();
+        }
         if set_offset {
             self.set_view_offset_from_edit_pos();
         }
@@ -991,15 +1400,124 @@ impl<G: EditGuard> EditField<G> {
         result
     }
 
+    // Toggle formatting of range; returns true if changed
+    fn toggle_format(&mut self, mgr: &mut Manager, cmd: Command, range: Range<usize>) -> bool {
+        let mut text = self.text.text().clone();
+        match cmd {
+            Command::Bold => {
+                let state = !text.all_in_range(range.clone(), |f| f.bold);
+                text.set_format(range, |f| f.bold = state);
+            }
+            Command::Italic => {
+                let state = !text.all_in_range(range.clone(), |f| f.italic);
+                text.set_format(range, |f| f.italic = state);
+            }
+            Command::Underline => {
+                let state = !text.all_in_range(range.clone(), |f| f.underline);
+                text.set_format(range, |f| f.underline = state);
+            }
+            Command::Link => {
+                let link = if text.all_in_range(range.clone(), |f| f.link.is_some()) {
+                    None
+                } else if is_url(&text[range.clone()]) {
+                    Some(text[range.clone()].to_string())
+                } else {
+                    match mgr.get_clipboard().filter(|s| is_url(s.trim())) {
+                        Some(url) => Some(url.trim().to_string()),
+                        None => return false,
+                    }
+                };
+                text.set_format(range, |f| f.link = link.clone());
+            }
+            _ => return false,
+        }
+
+        self.record_edit(mgr, EditKind::Other, true);
+        self.text.set_text(text);
+        true
+    }
+
+    fn copy_range(&mut self, mgr: &mut Manager, range: Range<usize>) {
+        let text = self.text.text();
+        mgr.set_clipboard((&text[range.clone()]).into());
+        let fragment = match self.rich {
+            true => Some(text.slice(range)),
+            false => None,
+        };
+        RICH_CLIPBOARD.with(|cb| *cb.borrow_mut() = fragment);
+    }
+
+    fn edit_state(&self) -> EditState {
+        EditState {
+            text: self.text.clone_string(),
+            format: self.text.text().runs().to_vec(),
+            edit_pos: self.selection.edit_pos(),
+            sel_pos: self.selection.sel_pos(),
+        }
+    }
+
+    // undo (or redo) one step of history, as an edit: returns the message
+    // from EditGuard::edit, if any
+    fn apply_history(&mut self, mgr: &mut Manager, redo: bool) -> Option<Box<dyn Any>> {
+        let current = self.edit_state();
+        let state = match redo {
+            false => self.history.undo(current),
+            true => self.history.redo(current),
+        };
+        if let Some(state) = state {
+            let text = RichText::from_runs(state.text, state.format);
+            self.text.set_text(text);
+            self.selection.set_edit_pos(state.edit_pos);
+            self.selection.set_sel_pos(state.sel_pos);
+            self.edit_x_coord = None;
+            self.text.prepare();
+            self.update_matches();
+            self.set_view_offset_from_edit_pos();
+            self.update_ime_cursor(mgr);
+            mgr.redraw(self.id());
+            if let Some(msg) = G::edit(self, mgr) {
+                return Some(Box::new(msg));
+            }
+        }
+        None
+    }
+
+    // Undo steps are recorded locally and referenced from the window's history
+    fn record_edit(&mut self, mgr: &mut Manager, kind: EditKind, replaces_selection: bool) {
+        let (text, selection) = (&self.text, &self.selection);
+        let new_step = self.history.record(kind, replaces_selection, || EditState {
+            text: text.clone_string(),
+            format: text.text().runs().to_vec(),
+            edit_pos: selection.edit_pos(),
+            sel_pos: selection.sel_pos(),
+        });
+        if new_step {
+            mgr.push_undo(self.id(), Box::new(EditUndo::<G>(PhantomData)));
+        }
+    }
+
     fn set_edit_pos_from_coord(&mut self, mgr: &mut Manager, coord: Coord) {
         let rel_pos = (coord - self.rect().pos + self.view_offset).into();
         self.selection
             .set_edit_pos(self.text.text_index_nearest(rel_pos));
         self.set_view_offset_from_edit_pos();
+        self.update_ime_cursor(mgr);
         self.edit_x_coord = None;
+        self.history.break_group();
         mgr.redraw(self.id());
     }
 
+    // A touch held in place moves the cursor instead of panning
+    fn touch_cursor_mode(&mut self, mgr: &mut Manager, touch_id: u64, coord: Coord) {
+        if !mgr.modifiers().ctrl() {
+            self.set_edit_pos_from_coord(mgr, coord);
+            if !mgr.modifiers().shift() {
+                self.selection.set_empty();
+            }
+        }
+        self.touch_phase = TouchPhase::Cursor(touch_id);
+    }
+
     fn pan_delta(&mut self, mgr: &mut Manager, delta: Offset) -> bool {
         let bounds = Vec2::from(self.text.env().bounds);
         let max_offset = (self.required - bounds).ceil();
@@ -1007,6 +1525,7 @@ impl<G: EditGuard> EditField<G> {
         let new_offset = (self.view_offset - delta).min(max_offset).max(Offset::ZERO);
         if new_offset != self.view_offset {
             self.view_offset = new_offset;
+            self.update_ime_cursor(mgr);
             mgr.redraw(self.id());
             true
         } else {
@@ -1045,8 +1564,10 @@ impl<G: EditGuard> HasStr for EditField<G> {
 
 impl<G: EditGuard> HasString for EditField<G> {
     fn set_string(&mut self, string: String) -> TkAction {
+        self.preedit = None;
         let avail = self.core.rect.size;
         let action = kas::text::util::set_string_and_prepare(&mut self.text, string, avail);
+        self.update_matches();
         let _ = G::update(self);
         action
     }
@@ -1059,27 +1580,51 @@ impl<G: EditGuard + 'static> event::Handler for EditField<G> {
         match event {
             Event::Activate => {
                 mgr.request_char_focus(self.id());
+                self.update_ime_cursor(mgr);
                 Response::None
             }
-            Event::LostCharFocus => G::focus_lost(self, mgr)
-                .map(|msg| msg.into())
-                .unwrap_or(Response::None),
+            Event::LostCharFocus => {
+                if self.clear_preedit() {
+                    self.text.prepare();
+                    mgr.redraw(self.id());
+                }
+                G::focus_lost(self, mgr)
+                    .map(|msg| msg.into())
+                    .unwrap_or(Response::None)
+            }
             Event::LostSelFocus => {
                 self.selection.set_empty();
                 mgr.redraw(self.id());
                 Response::None
             }
-            Event::Command(cmd, shift) => match self.control_key(mgr, cmd, shift) {
-                EditAction::None => Response::None,
-                EditAction::Unhandled => Response::Unhandled,
-                EditAction::Activate => Response::none_or_msg(G::activate(self, mgr)),
-                EditAction::Edit => Response::update_or_msg(G::edit(self, mgr)),
-            },
+            Event::Command(cmd, shift) => {
+                let action = self.control_key(mgr, cmd, shift);
+                // The edit position or selection may have changed
+                self.update_ime_cursor(mgr);
+                match action {
+                    EditAction::None => Response::None,
+                    EditAction::Unhandled => Response::Unhandled,
+                    EditAction::Activate => Response::none_or_msg(G::activate(self, mgr)),
+                    EditAction::Edit => Response::update_or_msg(G::edit(self, mgr)),
+                }
+            }
             Event::ReceivedCharacter(c) => match self.received_char(mgr, c) {
                 false => Response::Unhandled,
                 true => Response::update_or_msg(G::edit(self, mgr)),
             },
+            Event::Undo(undo) => undo.apply(self, mgr),
+            Event::ImePreedit { text, cursor } => match self.set_preedit(mgr, &text, cursor) {
+                false => Response::Unhandled,
+                true => Response::None,
+            },
+            Event::ImeCommit(text) => match self.insert_str(mgr, &text) {
+                false => Response::Unhandled,
+                true => Response::update_or_msg(G::edit(self, mgr)),
+            },
             Event::PressStart { source, coord, .. } if source.is_primary() => {
+                if self.clear_preedit() {
+                    self.text.prepare();
+                }
                 if let PressSource::Touch(touch_id) = source {
                     if self.touch_phase == TouchPhase::None {
                         self.touch_phase = TouchPhase::Start(touch_id, coord);
@@ -1101,6 +1646,7 @@ impl<G: EditGuard + 'static> event::Handler for EditField<G> {
                 }
                 mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None);
                 mgr.request_char_focus(self.id());
+                self.update_ime_cursor(mgr);
                 Response::None
             }
             Event::PressMove {
@@ -1172,18 +1718,16 @@ impl<G: EditGuard + 'static> event::Handler for EditField<G> {
                     Response::Unhandled
                 }
             }
+            Event::LongPress { source, .. } => match self.touch_phase {
+                TouchPhase::Start(touch_id, coord) if source == PressSource::Touch(touch_id) => {
+                    self.touch_cursor_mode(mgr, touch_id, coord);
+                    Response::None
+                }
+                _ => Response::Unhandled,
+            },
             Event::TimerUpdate => {
-                match self.touch_phase {
-                    TouchPhase::Start(touch_id, coord) => {
-                        if !mgr.modifiers().ctrl() {
-                            self.set_edit_pos_from_coord(mgr, coord);
-                            if !mgr.modifiers().shift() {
-                                self.selection.set_empty();
-                            }
-                        }
-                        self.touch_phase = TouchPhase::Cursor(touch_id);
-                    }
-                    _ => (),
+                if let TouchPhase::Start(touch_id, coord) = self.touch_phase {
+                    self.touch_cursor_mode(mgr, touch_id, coord);
                 }
                 Response::None
             }
@@ -1191,3 +1735,93 @@ impl<G: EditGuard + 'static> event::Handler for EditField<G> {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kas::event::VirtualKeyCode;
+    use kas::testing::Harness;
+
+    #[test]
+    fn ime_cursor_area() {
+        let mut harness = Harness::new(EditBox::new("abc"));
+        let x = |harness: &Harness<EditBox>| harness.shell().ime_cursor_area().unwrap().pos.0;
+        assert_eq!(harness.shell().ime_cursor_area(), None);
+
+        // The area is reported on gaining focus and as the caret moves
+        let rect = harness.widget().rect();
+        harness.click(rect.pos + rect.size / 2);
+        let x0 = x(&harness);
+        harness.key(VirtualKeyCode::Home);
+        let x1 = x(&harness);
+        assert!(x1 < x0);
+        harness.key(VirtualKeyCode::End);
+        let x2 = x(&harness);
+        assert!(x2 > x1);
+        harness.type_str("d");
+        assert!(x(&harness) > x2);
+    }
+
+    // Default shortcuts differ on MacOS
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn rich_editing() {
+        use kas::event::ModifiersState;
+        use kas::widget::Column;
+        use VirtualKeyCode as VK;
+        const CTRL: ModifiersState = ModifiersState::CTRL;
+
+        let boxes = vec![
+            EditBox::new("bold plain").rich(true),
+            EditBox::new("x").rich(true),
+            EditBox::new("y"),
+        ];
+        let mut harness = Harness::new(Column::new(boxes));
+        let focus = |harness: &mut Harness<Column<EditBox>>, index: usize| {
+            let rect = harness.widget()[index].rect();
+            harness.click(rect.pos + rect.size / 2);
+        };
+        let key_with = |harness: &mut Harness<Column<EditBox>>, modifiers, vkey| {
+            harness.set_modifiers(modifiers);
+            harness.key(vkey);
+            harness.set_modifiers(ModifiersState::empty());
+        };
+        let bold = |harness: &Harness<Column<EditBox>>, index: usize, pos: usize| {
+            harness.widget()[index].rich_text().format_at(pos).bold
+        };
+
+        // Ctrl+B toggles bold on the selection
+        focus(&mut harness, 0);
+        harness.key(VK::Home);
+        for _ in 0..4 {
+            key_with(&mut harness, ModifiersState::SHIFT, VK::Right);
+        }
+        key_with(&mut harness, CTRL, VK::B);
+        assert!(bold(&harness, 0, 0) && !bold(&harness, 0, 4));
+        key_with(&mut harness, CTRL, VK::C);
+        assert_eq!(harness.shell().clipboard(), Some("bold"));
+
+        // Pasting into a rich field keeps formatting; a plain field gets none
+        focus(&mut harness, 1);
+        harness.key(VK::End);
+        key_with(&mut harness, CTRL, VK::V);
+        assert_eq!(harness.widget()[1].get_str(), "xbold");
+        assert!(!bold(&harness, 1, 0) && bold(&harness, 1, 1));
+        focus(&mut harness, 2);
+        harness.key(VK::End);
+        key_with(&mut harness, CTRL, VK::V);
+        assert_eq!(harness.widget()[2].get_str(), "ybold");
+        assert!(harness.widget()[2].rich_text().is_plain());
+
+        // Undo and redo restore formatting
+        for _ in 0..3 {
+            key_with(&mut harness, CTRL, VK::Z);
+        }
+        assert_eq!(harness.widget()[2].get_str(), "y");
+        assert_eq!(harness.widget()[1].get_str(), "x");
+        assert!(harness.widget()[0].rich_text().is_plain());
+        key_with(&mut harness, CTRL | ModifiersState::SHIFT, VK::Z);
+        assert_eq!(harness.widget()[0].get_str(), "bold plain");
+        assert!(bold(&harness, 0, 0) && !bold(&harness, 0, 4));
+    }
+}
@@ -0,0 +1,395 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Find / replace bar
+
+use kas::event::{self, Command};
+use kas::prelude::*;
+use kas::text::FindOptions;
+use kas::widget::{CheckBox, EditBox, EditField, EditGuard, TextButton};
+
+#[derive(Clone, Debug, VoidMsg)]
+enum FindMsg {
+    Pattern,
+    CaseSensitive(bool),
+    WholeWord(bool),
+    Prev,
+    Next,
+    Replace,
+    ReplaceAll,
+    Close,
+}
+
+#[derive(Clone, Debug)]
+struct PatternGuard;
+impl EditGuard for PatternGuard {
+    type Msg = FindMsg;
+
+    fn activate(_: &mut EditField<Self>, _: &mut Manager) -> Option<FindMsg> {
+        Some(FindMsg::Next)
+    }
+
+    fn edit(_: &mut EditField<Self>, _: &mut Manager) -> Option<FindMsg> {
+        Some(FindMsg::Pattern)
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ReplaceGuard;
+impl EditGuard for ReplaceGuard {
+    type Msg = FindMsg;
+
+    fn activate(_: &mut EditField<Self>, _: &mut Manager) -> Option<FindMsg> {
+        Some(FindMsg::Replace)
+    }
+}
+
+// Note: labels do not use accelerator keys since these would remain bound
+// while the bar is hidden.
+#[derive(Clone, Debug, Widget)]
+#[layout(row)]
+#[handler(msg = FindMsg)]
+struct Controls {
+    #[widget_core]
+    core: CoreData,
+    #[layout_data]
+    layout_data: <Self as kas::LayoutData>::Data,
+    #[widget]
+    pattern: EditBox<PatternGuard>,
+    #[widget]
+    replacement: EditBox<ReplaceGuard>,
+    #[widget]
+    case_sensitive: CheckBox<FindMsg>,
+    #[widget]
+    whole_word: CheckBox<FindMsg>,
+    #[widget]
+    prev: TextButton<FindMsg>,
+    #[widget]
+    next: TextButton<FindMsg>,
+    #[widget]
+    replace: TextButton<FindMsg>,
+    #[widget]
+    replace_all: TextButton<FindMsg>,
+    #[widget]
+    close: TextButton<FindMsg>,
+}
+
+impl Controls {
+    fn new() -> Self {
+        Controls {
+            core: Default::default(),
+            layout_data: Default::default(),
+            pattern: EditBox::new("").with_guard(PatternGuard),
+            replacement: EditBox::new("").with_guard(ReplaceGuard),
+            case_sensitive: CheckBox::new("Match case")
+                .on_toggle(|_, state| Some(FindMsg::CaseSensitive(state))),
+            whole_word: CheckBox::new("Whole word")
+                .on_toggle(|_, state| Some(FindMsg::WholeWord(state))),
+            prev: TextButton::new_msg("Previous", FindMsg::Prev),
+            next: TextButton::new_msg("Next", FindMsg::Next),
+            replace: TextButton::new_msg("Replace", FindMsg::Replace),
+            replace_all: TextButton::new_msg("Replace all", FindMsg::ReplaceAll),
+            close: TextButton::new_msg("Close", FindMsg::Close),
+        }
+    }
+}
+
+/// An [`EditBox`] with a find / replace bar
+///
+/// The bar is hidden initially and opened by [`Command::Find`] or
+/// [`Command::FindReplace`] while the editor (or the bar) has focus; it is
+/// closed by [`Command::Escape`] or its "Close" button. [`Command::FindNext`]
+/// and [`Command::FindPrev`] select the next / previous match.
+///
+/// While open, all matches are highlighted in the editor (see
+/// [`EditField::set_find`]). Replacements are recorded in the editor's undo
+/// history and reported as user edits (see [`EditGuard::edit`]).
+///
+/// This is intended for use with multi-line editors; the editor is accessible
+/// via the `editor` field.
+#[derive(Clone, Debug, Widget)]
+#[handler(send=noauto, msg = G::Msg)]
+pub struct FindBar<G: EditGuard = ()> {
+    #[widget_core]
+    core: CoreData,
+    /// The editor
+    #[widget]
+    pub editor: EditBox<G>,
+    #[widget]
+    controls: Controls,
+    options: FindOptions,
+    open: bool,
+    bar_height: i32,
+}
+
+impl<G: EditGuard> FindBar<G> {
+    /// Construct around the given `editor`
+    pub fn new(editor: EditBox<G>) -> Self {
+        FindBar {
+            core: Default::default(),
+            editor,
+            controls: Controls::new(),
+            options: Default::default(),
+            open: false,
+            bar_height: 0,
+        }
+    }
+
+    /// True if the find bar is open
+    #[inline]
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Open the find bar
+    ///
+    /// The search pattern is initialised from the editor's selection (if any
+    /// and not multi-line). Keyboard focus is given to the search pattern
+    /// input, or to the replacement input if `replace`.
+    pub fn open(&mut self, mgr: &mut Manager, replace: bool) {
+        let selection = self.editor.selected_text();
+        if !selection.is_empty() && !selection.contains('\n') {
+            let selection = selection.to_string();
+            *mgr |= self.controls.pattern.set_string(selection);
+        }
+        if !self.open {
+            self.open = true;
+            *mgr |= TkAction::RESIZE;
+        }
+        self.update_find(mgr);
+
+        let id = match replace {
+            false => (*self.controls.pattern).id(),
+            true => (*self.controls.replacement).id(),
+        };
+        mgr.request_char_focus(id);
+    }
+
+    /// Close the find bar
+    ///
+    /// This clears match highlighting and returns keyboard focus to the editor.
+    pub fn close(&mut self, mgr: &mut Manager) {
+        if !self.open {
+            return;
+        }
+        self.open = false;
+        *mgr |= self.editor.clear_find() | TkAction::RESIZE;
+        mgr.request_char_focus((*self.editor).id());
+    }
+
+    fn update_find(&mut self, mgr: &mut Manager) {
+        let pattern = self.controls.pattern.get_str();
+        *mgr |= self.editor.set_find(pattern, self.options);
+    }
+
+    fn handle_find(&mut self, mgr: &mut Manager, msg: FindMsg) -> Response<G::Msg> {
+        match msg {
+            FindMsg::Pattern => self.update_find(mgr),
+            FindMsg::CaseSensitive(state) => {
+                self.options.case_sensitive = state;
+                self.update_find(mgr);
+            }
+            FindMsg::WholeWord(state) => {
+                self.options.whole_word = state;
+                self.update_find(mgr);
+            }
+            FindMsg::Prev => {
+                self.editor.find_next(mgr, true);
+            }
+            FindMsg::Next => {
+                self.editor.find_next(mgr, false);
+            }
+            FindMsg::Replace => {
+                let replacement = self.controls.replacement.get_str().to_string();
+                return self.editor.replace(mgr, &replacement);
+            }
+            FindMsg::ReplaceAll => {
+                let replacement = self.controls.replacement.get_str().to_string();
+                return self.editor.replace_all(mgr, &replacement);
+            }
+            FindMsg::Close => self.close(mgr),
+        }
+        Response::None
+    }
+}
+
+impl<G: EditGuard> Layout for FindBar<G> {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let editor_rules = self.editor.size_rules(size_handle, axis);
+        let bar_rules = self.controls.size_rules(size_handle, axis);
+        if axis.is_vertical() {
+            self.bar_height = bar_rules.ideal_size();
+        }
+        match (self.open, axis.is_vertical()) {
+            (false, _) => editor_rules,
+            (true, false) => editor_rules.max(bar_rules),
+            (true, true) => editor_rules.appended(bar_rules),
+        }
+    }
+
+    fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        let mut editor_rect = rect;
+        if self.open {
+            let bar_height = self.bar_height.min(rect.size.1);
+            editor_rect.size.1 -= bar_height;
+            let pos = Coord(rect.pos.0, rect.pos.1 + editor_rect.size.1);
+            let bar_rect = Rect::new(pos, Size::new(rect.size.0, bar_height));
+            self.controls.set_rect(mgr, bar_rect, align);
+        }
+        self.editor.set_rect(mgr, editor_rect, align);
+    }
+
+    fn spatial_range(&self) -> (usize, usize) {
+        // Exclude the bar when closed
+        match self.open {
+            false => (0, 0),
+            true => (0, 1),
+        }
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        let bar = match self.open {
+            false => None,
+            true => self.controls.find_id(coord),
+        };
+        bar.or_else(|| self.editor.find_id(coord))
+            .or(Some(self.id()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        self.editor.draw(draw_handle, mgr, disabled);
+        if self.open {
+            self.controls.draw(draw_handle, mgr, disabled);
+        }
+    }
+}
+
+impl<G: EditGuard> event::SendEvent for FindBar<G> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
+        if self.is_disabled() {
+            return Response::Unhandled;
+        }
+
+        let r = if id <= self.editor.id() {
+            self.editor.send(mgr, id, event.clone())
+        } else if id <= self.controls.id() {
+            match self.controls.send(mgr, id, event.clone()).try_into() {
+                Ok(r) => r,
+                Err(msg) => return self.handle_find(mgr, msg),
+            }
+        } else {
+            debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
+            Manager::handle_generic(self, mgr, event.clone())
+        };
+
+        match r {
+            Response::Unhandled => match event {
+                Event::Command(Command::Find, _) => self.open(mgr, false),
+                Event::Command(Command::FindReplace, _) => self.open(mgr, true),
+                Event::Command(Command::FindNext, _) if self.open => {
+                    self.editor.find_next(mgr, false);
+                }
+                Event::Command(Command::FindPrev, _) if self.open => {
+                    self.editor.find_next(mgr, true);
+                }
+                Event::Command(Command::Escape, _) if self.open => self.close(mgr),
+                _ => return Response::Unhandled,
+            },
+            r => return r,
+        }
+        Response::None
+    }
+}
+
+// Default shortcuts differ on MacOS
+#[cfg(all(test, not(target_os = "macos")))]
+mod test {
+    use super::*;
+    use kas::class::HasStr;
+    use kas::event::{ModifiersState, VirtualKeyCode as VK};
+    use kas::testing::Harness;
+
+    fn key_with(harness: &mut Harness<FindBar>, modifiers: ModifiersState, vkey: VK) {
+        harness.set_modifiers(modifiers);
+        harness.key(vkey);
+        harness.set_modifiers(ModifiersState::empty());
+    }
+
+    fn new_harness(text: &str) -> Harness<FindBar> {
+        let mut harness = Harness::new(FindBar::new(EditBox::new(text).multi_line(true)));
+        let rect = harness.widget().editor.rect();
+        harness.click(rect.pos + rect.size / 2);
+        harness.key(VK::Home);
+        harness
+    }
+
+    #[test]
+    fn find_next_and_replace() {
+        let mut harness = new_harness("one two one three one");
+        for _ in 0..3 {
+            key_with(&mut harness, ModifiersState::SHIFT, VK::Right);
+        }
+        assert_eq!(harness.widget().editor.selected_text(), "one");
+
+        // Ctrl+F opens the bar, taking the pattern from the selection
+        key_with(&mut harness, ModifiersState::CTRL, VK::F);
+        let bar = harness.widget();
+        assert!(bar.is_open());
+        assert_eq!(bar.controls.pattern.get_str(), "one");
+        assert_eq!(bar.editor.find_matches(), &[0..3, 8..11, 18..21]);
+
+        // FindPrev wraps around to the last match; replacing it wraps to the first
+        key_with(&mut harness, ModifiersState::SHIFT, VK::F3);
+        let replace = |harness: &mut Harness<FindBar>| {
+            harness.with_manager(|mgr, bar| {
+                let _ = bar.editor.replace(mgr, "1");
+            });
+        };
+        replace(&mut harness);
+        assert_eq!(harness.widget().editor.get_str(), "one two one three 1");
+        replace(&mut harness);
+        assert_eq!(harness.widget().editor.get_str(), "1 two one three 1");
+        assert_eq!(harness.widget().editor.find_matches(), &[6..9]);
+
+        // FindNext wraps around to the only remaining match
+        harness.key(VK::F3);
+        assert_eq!(harness.widget().editor.selected_text(), "one");
+        replace(&mut harness);
+        assert_eq!(harness.widget().editor.get_str(), "1 two 1 three 1");
+        assert_eq!(harness.widget().editor.find_matches(), &[]);
+    }
+
+    #[test]
+    fn replace_all_and_close() {
+        let mut harness = new_harness("a-b-a-a");
+
+        // With no selection, the pattern is typed
+        key_with(&mut harness, ModifiersState::CTRL, VK::F);
+        harness.type_str("a");
+        assert_eq!(harness.widget().editor.find_matches(), &[0..1, 4..5, 6..7]);
+        key_with(&mut harness, ModifiersState::CTRL, VK::R);
+        harness.type_str("xy");
+        harness.with_manager(|mgr, bar| {
+            let _ = bar.handle_find(mgr, FindMsg::ReplaceAll);
+        });
+        assert_eq!(harness.widget().editor.get_str(), "xy-b-xy-xy");
+
+        // Escape closes the bar, clears highlighting and refocusses the editor
+        harness.key(VK::Escape);
+        let bar = harness.widget();
+        assert!(!bar.is_open());
+        assert_eq!(bar.editor.find_matches(), &[]);
+        assert_eq!(harness.state().char_focus((*bar.editor).id()), (true, true));
+
+        // Replacing all is a single undo step
+        key_with(&mut harness, ModifiersState::CTRL, VK::Z);
+        assert_eq!(harness.widget().editor.get_str(), "a-b-a-a");
+    }
+}
@@ -5,22 +5,164 @@
 
 //! Text widgets
 
+use std::any::Any;
+use std::fmt::{self, Debug};
+use std::ops::Range;
+use std::rc::Rc;
+
+use kas::access::Role;
 use kas::draw::TextClass;
+use kas::event::CursorIcon;
+use kas::geom::Vec2;
+#[cfg(feature = "markdown")]
+use kas::text::format::Markdown;
 use kas::text::format::{EditableText, FormattableText};
+use kas::text::RichText;
 use kas::{event, prelude::*};
 
+/// A link within a [`Label`]
+///
+/// Links are drawn by the parent label; this widget only handles input.
+#[derive(Clone, Default, Debug, Widget)]
+#[handler(handle=noauto)]
+#[widget(config=noauto)]
+struct Link {
+    #[widget_core]
+    core: CoreData,
+    range: Range<usize>,
+    target: String,
+    rects: Vec<Rect>,
+}
+
+impl WidgetConfig for Link {
+    fn key_nav(&self) -> bool {
+        true
+    }
+    fn cursor_icon(&self) -> CursorIcon {
+        CursorIcon::Hand
+    }
+}
+
+impl Layout for Link {
+    fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+        SizeRules::EMPTY
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        match self.rects.iter().any(|rect| rect.contains(coord)) {
+            true => Some(self.id()),
+            false => None,
+        }
+    }
+
+    fn draw(&self, _: &mut dyn DrawHandle, _: &event::ManagerState, _: bool) {}
+}
+
+impl event::Handler for Link {
+    type Msg = String;
+
+    #[inline]
+    fn activation_via_press(&self) -> bool {
+        true
+    }
+
+    fn handle(&mut self, _: &mut Manager, event: Event) -> Response<String> {
+        match event {
+            Event::Activate => Response::Msg(self.target.clone()),
+            _ => Response::Unhandled,
+        }
+    }
+}
+
 /// A text label
 ///
 /// This type is generic over the text type. Some aliases are available:
 /// [`StrLabel`], [`StringLabel`], [`AccelLabel`].
-#[derive(Clone, Default, Debug, Widget)]
-pub struct Label<T: FormattableText + 'static> {
+///
+/// Labels may contain links: for [`RichText`] these are taken from the
+/// formatting; for `Markdown` (with the `markdown` feature) the label must be
+/// constructed via `Label::from_markdown`. Links may be clicked or navigated
+/// to via the Tab key and activated; the mouse cursor changes to a hand over
+/// links. On activation, the link's target is passed to the handler set via
+/// [`Label::on_link`].
+#[derive(Widget)]
+#[widget(children=noauto, access=noauto)]
+#[handler(send=noauto, msg=M)]
+pub struct Label<T: FormattableText + 'static, M: 'static = VoidMsg> {
+    first_id: WidgetId,
     #[widget_core]
     core: CoreData,
     label: Text<T>,
+    links: Vec<Link>,
+    on_link: Option<Rc<dyn Fn(&mut Manager, &str) -> Option<M>>>,
+}
+
+impl<T: FormattableText + Clone + 'static, M: 'static> Clone for Label<T, M> {
+    fn clone(&self) -> Self {
+        Label {
+            first_id: self.first_id,
+            core: self.core.clone(),
+            label: self.label.clone(),
+            links: self.links.clone(),
+            on_link: self.on_link.clone(),
+        }
+    }
 }
 
-impl<T: FormattableText + 'static> Layout for Label<T> {
+impl<T: FormattableText + Default + 'static, M: 'static> Default for Label<T, M> {
+    fn default() -> Self {
+        Label {
+            first_id: Default::default(),
+            core: Default::default(),
+            label: Default::default(),
+            links: vec![],
+            on_link: None,
+        }
+    }
+}
+
+impl<T: FormattableText + Debug + 'static, M: 'static> Debug for Label<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Label {{ core: {:?}, label: {:?}, links: {:?}, ... }}",
+            self.core, self.label, self.links
+        )
+    }
+}
+
+impl<T: FormattableText + 'static, M: 'static> WidgetChildren for Label<T, M> {
+    #[inline]
+    fn first_id(&self) -> WidgetId {
+        self.first_id
+    }
+    fn record_first_id(&mut self, id: WidgetId) {
+        self.first_id = id;
+    }
+    #[inline]
+    fn num_children(&self) -> usize {
+        self.links.len()
+    }
+    #[inline]
+    fn get_child(&self, index: usize) -> Option<&dyn WidgetConfig> {
+        self.links.get(index).map(|w| w.as_widget())
+    }
+    #[inline]
+    fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
+        self.links.get_mut(index).map(|w| w.as_widget_mut())
+    }
+}
+
+impl<T: FormattableText + 'static, M: 'static> Accessible for Label<T, M> {
+    fn access_role(&self) -> Role {
+        Role::Label
+    }
+    fn access_label(&self) -> Option<String> {
+        Some(self.label.as_str().to_string())
+    }
+}
+
+impl<T: FormattableText + 'static, M: 'static> Layout for Label<T, M> {
     #[inline]
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         size_handle.text_bound(&mut self.label, TextClass::Label, axis)
@@ -32,25 +174,48 @@ impl<T: FormattableText + 'static> Layout for Label<T> {
             env.set_bounds(rect.size.into());
             env.set_align(align.unwrap_or(Align::Default, Align::Centre));
         });
+        self.update_link_rects();
+    }
+
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        for link in &self.links {
+            if let Some(id) = link.find_id(coord) {
+                return Some(id);
+            }
+        }
+        Some(self.id())
     }
 
     #[cfg(feature = "min_spec")]
-    default fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
-        draw_handle.text_effects(
-            self.core.rect.pos,
-            Offset::ZERO,
-            &self.label,
-            TextClass::Label,
-        );
+    default fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &ManagerState, _: bool) {
+        self.draw_with_links(draw_handle, mgr);
     }
     #[cfg(not(feature = "min_spec"))]
-    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &ManagerState, _: bool) {
-        draw_handle.text_effects(
-            self.core.rect.pos,
-            Offset::ZERO,
-            &self.label,
-            TextClass::Label,
-        );
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &ManagerState, _: bool) {
+        self.draw_with_links(draw_handle, mgr);
+    }
+}
+impl<T: FormattableText + 'static, M: 'static> event::SendEvent for Label<T, M> {
+    fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<M> {
+        if self.is_disabled() {
+            return Response::Unhandled;
+        }
+
+        for i in 0..self.links.len() {
+            if id <= self.links[i].id() {
+                let r = self.links[i].send(mgr, id, event);
+                return r.try_into().unwrap_or_else(|target: String| {
+                    let msg = self.on_link.as_ref().and_then(|f| f(mgr, &target));
+                    Response::none_or_msg(msg)
+                });
+            }
+        }
+
+        debug_assert!(id == self.id(), "SendEvent::send: bad WidgetId");
+        Manager::handle_generic(self, mgr, event)
     }
 }
 
@@ -100,33 +265,286 @@ impl<T: FormattableText + 'static> Label<T> {
     /// Construct from `label`
     #[inline]
     pub fn new(label: T) -> Self {
-        Label {
+        let mut label = Label {
+            first_id: Default::default(),
             core: Default::default(),
             label: Text::new_multi(label),
+            links: vec![],
+            on_link: None,
+        };
+        label.links = find_links(label.label.text());
+        label
+    }
+
+    /// Set the link handler `f`
+    ///
+    /// On activation of a link, the closure `f` is called with the link's
+    /// target. The message generated by `f`, if any, is returned for handling
+    /// through the parent widget (or other ancestor). What to do with the
+    /// target (e.g. open a URL, navigate a help page) is up to the handler.
+    #[inline]
+    pub fn on_link<M, F>(self, f: F) -> Label<T, M>
+    where
+        F: Fn(&mut Manager, &str) -> Option<M> + 'static,
+    {
+        Label {
+            first_id: self.first_id,
+            core: self.core,
+            label: self.label,
+            links: self.links,
+            on_link: Some(Rc::new(f)),
         }
     }
+}
+
+#[cfg(feature = "markdown")]
+impl Label<Markdown> {
+    /// Construct from Markdown source, with links
+    ///
+    /// Links are replaced by their text before the `input` is parsed by
+    /// [`Markdown::new`]; link positions are taken from the parsed text. Other
+    /// formatting is as supported by [`Markdown`].
+    pub fn from_markdown(input: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let (source, marked, targets) = split_markdown_links(input);
+        let links = locate_links(Markdown::new(&marked)?.as_str(), targets);
+        let mut label = Label::new(Markdown::new(&source)?);
+        label.links = links;
+        Ok(label)
+    }
+}
 
+#[cfg(feature = "markdown")]
+impl<M: 'static> Label<Markdown, M> {
+    /// Set text from Markdown source, with links
+    ///
+    /// See [`Label::from_markdown`]. On error, the label is unchanged.
+    /// Triggers a [reconfigure action](Manager::send_action) if the number of
+    /// links changes.
+    ///
+    /// Note: this must not be called before fonts have been initialised
+    /// (usually done by the theme when the main loop starts).
+    pub fn set_markdown(&mut self, input: &str) -> Result<TkAction, Box<dyn std::error::Error>> {
+        let (source, marked, targets) = split_markdown_links(input);
+        let links = locate_links(Markdown::new(&marked)?.as_str(), targets);
+        let text = Markdown::new(&source)?;
+        let size = self.core.rect.size;
+        let action = kas::text::util::set_text_and_prepare(&mut self.label, text, size);
+        Ok(action | self.set_links(links))
+    }
+}
+
+impl<T: FormattableText + 'static, M: 'static> Label<T, M> {
     /// Set text in an existing `Label`
     ///
+    /// Links are found as in [`Label::new`]. Triggers a
+    /// [reconfigure action](Manager::send_action) if the number of links
+    /// changes.
+    ///
     /// Note: this must not be called before fonts have been initialised
     /// (usually done by the theme when the main loop starts).
     pub fn set_text(&mut self, text: T) -> TkAction {
-        kas::text::util::set_text_and_prepare(&mut self.label, text, self.core.rect.size)
+        let size = self.core.rect.size;
+        let action = kas::text::util::set_text_and_prepare(&mut self.label, text, size);
+        let links = find_links(self.label.text());
+        action | self.set_links(links)
+    }
+
+    fn set_links(&mut self, links: Vec<Link>) -> TkAction {
+        let mut action = TkAction::empty();
+        if links.len() != self.links.len() {
+            action |= TkAction::RECONFIGURE;
+            self.links = links;
+        } else {
+            for (link, new) in self.links.iter_mut().zip(links) {
+                link.range = new.range;
+                link.target = new.target;
+            }
+        }
+        self.update_link_rects();
+        action
+    }
+
+    fn update_link_rects(&mut self) {
+        let pos = self.core.rect.pos;
+        for link in &mut self.links {
+            link.rects.clear();
+            let mut p_min = Coord::MAX;
+            let mut p_max = Coord::MIN;
+            for (p1, p2) in self.label.highlight_lines(link.range.clone()) {
+                let (p1, p2) = (Vec2::from(p1), Vec2::from(p2));
+                let rect = Rect::new(pos + Offset::from(p1), Size::from(p2 - p1));
+                p_min = p_min.min(rect.pos);
+                p_max = p_max.max(rect.pos + rect.size);
+                link.rects.push(rect);
+            }
+            link.core.rect = match link.rects.is_empty() {
+                true => Rect::new(pos, Size::ZERO),
+                false => Rect::new(p_min, Size::from(p_max - p_min)),
+            };
+        }
+    }
+
+    fn draw_with_links(&self, draw_handle: &mut dyn DrawHandle, mgr: &ManagerState) {
+        // The link with navigation focus is drawn as selected
+        let focus = self.links.iter().find(|link| mgr.nav_focus(link.id()));
+        if let Some(link) = focus {
+            draw_handle.text_offset_effects(
+                self.core.rect.pos,
+                self.label.env().bounds.into(),
+                Offset::ZERO,
+                self.label.as_ref(),
+                link.range.clone(),
+                self.label.text().effect_tokens(),
+                TextClass::Label,
+            );
+        } else {
+            draw_handle.text_effects(
+                self.core.rect.pos,
+                Offset::ZERO,
+                &self.label,
+                TextClass::Label,
+            );
+        }
     }
 }
 
-impl<T: FormattableText + 'static> HasStr for Label<T> {
+impl<T: FormattableText + 'static, M: 'static> HasStr for Label<T, M> {
     fn get_str(&self) -> &str {
         self.label.as_str()
     }
 }
 
-impl<T: FormattableText + EditableText + 'static> HasString for Label<T> {
+impl<T: FormattableText + EditableText + 'static, M: 'static> HasString for Label<T, M> {
     fn set_string(&mut self, string: String) -> TkAction {
-        kas::text::util::set_string_and_prepare(&mut self.label, string, self.core.rect.size)
+        let size = self.core.rect.size;
+        let action = kas::text::util::set_string_and_prepare(&mut self.label, string, size);
+        let links = find_links(self.label.text());
+        action | self.set_links(links)
     }
 }
 
+// Find links in text formatting (only supported for RichText); does not
+// assign rects.
+fn find_links<T: FormattableText + 'static>(text: &T) -> Vec<Link> {
+    let text = match (text as &dyn Any).downcast_ref::<RichText>() {
+        Some(text) => text,
+        None => return vec![],
+    };
+
+    let mut links: Vec<Link> = vec![];
+    let runs = text.runs();
+    for (i, run) in runs.iter().enumerate() {
+        let end = runs
+            .get(i + 1)
+            .map(|run| usize::conv(run.start))
+            .unwrap_or(text.len());
+        if let Some(target) = run.format.link.as_ref() {
+            let start = usize::conv(run.start);
+            // Adjacent runs differing only in other formatting
+            if let Some(last) = links.last_mut() {
+                if last.range.end == start && last.target == *target {
+                    last.range.end = end;
+                    continue;
+                }
+            }
+            links.push(Link {
+                core: Default::default(),
+                range: start..end,
+                target: target.clone(),
+                rects: vec![],
+            });
+        }
+    }
+    links
+}
+
+// Markers delimiting the content of links; see split_markdown_links
+#[cfg(feature = "markdown")]
+const LINK_START: char = '\u{E000}';
+#[cfg(feature = "markdown")]
+const LINK_END: char = '\u{E001}';
+
+// Replace each link in Markdown `input` by its content. Returns the new
+// source, the same source with the content of each link enclosed by
+// LINK_START and LINK_END, and the target of each enclosed link, in order.
+#[cfg(feature = "markdown")]
+fn split_markdown_links(input: &str) -> (String, String, Vec<String>) {
+    use pulldown_cmark::{Event, Options, Parser, Tag};
+
+    let mut source = String::with_capacity(input.len());
+    let mut marked = String::with_capacity(input.len());
+    let mut targets = vec![];
+    let mut last = 0;
+    // While within a link: source range of the link and of its content, and
+    // the link target
+    let mut link: Option<(Range<usize>, Option<Range<usize>>, String)> = None;
+
+    let parser = Parser::new_ext(input, Options::ENABLE_STRIKETHROUGH);
+    for (event, range) in parser.into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link(_, dest, _)) => {
+                link = Some((range, None, dest.to_string()));
+            }
+            Event::End(Tag::Link(..)) => {
+                if let Some((outer, inner, target)) = link.take() {
+                    let before = &input[last..outer.start];
+                    source.push_str(before);
+                    marked.push_str(before);
+                    if let Some(inner) = inner {
+                        source.push_str(&input[inner.clone()]);
+                        marked.push(LINK_START);
+                        marked.push_str(&input[inner]);
+                        marked.push(LINK_END);
+                        targets.push(target);
+                    }
+                    last = outer.end;
+                }
+            }
+            _ => {
+                if let Some((_, inner, _)) = link.as_mut() {
+                    *inner = Some(match inner.take() {
+                        Some(r) => r.start.min(range.start)..r.end.max(range.end),
+                        None => range,
+                    });
+                }
+            }
+        }
+    }
+    source.push_str(&input[last..]);
+    marked.push_str(&input[last..]);
+    (source, marked, targets)
+}
+
+// Find links in `rendered`, the text parsed from the marked source of
+// split_markdown_links. Ranges exclude the markers, thus refer to the text
+// parsed from the unmarked source.
+#[cfg(feature = "markdown")]
+fn locate_links(rendered: &str, targets: Vec<String>) -> Vec<Link> {
+    let mut targets = targets.into_iter();
+    let mut result = vec![];
+    let mut start = None;
+    // Length of markers before the current position
+    let mut removed = 0;
+    for (index, c) in rendered.char_indices() {
+        match c {
+            LINK_START => start = Some(index - removed),
+            LINK_END => {
+                if let (Some(start), Some(target)) = (start.take(), targets.next()) {
+                    result.push(Link {
+                        core: Default::default(),
+                        range: start..index - removed,
+                        target,
+                        rects: vec![],
+                    });
+                }
+            }
+            _ => continue,
+        }
+        removed += c.len_utf8();
+    }
+    result
+}
+
 /// Label with `&'static str` as backing type
 ///
 /// Warning: this type does not support [`HasString`]. Assignment is possible
@@ -162,3 +580,94 @@ impl SetAccel for AccelLabel {
         action | kas::text::util::set_text_and_prepare(&mut self.label, string, self.core.rect.size)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kas::event::VirtualKeyCode;
+    use kas::testing::Harness;
+
+    fn label() -> Label<RichText, String> {
+        let mut text = RichText::from("See a.org or b.org.");
+        text.set_format(4..9, |f| f.link = Some("https://a.org".into()));
+        text.set_format(13..18, |f| f.link = Some("https://b.org".into()));
+        Label::new(text).on_link(|_, target| Some(target.to_string()))
+    }
+
+    fn centre(rect: Rect) -> Coord {
+        rect.pos + rect.size / 2
+    }
+
+    #[test]
+    fn link_hit_test() {
+        let mut harness = Harness::new(label());
+        let (a, b) = (
+            harness.widget().links[0].id(),
+            harness.widget().links[1].id(),
+        );
+        let a_rect = harness.widget().links[0].rect();
+        let b_rect = harness.widget().links[1].rect();
+        assert!(a_rect.size.0 > 0 && b_rect.pos.0 > a_rect.pos.0);
+
+        assert_eq!(harness.find_id(centre(a_rect)), Some(a));
+        assert_eq!(harness.find_id(centre(b_rect)), Some(b));
+        let label_id = harness.widget().id();
+        let start = harness.widget().rect().pos;
+        assert_eq!(harness.find_id(start + Offset(1, 1)), Some(label_id));
+
+        harness.click(start + Offset(1, 1));
+        assert_eq!(harness.take_messages(), Vec::<String>::new());
+        harness.click(centre(b_rect));
+        assert_eq!(harness.take_messages(), vec!["https://b.org".to_string()]);
+    }
+
+    #[test]
+    fn link_key_nav() {
+        let mut harness = Harness::new(label());
+        let (a, b) = (
+            harness.widget().links[0].id(),
+            harness.widget().links[1].id(),
+        );
+
+        harness.key(VirtualKeyCode::Tab);
+        assert!(harness.state().nav_focus(a));
+        harness.key(VirtualKeyCode::Tab);
+        assert!(harness.state().nav_focus(b));
+        harness.key(VirtualKeyCode::Return);
+        assert_eq!(harness.take_messages(), vec!["https://b.org".to_string()]);
+
+        // Links are updated with the text
+        let text = RichText::from("no links");
+        let action = harness.widget_mut().set_text(text);
+        assert!(action.contains(TkAction::RECONFIGURE));
+        assert_eq!(harness.widget().num_children(), 0);
+    }
+
+    #[cfg(feature = "markdown")]
+    #[test]
+    fn markdown_links() {
+        let input = "A [*first* link](x.org), <http://y.org>\nand [b\nc](z).";
+        let (source, marked, targets) = split_markdown_links(input);
+        assert_eq!(source, "A *first* link, http://y.org\nand b\nc.");
+        let expected = "A \u{E000}*first* link\u{E001}, \u{E000}http://y.org\u{E001}\nand \u{E000}b\nc\u{E001}.";
+        assert_eq!(marked, expected);
+        assert_eq!(targets, vec!["x.org", "http://y.org", "z"]);
+
+        let rendered =
+            "A \u{E000}first link\u{E001}, \u{E000}http://y.org\u{E001} and \u{E000}b c\u{E001}.";
+        let links = locate_links(rendered, targets);
+        let ranges: Vec<_> = links.iter().map(|link| link.range.clone()).collect();
+        assert_eq!(ranges, vec![2..12, 14..26, 31..34]);
+    }
+
+    #[cfg(feature = "markdown")]
+    #[test]
+    fn markdown_repeated_link_text() {
+        // The link is not the first occurrence of its text
+        let input = "Read the manual, or see [manual](x.org)";
+        let label = Label::from_markdown(input).unwrap();
+        assert_eq!(label.links.len(), 1);
+        assert_eq!(label.links[0].range, 24..30);
+        assert_eq!(&label.label.as_str()[24..30], "manual");
+    }
+}
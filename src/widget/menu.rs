@@ -7,16 +7,19 @@
 
 use std::ops::{Deref, DerefMut};
 
+mod context_menu;
 mod menu_entry;
 mod menu_frame;
 mod menubar;
 mod submenu;
 
+pub(crate) use context_menu::ContextMenuPopup;
 pub use menu_entry::{MenuEntry, MenuToggle};
 pub use menu_frame::MenuFrame;
 pub use menubar::MenuBar;
 pub use submenu::SubMenu;
 
+use kas::access::{AccessActions, Role};
 use kas::{event, prelude::*};
 
 /// Trait governing menus, sub-menus and menu-entries
@@ -81,6 +84,12 @@ impl<M: 'static> WidgetChildren for Box<dyn Menu<Msg = M>> {
     fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn WidgetConfig> {
         self.as_mut().get_child_mut(index)
     }
+    fn child_name(&self, index: usize) -> Option<&'static str> {
+        self.as_ref().child_name(index)
+    }
+    fn child_key(&self, index: usize) -> Option<String> {
+        self.as_ref().child_key(index)
+    }
 
     fn find_child(&self, id: WidgetId) -> Option<usize> {
         self.as_ref().find_child(id)
@@ -100,6 +109,27 @@ impl<M: 'static> WidgetChildren for Box<dyn Menu<Msg = M>> {
     }
 }
 
+impl<M: 'static> Accessible for Box<dyn Menu<Msg = M>> {
+    fn access_role(&self) -> Role {
+        self.as_ref().access_role()
+    }
+    fn access_label(&self) -> Option<String> {
+        self.as_ref().access_label()
+    }
+    fn access_value(&self) -> Option<String> {
+        self.as_ref().access_value()
+    }
+    fn access_checked(&self) -> Option<bool> {
+        self.as_ref().access_checked()
+    }
+    fn access_actions(&self) -> AccessActions {
+        self.as_ref().access_actions()
+    }
+    fn access_child_hidden(&self, index: usize) -> bool {
+        self.as_ref().access_child_hidden(index)
+    }
+}
+
 impl<M: 'static> WidgetConfig for Box<dyn Menu<Msg = M>> {
     fn configure(&mut self, mgr: &mut Manager) {
         self.as_mut().configure(mgr);
@@ -114,6 +144,15 @@ impl<M: 'static> WidgetConfig for Box<dyn Menu<Msg = M>> {
     fn cursor_icon(&self) -> event::CursorIcon {
         self.as_ref().cursor_icon()
     }
+    fn context_menu(&mut self, mgr: &mut Manager, menu: &mut event::ContextMenu) {
+        self.as_mut().context_menu(mgr, menu);
+    }
+    fn tooltip(&self) -> Option<kas::text::RichText> {
+        self.as_ref().tooltip()
+    }
+    fn accept_files(&self) -> bool {
+        self.as_ref().accept_files()
+    }
 }
 
 impl<M: 'static> Layout for Box<dyn Menu<Msg = M>> {
@@ -0,0 +1,336 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Context menu pop-up
+
+use kas::access::Role;
+use kas::draw::{InputState, TextClass};
+use kas::event::{self, Command, ContextMenu, ContextMenuEntry, GrabMode};
+use kas::prelude::*;
+use kas::WindowId;
+
+#[derive(Clone, Debug)]
+enum Row {
+    Entry {
+        label: Text<AccelString>,
+        shortcut: Text<String>,
+        command: Command,
+        enabled: bool,
+    },
+    Separator,
+}
+
+/// Pop-up displaying a [`ContextMenu`]
+///
+/// This widget is owned by [`kas::widget::Window`] and is its own pop-up
+/// parent. On selection the pop-up closes and the entry's command is emitted.
+///
+/// Labels are displayed without accelerator-key indicators since accelerator
+/// keys are not bound in context menus.
+#[derive(Clone, Debug, Default, Widget)]
+#[widget(config(key_nav = true), access=noauto)]
+#[handler(handle=noauto)]
+pub(crate) struct ContextMenuPopup {
+    #[widget_core]
+    core: CoreData,
+    rows: Vec<Row>,
+    rects: Vec<Rect>,
+    vert_rules: Vec<SizeRules>,
+    offset: Offset,
+    size: Size,
+    label_off: Offset,
+    frame_size: Size,
+    cursor: Option<usize>,
+    anchor: Rect,
+    target: Option<WidgetId>,
+    popup_id: Option<WindowId>,
+}
+
+impl ContextMenuPopup {
+    /// The target widget of the last menu opened
+    pub(crate) fn target(&self) -> Option<WidgetId> {
+        self.target
+    }
+
+    /// The rect the menu is placed against
+    pub(crate) fn anchor(&self) -> Rect {
+        self.anchor
+    }
+
+    /// Open as a pop-up, replacing any previous contents
+    ///
+    /// Does nothing if `menu` is empty.
+    pub(crate) fn open(&mut self, mgr: &mut Manager, menu: &ContextMenu, anchor: Rect) {
+        self.close(mgr);
+        if menu.is_empty() {
+            return;
+        }
+
+        let config = mgr.config();
+        self.rows = (menu.entries().iter())
+            .map(|entry| match entry {
+                ContextMenuEntry::Command {
+                    label,
+                    command,
+                    enabled,
+                } => {
+                    let shortcut = config.shortcuts.describe_command(*command);
+                    Row::Entry {
+                        label: Text::new_single(label.clone()),
+                        shortcut: Text::new_single(shortcut.unwrap_or_default()),
+                        command: *command,
+                        enabled: *enabled,
+                    }
+                }
+                ContextMenuEntry::Separator => Row::Separator,
+            })
+            .collect();
+        drop(config);
+
+        self.rects.clear();
+        self.cursor = None;
+        self.anchor = anchor;
+        self.target = Some(menu.target());
+        let id = mgr.add_popup(kas::Popup {
+            id: self.id(),
+            parent: self.id(),
+            direction: Direction::Down,
+        });
+        self.popup_id = Some(id);
+        mgr.set_nav_focus(self.id());
+    }
+
+    /// Close the pop-up, if open
+    pub(crate) fn close(&mut self, mgr: &mut Manager) {
+        if let Some(id) = self.popup_id {
+            mgr.close_window(id);
+        }
+    }
+
+    fn row_at(&self, coord: Coord) -> Option<usize> {
+        (self.rects.iter())
+            .position(|rect| rect.contains(coord))
+            .filter(|index| self.is_enabled(*index))
+    }
+
+    fn is_enabled(&self, index: usize) -> bool {
+        match self.rows.get(index) {
+            Some(Row::Entry { enabled, .. }) => *enabled,
+            _ => false,
+        }
+    }
+
+    fn set_cursor(&mut self, mgr: &mut Manager, cursor: Option<usize>) {
+        if self.cursor != cursor {
+            self.cursor = cursor;
+            mgr.redraw(self.id());
+        }
+    }
+
+    /// Move the cursor to the next enabled entry after `start` (or before)
+    fn next_cursor(&self, start: Option<usize>, reverse: bool) -> Option<usize> {
+        let len = self.rows.len();
+        let mut index = start;
+        for _ in 0..len {
+            let next = match (index, reverse) {
+                (None, false) => 0,
+                (None, true) => len - 1,
+                (Some(i), false) => (i + 1) % len,
+                (Some(i), true) => (i + len - 1) % len,
+            };
+            if self.is_enabled(next) {
+                return Some(next);
+            }
+            index = Some(next);
+        }
+        None
+    }
+
+    fn select(&mut self, mgr: &mut Manager, index: Option<usize>) -> Response<Command> {
+        match index.and_then(|i| self.rows.get(i)) {
+            Some(Row::Entry {
+                command,
+                enabled: true,
+                ..
+            }) => {
+                let command = *command;
+                self.close(mgr);
+                Response::Msg(command)
+            }
+            _ => Response::None,
+        }
+    }
+}
+
+impl Accessible for ContextMenuPopup {
+    fn access_role(&self) -> Role {
+        Role::Menu
+    }
+}
+
+impl Layout for ContextMenuPopup {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let entry_frame = size_handle.menu_frame(axis.is_vertical());
+        let separator = SizeRules::extract_fixed(axis, size_handle.separator(), Default::default());
+
+        if axis.is_vertical() {
+            self.vert_rules.clear();
+        }
+        let mut rules = SizeRules::EMPTY;
+        for row in &mut self.rows {
+            let row_rules = match row {
+                Row::Entry {
+                    label, shortcut, ..
+                } => {
+                    let label_rules = size_handle.text_bound(label, TextClass::LabelFixed, axis);
+                    let shortcut_rules =
+                        size_handle.text_bound(shortcut, TextClass::LabelFixed, axis);
+                    let text_rules = match axis.is_vertical() {
+                        false => label_rules.appended(shortcut_rules),
+                        true => label_rules.max(shortcut_rules),
+                    };
+                    let (row_rules, offset, size) = entry_frame.surround(text_rules);
+                    self.label_off.set_component(axis, offset);
+                    self.frame_size.set_component(axis, size);
+                    row_rules
+                }
+                Row::Separator => separator,
+            };
+            if axis.is_vertical() {
+                self.vert_rules.push(row_rules);
+                rules.append(row_rules);
+            } else {
+                rules = rules.max(row_rules);
+            }
+        }
+
+        let frame_rules = size_handle.frame(axis.is_vertical());
+        let (rules, offset, size) = frame_rules.surround(rules);
+        self.offset.set_component(axis, offset);
+        self.size.set_component(axis, size);
+        rules
+    }
+
+    fn set_rect(&mut self, _: &mut Manager, rect: Rect, _: AlignHints) {
+        self.core.rect = rect;
+        let pos = rect.pos + self.offset;
+        let size = rect.size - self.size;
+
+        let mut heights = vec![0; self.vert_rules.len()];
+        SizeRules::solve_seq(&mut heights, &self.vert_rules, size.1);
+
+        self.rects.clear();
+        let mut y = pos.1;
+        for (row, h) in self.rows.iter_mut().zip(heights) {
+            let row_rect = Rect::new(Coord(pos.0, y), Size::new(size.0, h));
+            if let Row::Entry {
+                label, shortcut, ..
+            } = row
+            {
+                let bounds = (row_rect.size - self.frame_size).into();
+                label.update_env(|env| {
+                    env.set_bounds(bounds);
+                    env.set_align((Align::Default, Align::Centre));
+                });
+                shortcut.update_env(|env| {
+                    env.set_bounds(bounds);
+                    env.set_align((Align::BR, Align::Centre));
+                });
+            }
+            self.rects.push(row_rect);
+            y += h;
+        }
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        draw_handle.menu_frame(self.core.rect);
+        let disabled = disabled || self.is_disabled();
+        let nav_focus = mgr.nav_focus(self.id());
+        for (i, (row, rect)) in self.rows.iter().zip(self.rects.iter()).enumerate() {
+            match row {
+                Row::Entry {
+                    label,
+                    shortcut,
+                    enabled,
+                    ..
+                } => {
+                    let active = self.cursor == Some(i);
+                    let state = InputState {
+                        disabled: disabled || !enabled,
+                        hover: active,
+                        nav_focus: nav_focus && active,
+                        ..Default::default()
+                    };
+                    draw_handle.menu_entry(*rect, state);
+                    let pos = rect.pos + self.label_off;
+                    draw_handle.text_accel(pos, label, false, TextClass::Label);
+                    draw_handle.text(pos, shortcut, TextClass::Label);
+                }
+                Row::Separator => draw_handle.separator(*rect),
+            }
+        }
+    }
+}
+
+impl event::Handler for ContextMenuPopup {
+    type Msg = Command;
+
+    fn handle(&mut self, mgr: &mut Manager, event: Event) -> Response<Command> {
+        match event {
+            Event::Activate => return self.select(mgr, self.cursor),
+            Event::Command(cmd, _) => {
+                let cursor = match cmd {
+                    Command::Up => self.next_cursor(self.cursor, true),
+                    Command::Down => self.next_cursor(self.cursor, false),
+                    Command::Home => self.next_cursor(None, false),
+                    Command::End => self.next_cursor(None, true),
+                    _ => return Response::Unhandled,
+                };
+                self.set_cursor(mgr, cursor);
+            }
+            Event::PressStart {
+                source,
+                start_id,
+                coord,
+            } => {
+                // As pop-up parent we receive all presses while open;
+                // those elsewhere close the menu.
+                if start_id != self.id() {
+                    return Response::Unhandled;
+                }
+                let cursor = self.row_at(coord);
+                self.set_cursor(mgr, cursor);
+                mgr.request_grab(self.id(), source, coord, GrabMode::Grab, None);
+            }
+            Event::PressMove { cur_id, coord, .. } => {
+                let cursor = match cur_id == Some(self.id()) {
+                    true => self.row_at(coord),
+                    false => None,
+                };
+                self.set_cursor(mgr, cursor);
+            }
+            Event::PressEnd { end_id, coord, .. } => {
+                if end_id == Some(self.id()) {
+                    let index = self.row_at(coord);
+                    return self.select(mgr, index);
+                }
+            }
+            Event::NewPopup(id) => {
+                if id != self.id() {
+                    self.close(mgr);
+                }
+            }
+            Event::PopupRemoved(id) => {
+                debug_assert_eq!(Some(id), self.popup_id);
+                self.popup_id = None;
+                self.rows.clear();
+                self.rects.clear();
+                self.cursor = None;
+            }
+            _ => return Response::Unhandled,
+        }
+        Response::None
+    }
+}
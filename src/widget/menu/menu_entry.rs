@@ -8,6 +8,7 @@
 use std::fmt::{self, Debug};
 
 use super::Menu;
+use kas::access::{AccessActions, Role};
 use kas::dir::Right;
 use kas::draw::TextClass;
 use kas::event;
@@ -17,7 +18,7 @@ use kas::widget::{AccelLabel, CheckBoxBare};
 
 /// A standard menu entry
 #[derive(Clone, Debug, Default, Widget)]
-#[widget(config=noauto)]
+#[widget(config=noauto, access=noauto)]
 #[handler(handle=noauto)]
 pub struct MenuEntry<M: Clone + Debug + 'static> {
     #[widget_core]
@@ -41,6 +42,18 @@ impl<M: Clone + Debug + 'static> WidgetConfig for MenuEntry<M> {
     }
 }
 
+impl<M: Clone + Debug + 'static> Accessible for MenuEntry<M> {
+    fn access_role(&self) -> Role {
+        Role::MenuItem
+    }
+    fn access_label(&self) -> Option<String> {
+        Some(self.label.as_str().to_string())
+    }
+    fn access_actions(&self) -> AccessActions {
+        AccessActions::CLICK
+    }
+}
+
 impl<M: Clone + Debug + 'static> Layout for MenuEntry<M> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let frame_rules = size_handle.menu_frame(axis.is_vertical());
@@ -122,7 +135,7 @@ impl<M: Clone + Debug> Menu for MenuEntry<M> {}
 /// A menu entry which can be toggled
 #[derive(Clone, Default, Widget)]
 #[handler(msg = M, generics = <> where M: From<VoidMsg>)]
-#[widget(config=noauto)]
+#[widget(config=noauto, access=noauto)]
 pub struct MenuToggle<M: 'static> {
     #[widget_core]
     core: CoreData,
@@ -202,6 +215,21 @@ impl<M: 'static> WidgetConfig for MenuToggle<M> {
     }
 }
 
+impl<M: 'static> Accessible for MenuToggle<M> {
+    fn access_role(&self) -> Role {
+        Role::MenuItemCheckBox
+    }
+    fn access_label(&self) -> Option<String> {
+        Some(self.label.get_str().to_string())
+    }
+    fn access_checked(&self) -> Option<bool> {
+        Some(self.checkbox.get_bool())
+    }
+    fn access_actions(&self) -> AccessActions {
+        AccessActions::CLICK
+    }
+}
+
 impl<M: 'static> Layout for MenuToggle<M> {
     // NOTE: This code is mostly copied from the macro expansion.
     // Only draw() is significantly different.
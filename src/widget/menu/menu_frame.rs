@@ -5,11 +5,13 @@
 
 //! Menus
 
+use kas::access::Role;
 use kas::{event, prelude::*};
 
 /// A frame around content, plus background
 #[derive(Clone, Debug, Default, Widget)]
 #[handler(msg = <W as Handler>::Msg)]
+#[widget(access=noauto)]
 pub struct MenuFrame<W: Widget> {
     #[widget_core]
     core: CoreData,
@@ -32,6 +34,12 @@ impl<W: Widget> MenuFrame<W> {
     }
 }
 
+impl<W: Widget> Accessible for MenuFrame<W> {
+    fn access_role(&self) -> Role {
+        Role::Menu
+    }
+}
+
 impl<W: Widget> Layout for MenuFrame<W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let frame_rules = size_handle.frame(axis.is_vertical());
@@ -8,6 +8,7 @@
 use std::time::Duration;
 
 use super::{Menu, SubMenu};
+use kas::access::Role;
 use kas::event::{self, Command, GrabMode};
 use kas::prelude::*;
 use kas::widget::List;
@@ -20,6 +21,7 @@ const DELAY: Duration = Duration::from_millis(200);
 /// menus.
 #[derive(Clone, Debug, Widget)]
 #[handler(noauto)]
+#[widget(access=noauto)]
 pub struct MenuBar<D: Directional, W: Menu> {
     #[widget_core]
     core: CoreData,
@@ -50,6 +52,12 @@ impl<D: Directional, W: Menu> MenuBar<D, W> {
 }
 
 // NOTE: we could use layout(single) except for alignment
+impl<D: Directional, W: Menu> Accessible for MenuBar<D, W> {
+    fn access_role(&self) -> Role {
+        Role::MenuBar
+    }
+}
+
 impl<D: Directional, W: Menu> Layout for MenuBar<D, W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         self.bar.size_rules(size_handle, axis)
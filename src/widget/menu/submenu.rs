@@ -6,6 +6,7 @@
 //! Sub-menu
 
 use super::{Menu, MenuFrame};
+use kas::access::{AccessActions, Role};
 use kas::draw::TextClass;
 use kas::event::{self, Command, ConfigureManager};
 use kas::prelude::*;
@@ -14,7 +15,7 @@ use kas::WindowId;
 
 /// A sub-menu
 #[derive(Clone, Debug, Widget)]
-#[widget(config=noauto)]
+#[widget(config=noauto, access=noauto)]
 #[handler(noauto)]
 pub struct SubMenu<D: Directional, W: Menu> {
     #[widget_core]
@@ -91,7 +92,8 @@ impl<D: Directional, W: Menu> SubMenu<D, W> {
 impl<D: Directional, W: Menu> WidgetConfig for SubMenu<D, W> {
     fn configure_recurse<'a, 'b>(&mut self, mut cmgr: ConfigureManager<'a, 'b>) {
         cmgr.mgr().push_accel_layer(true);
-        self.list.configure_recurse(cmgr.child());
+        let name = self.child_name(0);
+        cmgr.configure_child(&mut self.list, name);
         self.core_data_mut().id = cmgr.next_id(self.id());
         let mgr = cmgr.mgr();
         mgr.pop_accel_layer(self.id());
@@ -106,6 +108,21 @@ impl<D: Directional, W: Menu> WidgetConfig for SubMenu<D, W> {
     }
 }
 
+impl<D: Directional, W: Menu> Accessible for SubMenu<D, W> {
+    fn access_role(&self) -> Role {
+        Role::MenuItem
+    }
+    fn access_label(&self) -> Option<String> {
+        Some(self.label.as_str().to_string())
+    }
+    fn access_actions(&self) -> AccessActions {
+        AccessActions::CLICK
+    }
+    fn access_child_hidden(&self, _: usize) -> bool {
+        self.popup_id.is_none()
+    }
+}
+
 impl<D: Directional, W: Menu> kas::Layout for SubMenu<D, W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let frame_rules = size_handle.menu_frame(axis.is_vertical());
@@ -294,3 +311,34 @@ impl<D: Directional, W: Menu> SetAccel for SubMenu<D, W> {
         action | kas::text::util::set_text_and_prepare(&mut self.label, string, avail)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::testing::Harness;
+    use crate::widget::Label;
+
+    #[derive(Clone, Debug, Widget)]
+    #[layout(single)]
+    struct Item {
+        #[widget_core]
+        core: CoreData,
+        #[widget(name = "label")]
+        label: Label<&'static str>,
+    }
+
+    impl Menu for Item {}
+
+    #[test]
+    fn child_names() {
+        let item = Item {
+            core: Default::default(),
+            label: Label::new("item"),
+        };
+        let mut harness = Harness::new(SubMenu::right("Menu", vec![item]));
+        let id = harness.widget().list[0].label.id();
+        assert_eq!(harness.id_of_name("label"), Some(id));
+        let label = harness.widget_mut().find_by_name_mut("label");
+        assert_eq!(label.map(|w| w.id()), Some(id));
+    }
+}
@@ -36,6 +36,7 @@
 //! -   [`CheckBox`]: a checkable box
 //! -   [`RadioBox`]: a checkable box bound to a group
 //! -   [`EditBox`]: a text-editing box
+//! -   [`FindBar`]: an [`EditBox`] with a find / replace bar
 //! -   [`ScrollBar`]: a scrollbar
 //! -   [`Slider`]: a slider
 //!
@@ -43,7 +44,7 @@
 //!
 //! -   [`Filler`]: an empty widget, sometimes used to fill space
 //! -   [`Separator`]: a visible bar to separate things
-//! -   [`Label`]: a simple text label
+//! -   [`Label`]: a simple text label, optionally with links
 //!
 //! ## Components
 //!
@@ -51,6 +52,7 @@
 //! -   [`CheckBoxBare`]: `CheckBox` without its label
 //! -   [`RadioBoxBare`]: `RadioBox` without its label
 //! -   [`DragHandle`]: a handle (e.g. for a slider, splitter or scrollbar)
+//! -   [`WithTooltip`]: adds a tooltip to any widget
 
 mod button;
 mod checkbox;
@@ -59,6 +61,7 @@ mod dialog;
 mod drag;
 mod editbox;
 mod filler;
+mod find_bar;
 mod frame;
 mod label;
 mod list;
@@ -72,6 +75,7 @@ mod separator;
 mod slider;
 mod splitter;
 mod stack;
+mod tooltip;
 mod window;
 
 pub mod view;
@@ -83,6 +87,7 @@ pub use dialog::MessageBox;
 pub use drag::DragHandle;
 pub use editbox::{EditBox, EditField, EditGuard};
 pub use filler::Filler;
+pub use find_bar::FindBar;
 pub use frame::Frame;
 pub use label::{AccelLabel, Label, StrLabel, StringLabel};
 pub use list::*;
@@ -96,4 +101,5 @@ pub use separator::Separator;
 pub use slider::{Slider, SliderType};
 pub use splitter::*;
 pub use stack::{BoxStack, RefStack, Stack};
+pub use tooltip::WithTooltip;
 pub use window::Window;
@@ -7,12 +7,14 @@
 
 use std::fmt::Debug;
 
+use kas::access::Role;
 use kas::prelude::*;
 
 /// A progress bar
 ///
 /// The "progress" value may range from 0.0 to 1.0.
 #[derive(Clone, Debug, Default, Widget)]
+#[widget(access=noauto)]
 pub struct ProgressBar<D: Directional> {
     #[widget_core]
     core: CoreData,
@@ -70,6 +72,15 @@ impl<D: Directional> ProgressBar<D> {
     }
 }
 
+impl<D: Directional> Accessible for ProgressBar<D> {
+    fn access_role(&self) -> Role {
+        Role::ProgressBar
+    }
+    fn access_value(&self) -> Option<String> {
+        Some(self.value.to_string())
+    }
+}
+
 impl<D: Directional> Layout for ProgressBar<D> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let mut size = size_handle.progress_bar();
@@ -11,13 +11,14 @@ use std::fmt::{self, Debug};
 use std::rc::Rc;
 
 use super::AccelLabel;
+use kas::access::{AccessActions, Role};
 use kas::event::{self, UpdateHandle};
 use kas::prelude::*;
 
 /// A bare radiobox (no label)
 #[derive(Clone, Widget)]
 #[handler(handle=noauto)]
-#[widget(config=noauto)]
+#[widget(config=noauto, access=noauto)]
 pub struct RadioBoxBare<M: 'static> {
     #[widget_core]
     core: CoreData,
@@ -49,6 +50,18 @@ impl<M: 'static> WidgetConfig for RadioBoxBare<M> {
     }
 }
 
+impl<M: 'static> Accessible for RadioBoxBare<M> {
+    fn access_role(&self) -> Role {
+        Role::RadioButton
+    }
+    fn access_checked(&self) -> Option<bool> {
+        Some(self.get_bool())
+    }
+    fn access_actions(&self) -> AccessActions {
+        AccessActions::CLICK
+    }
+}
+
 impl<M: 'static> event::Handler for RadioBoxBare<M> {
     type Msg = M;
 
@@ -184,7 +197,7 @@ impl<M: 'static> HasBool for RadioBoxBare<M> {
 #[derive(Clone, Widget)]
 #[layout(row, area=radiobox)]
 #[handler(msg = M, generics = <> where M: From<VoidMsg>)]
-#[widget(config=noauto)]
+#[widget(config=noauto, access=noauto)]
 pub struct RadioBox<M: 'static> {
     #[widget_core]
     core: CoreData,
@@ -302,6 +315,21 @@ impl<M: 'static> WidgetConfig for RadioBox<M> {
     }
 }
 
+impl<M: 'static> Accessible for RadioBox<M> {
+    fn access_role(&self) -> Role {
+        Role::RadioButton
+    }
+    fn access_label(&self) -> Option<String> {
+        Some(self.label.get_str().to_string())
+    }
+    fn access_checked(&self) -> Option<bool> {
+        Some(self.get_bool())
+    }
+    fn access_actions(&self) -> AccessActions {
+        AccessActions::CLICK
+    }
+}
+
 impl<M: 'static> HasBool for RadioBox<M> {
     #[inline]
     fn get_bool(&self) -> bool {
@@ -9,17 +9,53 @@ use super::Scrollable;
 use kas::draw::{ClipRegion, TextClass};
 use kas::event::ScrollDelta::{LineDelta, PixelDelta};
 use kas::event::{self, Command, PressSource};
+use kas::geom::Vec2;
 use kas::prelude::*;
 use std::fmt::Debug;
+use std::time::{Duration, Instant};
+
+/// Kinetic scrolling stops below this speed (pixels per second)
+const KINETIC_MIN_SPEED: f32 = 20.0;
+/// Fraction of kinetic scrolling velocity remaining after one second
+const KINETIC_DECAY: f32 = 0.05;
+/// Spring constant pulling an overshooting view back onto the end of the range
+const KINETIC_SPRING: f32 = 200.0;
+/// Interval between kinetic scrolling updates (about one frame)
+const KINETIC_STEP: Duration = Duration::from_millis(16);
+/// A touch held still for this long before release does not glide
+const KINETIC_HOLD: Duration = Duration::from_millis(50);
+/// A pause in touchpad scrolling of this length starts a glide
+const KINETIC_PAUSE: Duration = Duration::from_millis(40);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Kinetic {
+    None,
+    /// Measuring velocity of a touch drag or touchpad scroll
+    Track {
+        vel: Vec2,
+        time: Instant,
+        touchpad: bool,
+    },
+    /// Inertial motion with sub-pixel remainder
+    Glide {
+        vel: Vec2,
+        rest: Vec2,
+        time: Instant,
+    },
+}
 
 /// Logic for a scroll region
 ///
 /// This struct handles some scroll logic. It does not provide scrollbars.
+///
+/// If [`event::Config::kinetic_scrolling`] is enabled, touch drags and
+/// touchpad scrolling are followed by inertial motion.
 #[derive(Clone, Debug, PartialEq)]
 pub struct ScrollComponent {
     max_offset: Offset,
     offset: Offset,
     scroll_rate: f32,
+    kinetic: Kinetic,
 }
 
 impl Default for ScrollComponent {
@@ -29,6 +65,7 @@ impl Default for ScrollComponent {
             max_offset: Offset::ZERO,
             offset: Offset::ZERO,
             scroll_rate: 30.0,
+            kinetic: Kinetic::None,
         }
     }
 }
@@ -46,6 +83,9 @@ impl ScrollComponent {
     ///
     /// To translate a coordinate from the outer region to a coordinate of the
     /// scrolled region, add this offset.
+    ///
+    /// With [`event::Config::scroll_bounce`], kinetic scrolling may briefly
+    /// move the offset beyond the scroll range.
     #[inline]
     pub fn offset(&self) -> Offset {
         self.offset
@@ -102,6 +142,12 @@ impl ScrollComponent {
             Event::PressEnd { coord, .. } => {
                 *coord = *coord + self.offset;
             }
+            Event::DragMove { coord, .. } => {
+                *coord = *coord + self.offset;
+            }
+            Event::Drop { coord, .. } => {
+                *coord = *coord + self.offset;
+            }
             _ => {}
         };
         event
@@ -134,7 +180,9 @@ impl ScrollComponent {
     /// Behaviour on [`Event::PressStart`] is configurable: the closure is called on
     /// this event and should call [`Manager::request_grab`] if the press should
     /// scroll by drag. This allows control of which mouse button(s) are used and
-    /// whether any modifiers must be pressed. For example:
+    /// whether any modifiers must be pressed. The closure returns true if the
+    /// press is used; otherwise the press is reported as unhandled (allowing a
+    /// right press to open a context menu). For example:
     /// ```
     /// # use kas::prelude::*;
     /// # type Msg = ();
@@ -147,24 +195,33 @@ impl ScrollComponent {
     ///     -> Response<Msg>
     /// {
     ///     let window_size = Size(100, 80);
-    ///     let (action, response) = scroll.scroll_by_event(event, window_size, |source, _, coord| {
-    ///         if source.is_primary() {
-    ///             let icon = Some(kas::event::CursorIcon::Grabbing);
-    ///             mgr.request_grab(id, source, coord, kas::event::GrabMode::Grab, icon);
-    ///         }
-    ///     });
+    ///     let (action, response) = scroll.scroll_by_event(mgr, id, event, window_size,
+    ///         |mgr, source, _, coord| {
+    ///             if source.is_primary() {
+    ///                 let icon = Some(kas::event::CursorIcon::Grabbing);
+    ///                 mgr.request_grab(id, source, coord, kas::event::GrabMode::Grab, icon)
+    ///             } else {
+    ///                 false
+    ///             }
+    ///         });
     ///     *mgr |= action;
     ///     response.void_into()
     /// }
     /// ```
     ///
+    /// Kinetic scrolling (see [`event::Config::kinetic_scrolling`]) is
+    /// animated via [`Event::TimerUpdate`] events scheduled for widget `id`;
+    /// the caller should pass these events to this method.
+    ///
     /// If the returned [`TkAction`] is `None`, the scroll offset has not changed and
     /// the returned [`Response`] is either `None` or `Unhandled(..)`.
     /// If the returned [`TkAction`] is not `None`, the scroll offset has been
     /// updated and the second return value is `Response::None`.
     #[inline]
-    pub fn scroll_by_event<PS: FnMut(PressSource, WidgetId, Coord)>(
+    pub fn scroll_by_event<PS: FnMut(&mut Manager, PressSource, WidgetId, Coord) -> bool>(
         &mut self,
+        mgr: &mut Manager,
+        id: WidgetId,
         event: Event,
         window_size: Size,
         mut on_press_start: PS,
@@ -174,9 +231,11 @@ impl ScrollComponent {
 
         match event {
             Event::Command(Command::Home, _) => {
+                self.kinetic = Kinetic::None;
                 action = self.set_offset(Offset::ZERO);
             }
             Event::Command(Command::End, _) => {
+                self.kinetic = Kinetic::None;
                 action = self.set_offset(self.max_offset);
             }
             Event::Command(cmd, _) => {
@@ -197,15 +256,26 @@ impl ScrollComponent {
                     ),
                     PixelDelta(d) => d,
                 };
+                self.kinetic = Kinetic::None;
                 action = self.set_offset(self.offset - d);
             }
             Event::Scroll(delta) => {
                 let d = match delta {
-                    LineDelta(x, y) => Offset(
-                        (-self.scroll_rate * x).cast_nearest(),
-                        (self.scroll_rate * y).cast_nearest(),
-                    ),
-                    PixelDelta(d) => d,
+                    LineDelta(x, y) => {
+                        self.kinetic = Kinetic::None;
+                        Offset(
+                            (-self.scroll_rate * x).cast_nearest(),
+                            (self.scroll_rate * y).cast_nearest(),
+                        )
+                    }
+                    PixelDelta(d) => {
+                        // Touchpad scrolling: glide once deltas pause
+                        if mgr.config().kinetic_scrolling {
+                            self.track(mgr.now(), d, true);
+                            mgr.update_on_timer(KINETIC_PAUSE, id);
+                        }
+                        d
+                    }
                 };
                 action = self.set_offset(self.offset - d);
                 if action.is_empty() {
@@ -216,15 +286,171 @@ impl ScrollComponent {
                 source,
                 start_id,
                 coord,
-            } => on_press_start(source, start_id, coord),
-            Event::PressMove { delta, .. } => {
+            } => {
+                // A touch stops any glide (and any overshoot)
+                self.kinetic = Kinetic::None;
+                action = self.set_offset(self.offset);
+                if !on_press_start(mgr, source, start_id, coord) && action.is_empty() {
+                    response = Response::Unhandled;
+                }
+            }
+            Event::PressMove { source, delta, .. } => {
+                if source.is_touch() {
+                    self.track(mgr.now(), delta, false);
+                }
                 action = self.set_offset(self.offset - delta);
             }
-            Event::PressEnd { .. } => (), // consume due to request
+            Event::PressEnd { .. } => {
+                // consume due to request
+                match self.kinetic {
+                    Kinetic::Track { vel, time, .. } if mgr.now() - time < KINETIC_HOLD => {
+                        self.start_glide(mgr, id, vel);
+                    }
+                    _ => self.kinetic = Kinetic::None,
+                }
+            }
+            Event::TimerUpdate => action = self.kinetic_step(mgr, id),
             _ => response = Response::Unhandled,
         }
         (action, response)
     }
+
+    // Update the velocity estimate given a scroll by -delta
+    fn track(&mut self, now: Instant, delta: Offset, touchpad: bool) {
+        let mut vel = Vec2::ZERO;
+        if let Kinetic::Track { vel: v, time, .. } = self.kinetic {
+            let dt = now - time;
+            if dt < KINETIC_HOLD {
+                let sample = -Vec2::from(delta) / dt.as_secs_f32().max(1e-3);
+                vel = v * 0.25 + sample * 0.75;
+            }
+        }
+        self.kinetic = Kinetic::Track {
+            vel,
+            time: now,
+            touchpad,
+        };
+    }
+
+    fn start_glide(&mut self, mgr: &mut Manager, id: WidgetId, vel: Vec2) {
+        if !mgr.config().kinetic_scrolling || vel.sum_square() < KINETIC_MIN_SPEED.powi(2) {
+            self.kinetic = Kinetic::None;
+            return;
+        }
+        self.kinetic = Kinetic::Glide {
+            vel,
+            rest: Vec2::ZERO,
+            time: mgr.now(),
+        };
+        mgr.update_on_timer(KINETIC_STEP, id);
+    }
+
+    // Advance kinetic scrolling on a timer update
+    fn kinetic_step(&mut self, mgr: &mut Manager, id: WidgetId) -> TkAction {
+        let now = mgr.now();
+        let (vel, rest, time) = match self.kinetic {
+            Kinetic::Track {
+                vel,
+                time,
+                touchpad: true,
+            } => {
+                let dt = now - time;
+                if dt >= KINETIC_PAUSE {
+                    self.start_glide(mgr, id, vel);
+                } else {
+                    mgr.update_on_timer(KINETIC_PAUSE - dt, id);
+                }
+                return TkAction::empty();
+            }
+            Kinetic::Glide { vel, rest, time } => (vel, rest, time),
+            _ => return TkAction::empty(),
+        };
+
+        let max = Vec2::from(self.max_offset);
+        let mut pos = Vec2::from(self.offset) + rest;
+        let mut vel = vel;
+        let bounce = mgr.config().scroll_bounce;
+        let moving = glide(&mut pos, &mut vel, max, bounce, now - time);
+
+        // The offset is not clamped since the view may overshoot when bouncing
+        let offset = Offset::from(pos);
+        let action = match offset == self.offset {
+            true => TkAction::empty(),
+            false => {
+                self.offset = offset;
+                TkAction::REGION_MOVED
+            }
+        };
+
+        if moving {
+            self.kinetic = Kinetic::Glide {
+                vel,
+                rest: pos - Vec2::from(offset),
+                time: now,
+            };
+            mgr.update_on_timer(KINETIC_STEP, id);
+        } else {
+            self.kinetic = Kinetic::None;
+        }
+        action
+    }
+}
+
+/// Advance kinetic motion over `dt`, returning false once motion stops
+///
+/// Velocity `vel` decays over time. Where motion would leave the scroll range
+/// `0..=max`, it either stops at the end or, if `bounce`, overshoots and is
+/// pulled back onto the end by a critically damped spring (thus `pos` is only
+/// outside of the range while bouncing).
+fn glide(pos: &mut Vec2, vel: &mut Vec2, max: Vec2, bounce: bool, dt: Duration) -> bool {
+    // Integrate in steps of at most KINETIC_STEP for stability of the spring;
+    // after a long pause motion will have stopped anyway.
+    let dt = dt.min(Duration::from_secs(1)).as_secs_f32();
+    let steps: u32 = (dt / KINETIC_STEP.as_secs_f32()).cast_ceil();
+    let h = dt / steps.max(1) as f32;
+    for _ in 0..steps {
+        glide_axis(&mut pos.0, &mut vel.0, max.0, bounce, h);
+        glide_axis(&mut pos.1, &mut vel.1, max.1, bounce, h);
+    }
+
+    // Stop when slow and (nearly) within range
+    let max = max.max(Vec2::ZERO);
+    let clamped = pos.max(Vec2::ZERO).min(max);
+    let near = (*pos - clamped).abs().lt(Vec2::splat(0.5));
+    if vel.sum_square() < KINETIC_MIN_SPEED.powi(2) && near {
+        *pos = clamped;
+        *vel = Vec2::ZERO;
+        false
+    } else {
+        true
+    }
+}
+
+// Advance kinetic motion on one axis by `h` seconds (see `glide`)
+fn glide_axis(pos: &mut f32, vel: &mut f32, max: f32, bounce: bool, h: f32) {
+    let max = max.max(0.0);
+    let bound = pos.max(0.0).min(max);
+    if *pos == bound {
+        *pos += *vel * h;
+        *vel *= KINETIC_DECAY.powf(h);
+        if !bounce || max == 0.0 {
+            let clamped = pos.max(0.0).min(max);
+            if *pos != clamped {
+                *pos = clamped;
+                *vel = 0.0;
+            }
+        }
+    } else {
+        let dist = *pos - bound;
+        let accel = -KINETIC_SPRING * dist - 2.0 * KINETIC_SPRING.sqrt() * *vel;
+        *vel += accel * h;
+        *pos += *vel * h;
+        if (*pos - bound) * dist <= 0.0 {
+            // Settle on the end instead of passing it
+            *pos = bound;
+            *vel = 0.0;
+        }
+    }
 }
 
 /// A scrollable region
@@ -383,14 +609,20 @@ impl<W: Widget> event::SendEvent for ScrollRegion<W> {
         };
 
         let id = self.id();
-        let (action, response) =
-            self.scroll
-                .scroll_by_event(event, self.core.rect.size, |source, _, coord| {
-                    if source.is_primary() {
-                        let icon = Some(event::CursorIcon::Grabbing);
-                        mgr.request_grab(id, source, coord, event::GrabMode::Grab, icon);
-                    }
-                });
+        let (action, response) = self.scroll.scroll_by_event(
+            mgr,
+            id,
+            event,
+            self.core.rect.size,
+            |mgr, source, _, coord| {
+                if source.is_primary() {
+                    let icon = Some(event::CursorIcon::Grabbing);
+                    mgr.request_grab(id, source, coord, event::GrabMode::Grab, icon)
+                } else {
+                    false
+                }
+            },
+        );
         if !action.is_empty() {
             *mgr |= action;
             Response::Focus(self.core.rect)
@@ -412,3 +644,58 @@ impl<W: Widget> std::ops::DerefMut for ScrollRegion<W> {
         &mut self.inner
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAX: Vec2 = Vec2(0.0, 1000.0);
+
+    #[test]
+    fn glide_decay() {
+        let mut pos = Vec2(0.0, 100.0);
+        let mut vel = Vec2(0.0, 400.0);
+        assert!(glide(
+            &mut pos,
+            &mut vel,
+            MAX,
+            false,
+            Duration::from_secs(1)
+        ));
+        assert!((vel.1 - 400.0 * KINETIC_DECAY).abs() < 1e-3);
+        assert!(pos.1 > 100.0 && pos.1 < 500.0);
+    }
+
+    #[test]
+    fn glide_stop() {
+        let mut pos = Vec2(0.0, 100.0);
+        let mut vel = Vec2(0.0, 0.9 * KINETIC_MIN_SPEED);
+        assert!(!glide(&mut pos, &mut vel, MAX, false, KINETIC_STEP));
+        assert_eq!(vel, Vec2::ZERO);
+
+        // Without bounce, motion stops at the end of the range
+        let mut pos = Vec2(0.0, 990.0);
+        let mut vel = Vec2(0.0, 2000.0);
+        assert!(!glide(&mut pos, &mut vel, MAX, false, KINETIC_STEP));
+        assert_eq!((pos, vel), (Vec2(0.0, 1000.0), Vec2::ZERO));
+    }
+
+    #[test]
+    fn glide_bounce() {
+        let mut pos = Vec2(0.0, 990.0);
+        let mut vel = Vec2(0.0, 2000.0);
+        let mut overshoot = 0.0f32;
+        let mut moving = true;
+        for _ in 0..200 {
+            moving = glide(&mut pos, &mut vel, MAX, true, KINETIC_STEP);
+            overshoot = overshoot.max(pos.1 - MAX.1);
+            if !moving {
+                break;
+            }
+        }
+        // The view overshoots, then settles on the end of the range
+        assert!(overshoot > 1.0);
+        assert!(!moving);
+        assert_eq!((pos, vel), (Vec2(0.0, 1000.0), Vec2::ZERO));
+    }
+}
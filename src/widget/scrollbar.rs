@@ -8,6 +8,7 @@
 use std::fmt::Debug;
 
 use super::{DragHandle, ScrollRegion};
+use kas::access::Role;
 use kas::{event, prelude::*};
 
 /// A scroll bar
@@ -16,6 +17,7 @@ use kas::{event, prelude::*};
 /// and allow the size of the handle to be specified.
 #[derive(Clone, Debug, Default, Widget)]
 #[handler(send=noauto, msg = i32)]
+#[widget(access=noauto)]
 pub struct ScrollBar<D: Directional> {
     #[widget_core]
     core: CoreData,
@@ -199,6 +201,15 @@ impl<D: Directional> ScrollBar<D> {
     }
 }
 
+impl<D: Directional> Accessible for ScrollBar<D> {
+    fn access_role(&self) -> Role {
+        Role::ScrollBar
+    }
+    fn access_value(&self) -> Option<String> {
+        Some(self.value.to_string())
+    }
+}
+
 impl<D: Directional> Layout for ScrollBar<D> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let (size, min_len) = size_handle.scrollbar();
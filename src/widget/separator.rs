@@ -8,6 +8,7 @@
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
+use kas::access::Role;
 use kas::widget::Menu;
 use kas::{event, prelude::*};
 
@@ -16,6 +17,7 @@ use kas::{event, prelude::*};
 /// This widget draws a bar when in a list.
 #[derive(Clone, Debug, Default, Widget)]
 #[handler(msg=M)]
+#[widget(access=noauto)]
 pub struct Separator<M: Debug + 'static> {
     #[widget_core]
     core: CoreData,
@@ -47,6 +49,12 @@ impl<M: Debug> Separator<M> {
     }
 }
 
+impl<M: Debug> Accessible for Separator<M> {
+    fn access_role(&self) -> Role {
+        Role::Separator
+    }
+}
+
 impl<M: Debug> Layout for Separator<M> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         SizeRules::extract_fixed(axis, size_handle.separator(), Default::default())
@@ -5,17 +5,21 @@
 
 //! `Slider` control
 
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 use std::ops::{Add, Sub};
 use std::time::Duration;
 
 use super::DragHandle;
-use kas::event::{self, Command};
+use kas::access::Role;
+use kas::event::{self, Command, UndoValue};
 use kas::prelude::*;
 
 /// Requirements on type used by [`Slider`]
+///
+/// Via [`Display`], the value is reported to assistive technologies (see
+/// [`kas::Accessible::access_value`]).
 pub trait SliderType:
-    Copy + Debug + PartialOrd + Add<Output = Self> + Sub<Output = Self> + 'static
+    Copy + Debug + Display + PartialOrd + Add<Output = Self> + Sub<Output = Self> + 'static
 {
     /// Divide self by another instance of this type, returning an `f64`
     ///
@@ -86,7 +90,7 @@ impl SliderType for Duration {
 /// Sliders allow user input of a value from a fixed range.
 #[derive(Clone, Debug, Default, Widget)]
 #[handler(send=noauto, msg = T)]
-#[widget(config(key_nav = true, hover_highlight = true))]
+#[widget(config(key_nav = true, hover_highlight = true), access=noauto)]
 pub struct Slider<T: SliderType, D: Directional> {
     #[widget_core]
     core: CoreData,
@@ -188,6 +192,16 @@ impl<T: SliderType, D: Directional> Slider<T, D> {
         }
     }
 
+    // record a change from old_value in the undo history
+    fn push_undo(&self, mgr: &mut Manager, old_value: T) {
+        let set = |w: &mut Self, mgr: &mut Manager, value| {
+            *mgr |= w.set_value(value);
+            Some(w.value)
+        };
+        let op = UndoValue::merging(old_value, self.value, set);
+        mgr.push_undo(self.id(), Box::new(op));
+    }
+
     // true if not equal to old value
     fn set_offset(&mut self, offset: Offset) -> bool {
         let b = self.range.1 - self.range.0;
@@ -215,6 +229,15 @@ impl<T: SliderType, D: Directional> Slider<T, D> {
     }
 }
 
+impl<T: SliderType, D: Directional> Accessible for Slider<T, D> {
+    fn access_role(&self) -> Role {
+        Role::Slider
+    }
+    fn access_value(&self) -> Option<String> {
+        Some(self.value.to_string())
+    }
+}
+
 impl<T: SliderType, D: Directional> Layout for Slider<T, D> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let (size, min_len) = size_handle.slider();
@@ -259,6 +282,7 @@ impl<T: SliderType, D: Directional> event::SendEvent for Slider<T, D> {
             return Response::Unhandled;
         }
 
+        let old_value = self.value;
         let offset = if id <= self.handle.id() {
             match self.handle.send(mgr, id, event).try_into() {
                 Ok(res) => return res,
@@ -297,17 +321,20 @@ impl<T: SliderType, D: Directional> event::SendEvent for Slider<T, D> {
                         Response::None
                     } else {
                         mgr.send_action(action);
+                        self.push_undo(mgr, old_value);
                         Response::Msg(self.value)
                     };
                 }
                 Event::PressStart { source, coord, .. } => {
                     self.handle.handle_press_on_track(mgr, source, coord)
                 }
+                Event::Undo(undo) => return undo.apply(self, mgr),
                 _ => return Response::Unhandled,
             }
         };
 
         let r = if self.set_offset(offset) {
+            self.push_undo(mgr, old_value);
             Response::Msg(self.value)
         } else {
             Response::None
@@ -32,7 +32,7 @@ pub type RefStack<'a, M> = Stack<&'a mut dyn Widget<Msg = M>>;
 /// Drawing and event handling is O(1).
 #[derive(Clone, Default, Debug, Widget)]
 #[handler(send=noauto, msg=<W as event::Handler>::Msg)]
-#[widget(children=noauto)]
+#[widget(children=noauto, access=noauto)]
 pub struct Stack<W: Widget> {
     first_id: WidgetId,
     #[widget_core]
@@ -63,6 +63,12 @@ impl<W: Widget> WidgetChildren for Stack<W> {
     }
 }
 
+impl<W: Widget> Accessible for Stack<W> {
+    fn access_child_hidden(&self, index: usize) -> bool {
+        index != self.active
+    }
+}
+
 impl<W: Widget> Layout for Stack<W> {
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
         let mut rules = SizeRules::EMPTY;
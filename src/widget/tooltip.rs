@@ -0,0 +1,150 @@
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License in the LICENSE-APACHE file or at:
+//     https://www.apache.org/licenses/LICENSE-2.0
+
+//! Tooltips
+
+use kas::draw::TextClass;
+use kas::text::RichText;
+use kas::{event, prelude::*};
+
+/// A wrapper adding a tooltip to a widget
+///
+/// The tooltip is shown when the mouse hovers over the widget for a while
+/// (see [`event::Config::tooltip_delay_ms`]) or on [`event::Command::Tooltip`]
+/// while the widget has keyboard navigation focus. It is hidden on any press
+/// or when the mouse leaves the widget.
+///
+/// The tooltip may be plain text (e.g. `"Save the file".into()`) or formatted.
+#[derive(Clone, Debug, Default, Widget)]
+#[handler(msg = <W as Handler>::Msg)]
+#[widget(config=noauto)]
+pub struct WithTooltip<W: Widget> {
+    #[widget_core]
+    core: CoreData,
+    #[widget]
+    pub inner: W,
+    tooltip: RichText,
+}
+
+impl<W: Widget> WithTooltip<W> {
+    /// Construct around `inner` with the given `tooltip`
+    #[inline]
+    pub fn new<T: Into<RichText>>(inner: W, tooltip: T) -> Self {
+        WithTooltip {
+            core: Default::default(),
+            inner,
+            tooltip: tooltip.into(),
+        }
+    }
+
+    /// Set the tooltip
+    ///
+    /// Takes effect the next time the tooltip is shown.
+    #[inline]
+    pub fn set_tooltip<T: Into<RichText>>(&mut self, tooltip: T) {
+        self.tooltip = tooltip.into();
+    }
+}
+
+impl<W: Widget> WidgetConfig for WithTooltip<W> {
+    fn tooltip(&self) -> Option<RichText> {
+        Some(self.tooltip.clone())
+    }
+}
+
+impl<W: Widget> Layout for WithTooltip<W> {
+    #[inline]
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        self.inner.size_rules(size_handle, axis)
+    }
+
+    fn set_rect(&mut self, mgr: &mut Manager, rect: Rect, align: AlignHints) {
+        self.core.rect = rect;
+        self.inner.set_rect(mgr, rect, align);
+    }
+
+    #[inline]
+    fn find_id(&self, coord: Coord) -> Option<WidgetId> {
+        if !self.rect().contains(coord) {
+            return None;
+        }
+        self.inner.find_id(coord).or(Some(self.id()))
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
+        let disabled = disabled || self.is_disabled();
+        self.inner.draw(draw_handle, mgr, disabled);
+    }
+}
+
+impl<W: HasStr + Widget> HasStr for WithTooltip<W> {
+    fn get_str(&self) -> &str {
+        self.inner.get_str()
+    }
+}
+
+impl<W: HasString + Widget> HasString for WithTooltip<W> {
+    fn set_string(&mut self, text: String) -> TkAction {
+        self.inner.set_string(text)
+    }
+}
+
+/// Tooltip display
+///
+/// This widget is owned by [`kas::widget::Window`]. It is not a pop-up in the
+/// sense of [`kas::Popup`] since it must not receive input or focus; the
+/// window draws it while [`event::ManagerState::tooltip`] matches its target.
+#[derive(Clone, Debug, Default, Widget)]
+pub(crate) struct TooltipPopup {
+    #[widget_core]
+    core: CoreData,
+    label: Text<RichText>,
+    offset: Offset,
+    size: Size,
+    target: Option<WidgetId>,
+}
+
+impl TooltipPopup {
+    /// The target widget of the last tooltip set
+    pub(crate) fn target(&self) -> Option<WidgetId> {
+        self.target
+    }
+
+    /// Set contents; the caller must then resize this widget
+    pub(crate) fn set(&mut self, target: WidgetId, text: RichText) {
+        self.label = Text::new_multi(text);
+        self.target = Some(target);
+    }
+}
+
+impl Layout for TooltipPopup {
+    fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
+        let frame_rules = size_handle.frame(axis.is_vertical());
+        let text_rules = size_handle.text_bound(&mut self.label, TextClass::Label, axis);
+        let (rules, offset, size) = frame_rules.surround(text_rules);
+        self.offset.set_component(axis, offset);
+        self.size.set_component(axis, size);
+        rules
+    }
+
+    fn set_rect(&mut self, _: &mut Manager, rect: Rect, _: AlignHints) {
+        self.core.rect = rect;
+        let bounds = (rect.size - self.size).into();
+        self.label.update_env(|env| {
+            env.set_bounds(bounds);
+            env.set_align((Align::Default, Align::Centre));
+        });
+    }
+
+    fn find_id(&self, _: Coord) -> Option<WidgetId> {
+        None
+    }
+
+    fn draw(&self, draw_handle: &mut dyn DrawHandle, _: &event::ManagerState, _: bool) {
+        draw_handle.menu_frame(self.core.rect);
+        let pos = self.core.rect.pos + self.offset;
+        draw_handle.text_effects(pos, Offset::ZERO, &self.label, TextClass::Label);
+    }
+}
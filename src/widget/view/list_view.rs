@@ -6,7 +6,7 @@
 //! List view widget
 
 use super::{DefaultView, ListData, View};
-use kas::event::{CursorIcon, GrabMode, PressSource};
+use kas::event::{CursorIcon, DragPayload, GrabMode, PressSource};
 use kas::layout::solve_size_rules;
 use kas::prelude::*;
 #[allow(unused)] // doc links
@@ -36,11 +36,35 @@ struct WidgetData<K, W> {
     widget: W,
 }
 
+/// Drag-and-drop payload of a [`ListView`] row
+///
+/// See [`ListView::set_drag_and_drop`].
+#[derive(Clone, Debug)]
+pub struct ListDrag<K, I> {
+    /// Key of the dragged row
+    pub key: K,
+    /// Data of the dragged row
+    pub item: I,
+}
+
 /// Message type of [`ListView`]
 #[derive(Clone, Debug, VoidMsg)]
 pub enum ListMsg<K, M> {
     Select(K),
     Deselect(K),
+    /// Row `K` was dragged to position `usize` within the same list
+    ///
+    /// The position is the index (in the order of the data) of the row before
+    /// which `K` should be inserted (before removal of `K`).
+    Reorder(K, usize),
+    /// A row of another list was dropped at position `usize`
+    ///
+    /// The payload contains a [`ListDrag`].
+    Drop(DragPayload, usize),
+    /// Row `K` was dropped on another widget
+    ///
+    /// To move (rather than copy) rows between lists, remove the row here.
+    DraggedOut(K),
     Child(K, M),
 }
 
@@ -87,6 +111,8 @@ pub struct ListView<D: Directional, T: ListData + 'static, V: View<T::Key, T::It
     selection: LinearSet<T::Key>,
     press_event: Option<PressSource>,
     press_target: Option<T::Key>,
+    drag_enabled: bool,
+    drop_index: Option<usize>,
 }
 
 impl<D: Directional + Default, T: ListData, V: View<T::Key, T::Item> + Default> ListView<D, T, V> {
@@ -136,6 +162,8 @@ impl<D: Directional, T: ListData, V: View<T::Key, T::Item>> ListView<D, T, V> {
             selection: Default::default(),
             press_event: None,
             press_target: None,
+            drag_enabled: false,
+            drop_index: None,
         }
     }
 
@@ -243,6 +271,30 @@ impl<D: Directional, T: ListData, V: View<T::Key, T::Item>> ListView<D, T, V> {
         self.selection.remove(key)
     }
 
+    /// Get whether drag and drop is enabled
+    pub fn drag_and_drop(&self) -> bool {
+        self.drag_enabled
+    }
+    /// Enable or disable drag and drop
+    ///
+    /// When enabled, dragging a row starts a drag-and-drop operation (see
+    /// [`Manager::start_drag`]) with a [`ListDrag`] payload instead of
+    /// scrolling the list (wheel and keyboard scrolling are unaffected).
+    /// Rows from any list with the same key and item types may be dropped
+    /// between the rows of this list.
+    ///
+    /// The list does not modify its data itself; instead it emits
+    /// [`ListMsg::Reorder`], [`ListMsg::Drop`] and [`ListMsg::DraggedOut`].
+    /// The parent should update the data and call [`ListView::update_view`].
+    pub fn set_drag_and_drop(&mut self, enabled: bool) {
+        self.drag_enabled = enabled;
+    }
+    /// Enable or disable drag and drop (inline)
+    pub fn with_drag_and_drop(mut self, enabled: bool) -> Self {
+        self.drag_enabled = enabled;
+        self
+    }
+
     /// Manually trigger an update to handle changed data
     pub fn update_view(&mut self, mgr: &mut Manager) {
         for w in &mut self.widgets {
@@ -307,6 +359,43 @@ impl<D: Directional, T: ListData, V: View<T::Key, T::Item>> ListView<D, T, V> {
         let dur = (Instant::now() - time).as_micros();
         trace!("ListView::update_widgets completed in {}μs", dur);
     }
+
+    /// Position along the list of the gap before the row with data `index`
+    ///
+    /// This is the end of the gap, in content coordinates (as used by
+    /// `update_widgets`).
+    fn gap_pos(&self, index: usize) -> i32 {
+        let start = (self.core.rect.pos + self.offset).extract(self.direction);
+        let index = i32::conv(index);
+        match self.direction.is_reversed() {
+            false => start + self.child_skip * index,
+            true => {
+                let len = i32::conv(self.cur_len);
+                start + self.child_skip * (len - index)
+            }
+        }
+    }
+
+    /// Find the drop position for `coord` (in our own coordinates)
+    fn drop_index_at(&self, coord: Coord) -> usize {
+        let pos = (coord + self.scroll_offset()).extract(self.direction);
+        let d = match self.direction.is_reversed() {
+            false => pos - self.gap_pos(0),
+            true => self.gap_pos(0) - pos,
+        };
+        let index = (d + self.child_skip / 2) / self.child_skip.max(1);
+        usize::conv(index.max(0)).min(self.data.len())
+    }
+
+    /// The rect in which to draw the drop marker (in content coordinates)
+    fn drop_marker(&self, index: usize) -> Rect {
+        let mut pos = self.core.rect.pos + self.offset;
+        let mut size = self.child_size;
+        let thickness = self.child_inter_margin.max(1);
+        pos.set_component(self.direction, self.gap_pos(index) - thickness);
+        size.set_component(self.direction, thickness);
+        Rect::new(pos, size)
+    }
 }
 
 impl<D: Directional, T: ListData, V: View<T::Key, T::Item>> Scrollable for ListView<D, T, V> {
@@ -362,6 +451,10 @@ impl<D: Directional, T: ListData, V: View<T::Key, T::Item>> WidgetChildren for L
             .get_mut(index)
             .map(|w| w.widget.as_widget_mut())
     }
+    fn child_key(&self, index: usize) -> Option<String> {
+        let key = self.widgets.get(index)?.key.as_ref()?;
+        Some(format!("{:?}", key))
+    }
 }
 
 impl<D: Directional, T: ListData, V: View<T::Key, T::Item>> WidgetConfig for ListView<D, T, V> {
@@ -515,6 +608,9 @@ impl<D: Directional, T: ListData, V: View<T::Key, T::Item>> Layout for ListView<
                     }
                 }
             }
+            if let Some(index) = self.drop_index {
+                draw_handle.separator(self.drop_marker(index));
+            }
         });
     }
 }
@@ -581,11 +677,56 @@ impl<D: Directional, T: ListData, V: View<T::Key, T::Item>> SendEvent for ListVi
                     self.update_view(mgr);
                     return Response::Update;
                 }
-                Event::PressMove { source, .. } if self.press_event == Some(source) => {
+                Event::PressMove { source, coord, .. } if self.press_event == Some(source) => {
                     self.press_event = None;
+                    if self.drag_enabled {
+                        if let Some(key) = self.press_target.clone() {
+                            if let Some(item) = self.get_value(&key) {
+                                let payload = DragPayload::new(ListDrag { key, item });
+                                mgr.start_drag(self.id(), source, coord, payload);
+                            }
+                        }
+                        return Response::None;
+                    }
                     mgr.update_grab_cursor(self.id(), CursorIcon::Grabbing);
                     // fall through to scroll handler
                 }
+                Event::DragMove { payload, coord } => {
+                    if !self.drag_enabled || !payload.is::<ListDrag<T::Key, T::Item>>() {
+                        return Response::Unhandled;
+                    }
+                    let index = Some(self.drop_index_at(coord));
+                    if index != self.drop_index {
+                        self.drop_index = index;
+                        mgr.redraw(self.id());
+                    }
+                    return Response::None;
+                }
+                Event::DragLeave => {
+                    self.drop_index = None;
+                    mgr.redraw(self.id());
+                    return Response::None;
+                }
+                Event::Drop { payload, coord } => {
+                    self.drop_index = None;
+                    mgr.redraw(self.id());
+                    let index = self.drop_index_at(coord);
+                    if payload.source() != self.id() {
+                        return ListMsg::Drop(payload, index).into();
+                    }
+                    return match payload.get::<ListDrag<T::Key, T::Item>>() {
+                        Some(drag) => ListMsg::Reorder(drag.key.clone(), index).into(),
+                        None => Response::Unhandled,
+                    };
+                }
+                Event::DragEnd { target } => {
+                    if let (Some(target), Some(key)) = (target, self.press_target.clone()) {
+                        if target != self.id() {
+                            return ListMsg::DraggedOut(key).into();
+                        }
+                    }
+                    return Response::None;
+                }
                 Event::PressEnd { source, .. } if self.press_event == Some(source) => {
                     self.press_event = None;
                     return match self.sel_mode {
@@ -618,14 +759,20 @@ impl<D: Directional, T: ListData, V: View<T::Key, T::Item>> SendEvent for ListVi
         };
 
         let id = self.id();
-        let (action, response) =
-            self.scroll
-                .scroll_by_event(event, self.core.rect.size, |source, _, coord| {
-                    if source.is_primary() {
-                        let icon = Some(CursorIcon::Grabbing);
-                        mgr.request_grab(id, source, coord, GrabMode::Grab, icon);
-                    }
-                });
+        let (action, response) = self.scroll.scroll_by_event(
+            mgr,
+            id,
+            event,
+            self.core.rect.size,
+            |mgr, source, _, coord| {
+                if source.is_primary() {
+                    let icon = Some(CursorIcon::Grabbing);
+                    mgr.request_grab(id, source, coord, GrabMode::Grab, icon)
+                } else {
+                    false
+                }
+            },
+        );
         if !action.is_empty() {
             *mgr |= action;
             self.update_widgets(mgr);
@@ -635,3 +782,79 @@ impl<D: Directional, T: ListData, V: View<T::Key, T::Item>> SendEvent for ListVi
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kas::dir::Down;
+    use kas::event::MouseButton;
+    use kas::testing::Harness;
+    use kas::widget::Row;
+
+    type List = ListView<Down, Box<[String]>>;
+
+    fn list(items: &[&str]) -> List {
+        let data: Vec<String> = items.iter().map(|item| item.to_string()).collect();
+        ListView::new(data.into_boxed_slice()).with_drag_and_drop(true)
+    }
+
+    fn drag(harness: &mut Harness<Row<List>>, from: Coord, to: Coord) {
+        harness.mouse_move(from);
+        harness.mouse_press(MouseButton::Left);
+        harness.mouse_move(from + Offset(1, 1));
+        harness.mouse_move(to);
+        harness.mouse_release(MouseButton::Left);
+    }
+
+    #[test]
+    fn drag_and_drop() {
+        let lists = Row::new(vec![list(&["a", "b", "c"]), list(&["d"])]);
+        let mut harness = Harness::new(lists);
+        let row_rect =
+            |harness: &Harness<Row<List>>, i| harness.widget()[0].widgets[i].widget.rect();
+
+        // Drag row "a" to the end of the same list
+        let from = row_rect(&harness, 0).pos + Offset(1, 1);
+        let rect = row_rect(&harness, 2);
+        let to = rect.pos + rect.size - Offset(1, 1);
+        drag(&mut harness, from, to);
+        let msgs = harness.take_messages();
+        assert_eq!(msgs.len(), 1);
+        match msgs[0] {
+            (0, ListMsg::Reorder(0, 3)) => (),
+            ref msg => panic!("unexpected message: {:?}", msg),
+        }
+
+        // Drag row "a" to the other list
+        let rect = harness.widget()[1].rect();
+        let to = rect.pos + rect.size - Offset(1, 1);
+        drag(&mut harness, from, to);
+        let msgs = harness.take_messages();
+        assert_eq!(msgs.len(), 2);
+        match msgs[0] {
+            (1, ListMsg::Drop(ref payload, 1)) => {
+                assert_eq!(payload.source(), harness.widget()[0].id());
+                let drag = payload.get::<ListDrag<usize, String>>().unwrap();
+                assert_eq!((drag.key, drag.item.as_str()), (0, "a"));
+            }
+            ref msg => panic!("unexpected message: {:?}", msg),
+        }
+        match msgs[1] {
+            (0, ListMsg::DraggedOut(0)) => (),
+            ref msg => panic!("unexpected message: {:?}", msg),
+        }
+
+        // Payloads of other types are not accepted
+        let id = harness.widget()[1].id();
+        let event = Event::DragMove {
+            payload: DragPayload::new(1i32),
+            coord: to,
+        };
+        let mut response = None;
+        harness.with_manager(|mgr, lists| response = Some(lists[1].send(mgr, id, event)));
+        match response {
+            Some(Response::Unhandled) => (),
+            r => panic!("unexpected response: {:?}", r),
+        }
+    }
+}
@@ -46,7 +46,7 @@ mod view_widget;
 
 pub use data_traits::{ListData, ListDataMut, SingleData, SingleDataMut};
 pub use filter::{Filter, FilteredList, SimpleCaseInsensitiveFilter};
-pub use list_view::{ListMsg, ListView, SelectionMode};
+pub use list_view::{ListDrag, ListMsg, ListView, SelectionMode};
 pub use shared_data::SharedRc;
 pub use single_view::SingleView;
 pub use view_widget::{CheckBoxView, RadioBoxBareView, RadioBoxView, SliderView};
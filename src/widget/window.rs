@@ -8,22 +8,33 @@
 use smallvec::SmallVec;
 use std::fmt::{self, Debug};
 
-use kas::draw::ClipRegion;
-use kas::event::{self, UpdateHandle};
+use super::menu::ContextMenuPopup;
+use super::tooltip::TooltipPopup;
+use kas::access::Role;
+use kas::draw::{ClipRegion, TextClass};
+use kas::event::{self, Command, ContextMenu, UpdateHandle};
 use kas::layout;
 use kas::prelude::*;
 use kas::{Future, WindowId};
 
 /// The main instantiation of the [`Window`] trait.
+///
+/// The window owns the context menu (see [`event::ContextMenu`]) and the
+/// tooltip display (see [`Event::OpenTooltip`]).
 #[derive(Widget)]
 #[handler(send=noauto, generics = <M: Into<VoidMsg>> where W: Widget<Msg = M>)]
+#[widget(access=noauto)]
 pub struct Window<W: Widget + 'static> {
     #[widget_core]
     core: CoreData,
     restrict_dimensions: (bool, bool),
     title: String,
     #[widget]
-    w: W,
+    pub(crate) w: W,
+    #[widget]
+    context: ContextMenuPopup,
+    #[widget]
+    tooltip: TooltipPopup,
     popups: SmallVec<[(WindowId, kas::Popup); 16]>,
     drop: Option<(Box<dyn FnMut(&mut W)>, UpdateHandle)>,
 }
@@ -32,8 +43,8 @@ impl<W: Widget> Debug for Window<W> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "Window {{ core: {:?}, restrict_dimensions: {:?}, title: {:?}, w: {:?}, popups: {:?}, drop: ",
-            self.core, self.restrict_dimensions, self.title, self.w, self.popups,
+            "Window {{ core: {:?}, restrict_dimensions: {:?}, title: {:?}, w: {:?}, context: {:?}, tooltip: {:?}, popups: {:?}, drop: ",
+            self.core, self.restrict_dimensions, self.title, self.w, self.context, self.tooltip, self.popups,
         )?;
         if let Some(ref d) = self.drop {
             write!(f, "Some(<closure>, {:?})", d.1)?;
@@ -51,6 +62,8 @@ impl<W: Widget + Clone> Clone for Window<W> {
             restrict_dimensions: self.restrict_dimensions.clone(),
             title: self.title.clone(),
             w: self.w.clone(),
+            context: Default::default(),
+            tooltip: Default::default(),
             popups: Default::default(), // these are temporary; don't clone
             drop: None,                 // we cannot clone this!
         }
@@ -65,6 +78,8 @@ impl<W: Widget> Window<W> {
             restrict_dimensions: (true, false),
             title: title.to_string(),
             w,
+            context: Default::default(),
+            tooltip: Default::default(),
             popups: Default::default(),
             drop: None,
         }
@@ -117,6 +132,24 @@ impl<W: Widget> Window<W> {
     }
 }
 
+impl<W: Widget> Accessible for Window<W> {
+    fn access_role(&self) -> Role {
+        Role::Window
+    }
+    fn access_label(&self) -> Option<String> {
+        Some(self.title.clone())
+    }
+    fn access_child_hidden(&self, index: usize) -> bool {
+        match index {
+            0 => false,
+            // The context menu is reported only while open
+            1 => !self.popups.iter().any(|p| p.1.id == self.context.id()),
+            // The tooltip is not reported
+            _ => true,
+        }
+    }
+}
+
 impl<W: Widget> Layout for Window<W> {
     #[inline]
     fn size_rules(&mut self, size_handle: &mut dyn SizeHandle, axis: AxisInfo) -> SizeRules {
@@ -136,13 +169,18 @@ impl<W: Widget> Layout for Window<W> {
             return None;
         }
         for popup in self.popups.iter().rev() {
-            if let Some(id) = self.w.find_leaf(popup.1.id).and_then(|w| w.find_id(coord)) {
+            if let Some(id) = self.find_leaf(popup.1.id).and_then(|w| w.find_id(coord)) {
                 return Some(id);
             }
         }
         self.w.find_id(coord).or(Some(self.id()))
     }
 
+    fn spatial_range(&self) -> (usize, usize) {
+        // Exclude the context menu and tooltip, which are pop-ups
+        (0, 0)
+    }
+
     #[inline]
     fn draw(&self, draw_handle: &mut dyn DrawHandle, mgr: &event::ManagerState, disabled: bool) {
         let disabled = disabled || self.is_disabled();
@@ -154,13 +192,38 @@ impl<W: Widget> Layout for Window<W> {
                     .map(|w| w.draw(draw_handle, mgr, disabled));
             });
         }
+        if mgr.tooltip().is_some() && mgr.tooltip() == self.tooltip.target() {
+            let class = ClipRegion::Popup;
+            draw_handle.clip_region(self.core.rect, Offset::ZERO, class, &mut |draw_handle| {
+                self.tooltip.draw(draw_handle, mgr, disabled)
+            });
+        }
     }
 }
 
 impl<M: Into<VoidMsg>, W: Widget<Msg = M> + 'static> event::SendEvent for Window<W> {
     fn send(&mut self, mgr: &mut Manager, id: WidgetId, event: Event) -> Response<Self::Msg> {
-        if !self.is_disabled() && id <= self.w.id() {
+        if self.is_disabled() {
+            return Response::Unhandled;
+        }
+
+        if id <= self.w.id() {
             return self.w.send(mgr, id, event).into();
+        } else if id <= self.context.id() {
+            return match self.context.send(mgr, id, event).try_into() {
+                Ok(r) => r,
+                Err(cmd) => self.context_command(mgr, cmd),
+            };
+        } else if id == self.id() {
+            match event {
+                Event::OpenContextMenu { target, coord } => {
+                    return self.open_context_menu(mgr, target, coord);
+                }
+                Event::OpenTooltip { target, coord } => {
+                    return self.open_tooltip(mgr, target, coord);
+                }
+                _ => (),
+            }
         }
         Response::Unhandled
     }
@@ -206,12 +269,19 @@ impl<M: Into<VoidMsg>, W: Widget<Msg = M> + 'static> kas::Window for Window<W> {
     }
 }
 
-// This is like WidgetChildren::find, but returns a translated Rect.
-fn find_rect(widget: &dyn WidgetConfig, id: WidgetId) -> Option<Rect> {
+// Call `f` on each widget from `widget` to `id`, inclusive, passing the sum
+// of translations applied by ancestors (relative to `widget`).
+// Returns false if `id` is not found.
+fn walk_path<F>(widget: &dyn WidgetConfig, id: WidgetId, offset: Offset, f: &mut F) -> bool
+where
+    F: FnMut(&dyn WidgetConfig, Offset),
+{
+    if id > widget.id() {
+        return false;
+    }
+    f(widget, offset);
     if id == widget.id() {
-        return Some(widget.rect());
-    } else if id > widget.id() {
-        return None;
+        return true;
     }
 
     for i in 0..widget.num_children() {
@@ -219,11 +289,124 @@ fn find_rect(widget: &dyn WidgetConfig, id: WidgetId) -> Option<Rect> {
             if id > w.id() {
                 continue;
             }
-            return find_rect(w, id).map(|rect| rect - widget.translation(i));
+            return walk_path(w, id, offset + widget.translation(i), f);
         }
         break;
     }
-    None
+    false
+}
+
+// This is like WidgetChildren::find, but returns a translated Rect.
+fn find_rect(widget: &dyn WidgetConfig, id: WidgetId) -> Option<Rect> {
+    let mut rect = None;
+    let found = walk_path(widget, id, Offset::ZERO, &mut |w, offset| {
+        rect = Some(w.rect() - offset);
+    });
+    rect.filter(|_| found)
+}
+
+// Find the ids of all widgets from `widget` to `id`, inclusive.
+// Returns an empty list if `id` is not found.
+fn find_path(widget: &dyn WidgetConfig, id: WidgetId) -> Vec<WidgetId> {
+    let mut path = vec![];
+    if !walk_path(widget, id, Offset::ZERO, &mut |w, _| path.push(w.id())) {
+        path.clear();
+    }
+    path
+}
+
+impl<M: Into<VoidMsg>, W: Widget<Msg = M> + 'static> Window<W> {
+    fn open_context_menu(
+        &mut self,
+        mgr: &mut Manager,
+        target: WidgetId,
+        coord: Option<Coord>,
+    ) -> Response<VoidMsg> {
+        // Call WidgetConfig::context_menu on each widget from the root to target
+        let path = find_path(self.w.as_widget(), target);
+        if path.is_empty() {
+            return Response::Unhandled;
+        }
+        let mut menu = ContextMenu::new(target);
+        for id in path {
+            if let Some(w) = self.w.find_leaf_mut(id) {
+                w.context_menu(mgr, &mut menu);
+            }
+        }
+        menu.tidy();
+        if menu.is_empty() {
+            return Response::Unhandled;
+        }
+
+        let anchor = match coord {
+            Some(coord) => Rect::new(coord, Size::ZERO),
+            None => find_rect(self.w.as_widget(), target).unwrap_or(self.core.rect),
+        };
+        self.context.open(mgr, &menu, anchor);
+        Response::None
+    }
+
+    fn open_tooltip(
+        &mut self,
+        mgr: &mut Manager,
+        target: WidgetId,
+        coord: Option<Coord>,
+    ) -> Response<VoidMsg> {
+        // Use the tooltip of the target or its nearest ancestor
+        let path = find_path(self.w.as_widget(), target);
+        let text = (path.iter().rev())
+            .filter_map(|id| self.w.find_leaf(*id))
+            .find_map(|w| w.tooltip());
+        let text = match text {
+            Some(text) => text,
+            None => return Response::Unhandled,
+        };
+
+        // Place below the mouse cursor (approximating its height) or target
+        let anchor = match coord {
+            Some(coord) => {
+                let height = mgr.size_handle(|sh| sh.line_height(TextClass::Label));
+                Rect::new(coord, Size::new(0, height))
+            }
+            None => find_rect(self.w.as_widget(), target).unwrap_or(self.core.rect),
+        };
+
+        self.tooltip.set(target, text);
+        let widget = &mut self.tooltip;
+        let mut cache = mgr.size_handle(|sh| layout::SolveCache::find_constraints(widget, sh));
+        let size = cache.ideal(false).min(self.core.rect.size);
+        let r = self.core.rect;
+        let mut pos = Coord(anchor.pos.0, anchor.pos.1 + anchor.size.1);
+        if pos.1 + size.1 > r.pos.1 + r.size.1 {
+            pos.1 = anchor.pos.1 - size.1;
+        }
+        pos = pos.clamp(r.pos, r.pos + (r.size - size));
+        cache.apply_rect(widget, mgr, Rect::new(pos, size), false);
+        Response::None
+    }
+
+    fn context_command(&mut self, mgr: &mut Manager, cmd: Command) -> Response<VoidMsg> {
+        let target = match self.context.target() {
+            Some(id) => id,
+            None => return Response::None,
+        };
+
+        // Try the target, then its ancestors
+        let path = find_path(self.w.as_widget(), target);
+        for id in path.into_iter().rev() {
+            match self.w.send(mgr, id, Event::Command(cmd, false)) {
+                Response::Unhandled => (),
+                r => return r.into(),
+            }
+        }
+
+        match cmd {
+            Command::Undo => mgr.undo(),
+            Command::Redo => mgr.redo(),
+            _ => (),
+        }
+        Response::None
+    }
 }
 
 impl<W: Widget> Window<W> {
@@ -231,10 +414,14 @@ impl<W: Widget> Window<W> {
         // Notation: p=point/coord, s=size, m=margin
         // r=window/root rect, c=anchor rect
         let r = self.core.rect;
-        let popup = &mut self.popups[index].1;
+        let popup = self.popups[index].1.clone();
 
-        let c = find_rect(self.w.as_widget(), popup.parent).unwrap();
-        let widget = self.w.find_leaf_mut(popup.id).unwrap();
+        let c = if popup.id == self.context.id() {
+            self.context.anchor()
+        } else {
+            find_rect(self.w.as_widget(), popup.parent).unwrap()
+        };
+        let widget = self.find_leaf_mut(popup.id).unwrap();
         let mut cache = mgr.size_handle(|sh| layout::SolveCache::find_constraints(widget, sh));
         let ideal = cache.ideal(false);
         let m = cache.margins();
@@ -277,3 +464,118 @@ impl<W: Widget> Window<W> {
         cache.apply_rect(widget, mgr, rect, false);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kas::class::HasStr;
+    use kas::event::{AppCommand, MouseButton, PressSource, VirtualKeyCode};
+    use kas::testing::Harness;
+    use kas::widget::EditBox;
+
+    const SHOUT: AppCommand = AppCommand::new("Shout");
+
+    #[derive(Debug, Widget)]
+    #[layout(single)]
+    #[widget(config=noauto)]
+    #[handler(handle=noauto, msg=u32)]
+    struct Parent {
+        #[widget_core]
+        core: CoreData,
+        #[widget]
+        edit: EditBox,
+    }
+
+    impl WidgetConfig for Parent {
+        fn context_menu(&mut self, _: &mut Manager, menu: &mut ContextMenu) {
+            menu.push("&Shout", SHOUT.into());
+        }
+    }
+
+    impl event::Handler for Parent {
+        type Msg = u32;
+
+        fn handle(&mut self, _: &mut Manager, event: Event) -> Response<u32> {
+            match event {
+                Event::Command(Command::App(SHOUT), _) => Response::Msg(1),
+                _ => Response::Unhandled,
+            }
+        }
+    }
+
+    #[test]
+    fn context_menu() {
+        let parent = Parent {
+            core: Default::default(),
+            edit: EditBox::new("abc"),
+        };
+        let mut harness = Harness::new(parent);
+        let rect = harness.widget().edit.rect();
+        let coord = rect.pos + rect.size / 2;
+        harness.click(coord);
+
+        let open_menu = |harness: &mut Harness<Parent>| {
+            harness.mouse_press(MouseButton::Right);
+            harness.mouse_release(MouseButton::Right);
+        };
+
+        // The parent's entry comes first
+        open_menu(&mut harness);
+        harness.key(VirtualKeyCode::Down);
+        harness.key(VirtualKeyCode::Return);
+        assert_eq!(harness.take_messages(), vec![1]);
+
+        // The edit box's entries follow; the last is "Select All"
+        open_menu(&mut harness);
+        harness.key(VirtualKeyCode::End);
+        harness.key(VirtualKeyCode::Return);
+        assert_eq!(harness.take_messages(), vec![]);
+        harness.type_str("x");
+        assert_eq!(harness.widget().edit.get_str(), "x");
+    }
+
+    // Handles right presses only
+    #[derive(Debug, Widget)]
+    #[handler(handle=noauto, msg=u32)]
+    struct RightPress {
+        #[widget_core]
+        core: CoreData,
+    }
+
+    impl Layout for RightPress {
+        fn size_rules(&mut self, _: &mut dyn SizeHandle, _: AxisInfo) -> SizeRules {
+            SizeRules::fixed(100, (0, 0))
+        }
+
+        fn draw(&self, _: &mut dyn DrawHandle, _: &ManagerState, _: bool) {}
+    }
+
+    impl event::Handler for RightPress {
+        type Msg = u32;
+
+        fn handle(&mut self, _: &mut Manager, event: Event) -> Response<u32> {
+            match event {
+                Event::PressStart {
+                    source: PressSource::Mouse(MouseButton::Right, _),
+                    ..
+                } => Response::Msg(2),
+                _ => Response::Unhandled,
+            }
+        }
+    }
+
+    #[test]
+    fn handled_right_press() {
+        let mut harness = Harness::new(RightPress {
+            core: Default::default(),
+        });
+        let rect = harness.widget().rect();
+        harness.mouse_move(rect.pos + rect.size / 2);
+
+        // The widget receives the press, and no menu is opened
+        harness.mouse_press(MouseButton::Right);
+        harness.mouse_release(MouseButton::Right);
+        assert_eq!(harness.take_messages(), vec![2]);
+        assert!(harness.shell().popups().is_empty());
+    }
+}